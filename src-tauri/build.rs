@@ -1,3 +1,82 @@
+// Every command gets allow-/deny- permissions generated, so each window's capability
+// grants only the commands it uses (see capabilities/ and permissions/)
+const COMMANDS: &[&str] = &[
+    "has_activation",
+    "check_database_integrity",
+    "get_mac_address",
+    "perform_new_activation",
+    "perform_reactivation",
+    "check_activation_expiry",
+    "perform_login",
+    "logout",
+    "has_active_content_path",
+    "add_and_set_active_content_path",
+    "get_content_paths",
+    "set_active_content_path",
+    "get_active_content_path",
+    "list_directories_in_path",
+    "list_directory_page",
+    "get_pinned_order",
+    "set_pinned_order",
+    "delete_content_path",
+    "parse_xml_quiz",
+    "index_active_content_path",
+    "get_content_items",
+    "get_content_item",
+    "get_content_hierarchy",
+    "get_content_manifest",
+    "verify_content_path",
+    "import_content_package",
+    "apply_content_update",
+    "get_content_rollback_point",
+    "rollback_content_update",
+    "scan_removable_media",
+    "get_thumbnail",
+    "get_media_info",
+    "get_pdf_page_count",
+    "get_pdf_page_text",
+    "open_content_viewer",
+    "get_viewer_session",
+    "record_viewer_position",
+    "commit_scorm_values",
+    "get_xapi_statements",
+    "export_xapi_statements",
+    "rebuild_active_search_index",
+    "search_content",
+    "record_video_progress",
+    "record_pdf_page_view",
+    "record_quiz_completion",
+    "get_content_progress",
+    "get_resume_item",
+    "get_progress_rollup",
+    "get_section_progress",
+    "regenerate_recovery_code",
+    "recover_admin_access",
+    "get_role_content_categories",
+    "set_role_content_categories",
+    "get_my_permissions",
+    "add_class",
+    "add_section",
+    "delete_section",
+    "get_sections",
+    "get_section_roster",
+    "assign_teacher_to_section",
+    "remove_teacher_from_section",
+    "get_teacher_sections",
+    "import_users_csv",
+    "export_users_csv",
+    "add_user",
+    "get_users",
+    "update_user",
+    "delete_user",
+    "get_deleted_users",
+    "restore_user",
+    "purge_user",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new().app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
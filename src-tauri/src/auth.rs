@@ -1,22 +1,14 @@
 // src-tauri/src/auth.rs
 
-use crate::db::get_db_path; // Import db module and necessary functions
+use crate::db::Database; // Import db module and necessary functions
+use crate::recovery::replace_recovery_code;
+use crate::roles::{end_session, start_session, Role, SessionUser};
 use chrono::{NaiveDateTime, Utc, DateTime}; // Add chrono to Cargo.toml for date handling
-use rusqlite::{Connection, Result};
+use rusqlite::Result;
 use serde::{Deserialize, Serialize}; // Assuming get_db_path is public in db.rs
 use std::collections::HashMap;
 use log::{info, error, warn}; // Added log imports
-
-#[derive(Debug, Serialize, Deserialize, Clone)] // Derive Clone for passing data
-pub struct ActivationDetails {
-    pub id: i64,
-    pub email: String,
-    pub organization_name: String,
-    pub serial_mac_id: String,
-    pub activation_code: String,
-    pub start_date: Option<String>, // Use String for dates from DB for simplicity, parse to NaiveDateTime when needed
-    pub end_date: Option<String>,
-}
+use tauri::State;
 
 // Updated to match frontend camelCase and required fields, will convert to snake_case
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,8 +33,16 @@ pub struct LoginCredentials {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginResponse {
+    pub id: i64,
     pub username: String,
-    pub role: String,
+    pub role: Role,
+}
+
+// Returned by perform_new_activation. The recovery code is shown to the admin once.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewActivationResult {
+    pub message: String,
+    pub recovery_code: String,
 }
 
 // Struct to match the successful API response structure for New Activation
@@ -91,7 +91,7 @@ pub async fn get_mac_address() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn perform_new_activation(form_data: NewActivationData) -> Result<String, String> {
+pub async fn perform_new_activation(database: State<'_, Database>, form_data: NewActivationData) -> Result<NewActivationResult, String> {
     info!("Attempting new activation.");
     // 1. Get MAC address
     let mac_id = get_mac_address().await?;
@@ -157,69 +157,70 @@ pub async fn perform_new_activation(form_data: NewActivationData) -> Result<Stri
 
     // 5. Save details to local DB
     info!("Saving new activation details to local DB.");
-    let db_path = get_db_path()?;
-    let mut db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for new activation: {}", e);
-        e.to_string()
-    })?;
-
-    let tx = db.transaction().map_err(|e| {
-        error!("Failed to start database transaction for new activation: {}", e);
-        e.to_string()
-    })?;
-
-    // Parse expiry date from API response and format for DB
-    let expiry_datetime = DateTime::parse_from_rfc3339(&api_response.product_key_details.expiry_date)
-        .map_err(|e| {
-            error!("Failed to parse API expiry date for new activation: {}", e);
-            format!("Failed to parse API expiry date: {}", e)
-        })?;
-    let end_date_str = expiry_datetime.with_timezone(&Utc).naive_utc().format("%Y-%m-%d %H:%M:%S").to_string();
-
-    let start_date_str = Utc::now().naive_utc().format("%Y-%m-%d %H:%M:%S").to_string(); // Example start date
-
-    // Insert into Activations table
-    tx.execute(
-        "INSERT INTO Activations (email, organization_name, serial_mac_id, activation_code, start_date, end_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        [&
-            api_response.credentials.username,
-            &institution_name_clone,
-            &mac_id,
-            &product_key_clone,
-            &start_date_str,
-            &end_date_str,
-        ],
-    ).map_err(|e| {
-        error!("Failed to insert activation into DB: {}", e);
-        e.to_string()
-    })?;
-
-    // Insert the user credentials from the API response
-    tx.execute(
-        "INSERT INTO Users (username, password, role) VALUES (?1, ?2, ?3)",
-        [&
-            api_response.credentials.username,
-            &api_response.credentials.password, // WARNING: Passwords should be hashed before storing!
-            "admin" // Assuming the API registered user is admin, adjust if roles are returned
-        ],
-    )
-    .map_err(|e| {
-        error!("Failed to insert user into DB: {}", e);
-        e.to_string()
-    })?;
-
-    tx.commit().map_err(|e| {
-        error!("Failed to commit database transaction for new activation: {}", e);
-        e.to_string()
-    })?;
-
-    info!("New activation successful.");
-    // 6. Return success message (can be from API response)
-    Ok(api_response.message)
+    database
+        .run(move |db| {
+            let tx = db.transaction().map_err(|e| {
+                error!("Failed to start database transaction for new activation: {}", e);
+                e.to_string()
+            })?;
+
+            // Parse expiry date from API response and format for DB
+            let expiry_datetime = DateTime::parse_from_rfc3339(&api_response.product_key_details.expiry_date)
+                .map_err(|e| {
+                    error!("Failed to parse API expiry date for new activation: {}", e);
+                    format!("Failed to parse API expiry date: {}", e)
+                })?;
+            let end_date_str = expiry_datetime.with_timezone(&Utc).naive_utc().format("%Y-%m-%d %H:%M:%S").to_string();
+
+            let start_date_str = Utc::now().naive_utc().format("%Y-%m-%d %H:%M:%S").to_string(); // Example start date
+
+            // Insert into Activations table
+            tx.execute(
+                "INSERT INTO Activations (email, organization_name, serial_mac_id, activation_code, start_date, end_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                [&
+                    api_response.credentials.username,
+                    &institution_name_clone,
+                    &mac_id,
+                    &product_key_clone,
+                    &start_date_str,
+                    &end_date_str,
+                ],
+            ).map_err(|e| {
+                error!("Failed to insert activation into DB: {}", e);
+                e.to_string()
+            })?;
+
+            // Insert the user credentials from the API response
+            tx.execute(
+                "INSERT INTO Users (username, password, role) VALUES (?1, ?2, ?3)",
+                [&
+                    api_response.credentials.username,
+                    &api_response.credentials.password, // WARNING: Passwords should be hashed before storing!
+                    Role::Admin.as_str() // The user registered through the API administers this install
+                ],
+            )
+            .map_err(|e| {
+                error!("Failed to insert user into DB: {}", e);
+                e.to_string()
+            })?;
+
+            // Offline way back in if the admin password is lost
+            let recovery_code = replace_recovery_code(&tx)?;
+
+            tx.commit().map_err(|e| {
+                error!("Failed to commit database transaction for new activation: {}", e);
+                e.to_string()
+            })?;
+
+            info!("New activation successful.");
+            // 6. Return success message (can be from API response) and the recovery code
+            Ok(NewActivationResult { message: api_response.message, recovery_code })
+        })
+        .await
 }
 
 #[tauri::command]
-pub async fn perform_reactivation(activation_key: String) -> Result<String, String> {
+pub async fn perform_reactivation(database: State<'_, Database>, activation_key: String) -> Result<String, String> {
     info!("Attempting reactivation for key: {}", activation_key);
     // 1. Get MAC address
     let mac_id = get_mac_address().await?;
@@ -267,111 +268,114 @@ pub async fn perform_reactivation(activation_key: String) -> Result<String, Stri
 
     // 4. Update local DB with new dates from API response
     info!("Updating local DB with reactivation details.");
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for reactivation: {}", e);
-        e.to_string()
-    })?;
-
-    // Parse expiry date from API response and format for DB
-    let expiry_datetime = DateTime::parse_from_rfc3339(&api_response.expiry_date)
-        .map_err(|e| {
-            error!("Failed to parse API expiry date for reactivation: {}", e);
-            format!("Failed to parse API expiry date: {}", e)
-        })?;
-    let end_date_str = expiry_datetime.with_timezone(&Utc).naive_utc().format("%Y-%m-%d %H:%M:%S").to_string();
-
-    let start_date_str = Utc::now().naive_utc().format("%Y-%m-%d %H:%M:%S").to_string(); // Assuming new start date is now
-
-    let updated_rows = db.execute(
-        "UPDATE Activations SET start_date = ?1, end_date = ?2 WHERE activation_code = ?3 AND serial_mac_id = ?4",
-        [&start_date_str, &end_date_str, &activation_key, &mac_id],
-    ).map_err(|e| {
-        error!("Failed to update activation in DB: {}", e);
-        e.to_string()
-    })?;
-
-    if updated_rows == 0 {
-        // This case might need more specific handling depending on your app logic
-        // If the API succeeded but no matching local activation was found, maybe insert instead of update?
-        warn!("Reactivation API succeeded, but no matching local activation found for key {}", activation_key);
-        // For now, we return an error, but you might want to change this.
-        return Err("Reactivation succeeded on backend, but failed to update local record.".to_string());
-    }
-
-    info!("Reactivation successful.");
-    // 5. Return success message from API
-    Ok(api_response.status)
+    database
+        .run(move |db| {
+            // Parse expiry date from API response and format for DB
+            let expiry_datetime = DateTime::parse_from_rfc3339(&api_response.expiry_date)
+                .map_err(|e| {
+                    error!("Failed to parse API expiry date for reactivation: {}", e);
+                    format!("Failed to parse API expiry date: {}", e)
+                })?;
+            let end_date_str = expiry_datetime.with_timezone(&Utc).naive_utc().format("%Y-%m-%d %H:%M:%S").to_string();
+
+            let start_date_str = Utc::now().naive_utc().format("%Y-%m-%d %H:%M:%S").to_string(); // Assuming new start date is now
+
+            let updated_rows = db.execute(
+                "UPDATE Activations SET start_date = ?1, end_date = ?2 WHERE activation_code = ?3 AND serial_mac_id = ?4",
+                [&start_date_str, &end_date_str, &activation_key, &mac_id],
+            ).map_err(|e| {
+                error!("Failed to update activation in DB: {}", e);
+                e.to_string()
+            })?;
+
+            if updated_rows == 0 {
+                // This case might need more specific handling depending on your app logic
+                // If the API succeeded but no matching local activation was found, maybe insert instead of update?
+                warn!("Reactivation API succeeded, but no matching local activation found for key {}", activation_key);
+                // For now, we return an error, but you might want to change this.
+                return Err("Reactivation succeeded on backend, but failed to update local record.".to_string());
+            }
+
+            info!("Reactivation successful.");
+            // 5. Return success message from API
+            Ok(api_response.status)
+        })
+        .await
 }
 
 #[tauri::command]
-pub fn check_activation_expiry() -> Result<bool, String> {
+pub async fn check_activation_expiry(database: State<'_, Database>) -> Result<bool, String> {
     info!("Checking activation expiry.");
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for expiry check: {}", e);
-        e.to_string()
-    })?;
-
-    let mut stmt = db
-        .prepare("SELECT end_date FROM Activations ORDER BY id DESC LIMIT 1")
-        .map_err(|e| {
-            error!("Failed to prepare statement for expiry check: {}", e);
-            e.to_string()
-        })?;
-
-    let end_date_str_result: Result<String, _> = stmt.query_row([], |row| row.get(0));
-
-    match end_date_str_result {
-        Ok(end_date_str) => {
-            info!("Retrieved end_date_str from DB: {}", end_date_str);
-            // Attempt to parse the date string using the format observed in the print output
-            let end_date = NaiveDateTime::parse_from_str(&end_date_str, "%Y-%m-%d %H:%M:%S")
+    database
+        .run(|db| {
+            let mut stmt = db
+                .prepare_cached("SELECT end_date FROM Activations ORDER BY id DESC LIMIT 1")
                 .map_err(|e| {
-                    error!("Failed to parse end_date_str: {}", e);
-                    format!("Failed to parse end_date_str: {}", e)
+                    error!("Failed to prepare statement for expiry check: {}", e);
+                    e.to_string()
                 })?;
-            let now = Utc::now().naive_utc();
-            Ok(now > end_date) // True if expired
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => {
-            info!("No activation found for expiry check. Treating as expired.");
-            Ok(true) // No activation found, treat as expired or requiring activation
-        }
-        Err(e) => {
-            error!("Error querying activation expiry: {}", e);
-            Err(e.to_string())
-        }
-    }
+
+            let end_date_str_result: Result<String, _> = stmt.query_row([], |row| row.get(0));
+
+            match end_date_str_result {
+                Ok(end_date_str) => {
+                    info!("Retrieved end_date_str from DB: {}", end_date_str);
+                    // Attempt to parse the date string using the format observed in the print output
+                    let end_date = NaiveDateTime::parse_from_str(&end_date_str, "%Y-%m-%d %H:%M:%S")
+                        .map_err(|e| {
+                            error!("Failed to parse end_date_str: {}", e);
+                            format!("Failed to parse end_date_str: {}", e)
+                        })?;
+                    let now = Utc::now().naive_utc();
+                    Ok(now > end_date) // True if expired
+                }
+                Err(rusqlite::Error::QueryReturnedNoRows) => {
+                    info!("No activation found for expiry check. Treating as expired.");
+                    Ok(true) // No activation found, treat as expired or requiring activation
+                }
+                Err(e) => {
+                    error!("Error querying activation expiry: {}", e);
+                    Err(e.to_string())
+                }
+            }
+        })
+        .await
 }
 
 #[tauri::command]
-pub fn perform_login(credentials: LoginCredentials) -> Result<LoginResponse, String> {
+pub async fn perform_login(database: State<'_, Database>, credentials: LoginCredentials) -> Result<LoginResponse, String> {
     info!("Attempting login for user: {}", credentials.email);
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for login: {}", e);
-        e.to_string()
-    })?;
+    // A new sign-in replaces whoever was signed in, even if it fails
+    end_session();
+    database
+        .run(move |db| {
+            let mut stmt = db
+                .prepare_cached("SELECT id, password, role FROM Users WHERE username = ?1 AND deleted_at IS NULL")
+                .map_err(|e| {
+                    error!("Failed to prepare statement for login: {}", e);
+                    e.to_string()
+                })?;
+            let (id, stored_password, role): (i64, String, Role) = stmt.query_row([&credentials.email], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| {
+                    error!("Failed to retrieve password and role for user {}: {}", credentials.email, e);
+                    "Invalid username or password".to_string()
+                })?;
 
-    let mut stmt = db
-        .prepare("SELECT password, role FROM Users WHERE username = ?1")
-        .map_err(|e| {
-            error!("Failed to prepare statement for login: {}", e);
-            e.to_string()
-        })?;
-    let (stored_password, role): (String, String) = stmt.query_row([&credentials.email], |row| Ok((row.get(0)?, row.get(1)?)))
-        .map_err(|e| {
-            error!("Failed to retrieve password and role for user {}: {}", credentials.email, e);
-            "Invalid username or password".to_string()
-        })?;
+            // WARNING: In a real application, compare hashed passwords!
+            if stored_password == credentials.password {
+                info!("Login successful for user: {} with role: {}", credentials.email, role.as_str());
+                start_session(SessionUser { id, username: credentials.email.clone(), role });
+                Ok(LoginResponse { id, username: credentials.email, role })
+            } else {
+                warn!("Login failed for user {}: Incorrect password.", credentials.email);
+                Err("Invalid username or password".to_string())
+            }
+        })
+        .await
+}
 
-    // WARNING: In a real application, compare hashed passwords!
-    if stored_password == credentials.password {
-        info!("Login successful for user: {} with role: {}", credentials.email, role);
-        Ok(LoginResponse { username: credentials.email, role })
-    } else {
-        warn!("Login failed for user {}: Incorrect password.", credentials.email);
-        Err("Invalid username or password".to_string())
-    }
+#[tauri::command]
+pub fn logout() -> Result<(), String> {
+    end_session();
+    Ok(())
 }
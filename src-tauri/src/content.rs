@@ -1,16 +1,37 @@
-use crate::db::get_db_path; 
-use rusqlite::{Connection, Result, params};
+use crate::db::Database;
+use rusqlite::{Connection, OptionalExtension, Result, params};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use open;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 use log::{info, error, warn};
+use tauri::State;
+use xml::reader::{EventReader, XmlEvent};
+use crate::encryption;
+use crate::i18n::{xml_lang, LocalizedText};
+use crate::indexer::{classify_file, is_package_dir, ContentType};
+use crate::integrity;
+use crate::manifest;
+use crate::media;
+use crate::roles::{require_permission, visible_content_types, Permission};
+use crate::watcher;
+
+// Optional per-folder file with localized titles for the entries in that folder
+const TITLES_FILE: &str = "titles.xml";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContentPath {
     pub id: i64,
     pub path: String,
     pub is_active: bool,
+    // Package details from the content manifest, when the path has one
+    pub title: Option<String>,
+    pub board: Option<String>,
+    pub language: Option<String>,
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,209 +39,430 @@ pub struct FileEntry {
     pub name: String,
     #[serde(rename = "isDirectory")] // Use camelCase for frontend
     pub is_directory: bool,
+    pub title: Option<String>, // Localized display title from the manifest or titles.xml, if any
+    pub order: Option<i64>, // Display position declared in the manifest
+    pub size: Option<u64>, // Files only
+    #[serde(rename = "modifiedAt")]
+    pub modified_at: Option<i64>, // Seconds since the Unix epoch
+    #[serde(rename = "contentType")]
+    pub content_type: Option<ContentType>, // Files recognised as content, and package folders
+    #[serde(rename = "childCount")]
+    pub child_count: Option<u64>, // Folders only
+    pub encrypted: bool,
 }
 
 // Function to check if there is at least one active content path
 #[tauri::command]
-pub fn has_active_content_path() -> Result<bool, String> {
+pub async fn has_active_content_path(database: State<'_, Database>) -> Result<bool, String> {
     info!("Checking for active content path.");
-    let db_path = get_db_path()?;
-    if !db_path.exists() {
-        info!("Database path does not exist. No active path found.");
-        return Ok(false); // No DB, no active path
-    }
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for active content path check: {}", e);
-        e.to_string()
-    })?;
-
-    let mut stmt = db.prepare("SELECT COUNT(*) FROM ContentPaths WHERE is_active = 1")
-        .map_err(|e| {
-            error!("Failed to prepare statement for active content path count: {}", e);
-            e.to_string()
-        })?;
-    let count: i64 = stmt.query_row([], |row| row.get(0))
-        .map_err(|e| {
-            error!("Failed to query for active content path count: {}", e);
-            e.to_string()
-        })?;
-
-    info!("Active content paths count: {}", count);
-    Ok(count > 0)
+    database
+        .run(|db| {
+            let mut stmt = db.prepare_cached("SELECT COUNT(*) FROM ContentPaths WHERE is_active = 1")
+                .map_err(|e| {
+                    error!("Failed to prepare statement for active content path count: {}", e);
+                    e.to_string()
+                })?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))
+                .map_err(|e| {
+                    error!("Failed to query for active content path count: {}", e);
+                    e.to_string()
+                })?;
+
+            info!("Active content paths count: {}", count);
+            Ok(count > 0)
+        })
+        .await
 }
 
-// Function to add a new content path and set it as active, deactivating others
+// Function to add a new content path and set it as active, deactivating others.
+// With `verify`, the path's checksum list is quick-checked first and problems block activation.
 #[tauri::command]
-pub fn add_and_set_active_content_path(path: String) -> Result<(), String> {
+pub async fn add_and_set_active_content_path(database: State<'_, Database>, path: String, verify: Option<bool>) -> Result<(), String> {
+    require_permission(Permission::ManageContent)?;
     info!("Adding and setting active content path: {}", path);
-    let db_path = get_db_path()?;
-    let mut db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for adding content path: {}", e);
-        e.to_string()
-    })?;
+    // The path is read and checked before a connection is taken
+    database
+        .run_blocking(move |database| {
+            let manifest = manifest::read_and_validate(Path::new(&path))?;
+            if verify.unwrap_or(false) {
+                integrity::quick_verify(Path::new(&path))?;
+            }
+            register_content_path(&mut *database.connection()?, &path, manifest.as_ref(), true)
+        })
+        .await
+}
+
+// Records a content path with its manifest details. When `activate` is set it becomes
+// the only active path; otherwise an existing row keeps its active flag.
+pub fn register_content_path(db: &mut Connection, path: &str, manifest: Option<&manifest::Manifest>, activate: bool) -> Result<(), String> {
+    // Lets the library be found again if its drive comes back at another mount point
+    let identity = media::identify_content_root(Path::new(path));
 
     let tx = db.transaction().map_err(|e| {
         error!("Failed to start database transaction for adding content path: {}", e);
         e.to_string()
     })?;
 
-    // Deactivate all existing paths
-    tx.execute("UPDATE ContentPaths SET is_active = 0", [])
-        .map_err(|e| {
-            error!("Failed to deactivate existing content paths: {}", e);
-            e.to_string()
-        })?;
-    info!("Deactivated existing content paths.");
+    if activate {
+        // Deactivate all existing paths
+        tx.execute("UPDATE ContentPaths SET is_active = 0", [])
+            .map_err(|e| {
+                error!("Failed to deactivate existing content paths: {}", e);
+                e.to_string()
+            })?;
+        info!("Deactivated existing content paths.");
+    }
 
-    // Insert the new path or update if it already exists
+    // Insert the new path or update it if it already exists. Updating in place keeps
+    // the id stable, so ContentItems indexed for this path remain attached to it.
     tx.execute(
-        "INSERT OR REPLACE INTO ContentPaths (path, is_active) VALUES (?1, 1)",
-        params![path],
+        "INSERT INTO ContentPaths (path, is_active, title, board, language, version, volume_uuid, volume_path, library_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(path) DO UPDATE SET
+            is_active = MAX(is_active, excluded.is_active),
+            title = excluded.title,
+            board = excluded.board,
+            language = excluded.language,
+            version = excluded.version,
+            volume_uuid = excluded.volume_uuid,
+            volume_path = excluded.volume_path,
+            library_id = excluded.library_id,
+            updated_at = CURRENT_TIMESTAMP",
+        params![
+            path,
+            activate,
+            manifest.map(|m| m.title.clone()),
+            manifest.and_then(|m| m.board.clone()),
+            manifest.and_then(|m| m.language.clone()),
+            manifest.map(|m| m.version.clone()),
+            identity.volume_uuid,
+            identity.volume_path,
+            identity.library_id,
+        ],
     ).map_err(|e| {
-        error!("Failed to insert or replace content path: {}", e);
+        error!("Failed to insert or update content path: {}", e);
         e.to_string()
     })?;
-    info!("Inserted or replaced new content path.");
+    info!("Inserted or updated content path {}.", path);
 
     tx.commit().map_err(|e| {
         error!("Failed to commit database transaction for adding content path: {}", e);
         e.to_string()
     })?;
-    info!("Successfully added and set active content path.");
+    info!("Successfully registered content path {} (active: {}).", path, activate);
+
+    watcher::notify_active_path_changed();
 
     Ok(())
 }
 
 // Function to get all content paths
 #[tauri::command]
-pub fn get_content_paths() -> Result<Vec<ContentPath>, String> {
+pub async fn get_content_paths(database: State<'_, Database>) -> Result<Vec<ContentPath>, String> {
     info!("Retrieving all content paths.");
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for getting content paths: {}", e);
-        e.to_string()
-    })?;
-
-    let mut stmt = db.prepare("SELECT id, path, is_active FROM ContentPaths")
-        .map_err(|e| {
-            error!("Failed to prepare statement for getting content paths: {}", e);
-            e.to_string()
-        })?;
+    database
+        .run(|db| {
+            let mut stmt = db.prepare_cached("SELECT id, path, is_active, title, board, language, version FROM ContentPaths")
+                .map_err(|e| {
+                    error!("Failed to prepare statement for getting content paths: {}", e);
+                    e.to_string()
+                })?;
     
-    let content_paths = stmt.query_map([], |row| {
-        Ok(ContentPath {
-            id: row.get(0)?,
-            path: row.get(1)?,
-            is_active: row.get(2)?,
+            let content_paths = stmt.query_map([], |row| {
+                Ok(ContentPath {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    is_active: row.get(2)?,
+                    title: row.get(3)?,
+                    board: row.get(4)?,
+                    language: row.get(5)?,
+                    version: row.get(6)?,
+                })
+            })
+            .map_err(|e| {
+                error!("Failed to query map for content paths: {}", e);
+                e.to_string()
+            })?
+            .collect::<Result<Vec<ContentPath>, _>>()
+            .map_err(|e| {
+                error!("Failed to collect content paths: {}", e);
+                e.to_string()
+            })?;
+
+            info!("Successfully retrieved {} content paths.", content_paths.len());
+            Ok(content_paths)
         })
-    })
-    .map_err(|e| {
-        error!("Failed to query map for content paths: {}", e);
-        e.to_string()
-    })?
-    .collect::<Result<Vec<ContentPath>, _>>()
-    .map_err(|e| {
-        error!("Failed to collect content paths: {}", e);
-        e.to_string()
-    })?;
-
-    info!("Successfully retrieved {} content paths.", content_paths.len());
-    Ok(content_paths)
+        .await
 }
 
-// Function to set a content path as active, deactivating others
+// Function to set a content path as active, deactivating others.
+// With `verify`, the path is quick-checked against its checksum list first.
 #[tauri::command]
-pub fn set_active_content_path(id: i32) -> Result<(), String> {
+pub async fn set_active_content_path(database: State<'_, Database>, id: i32, verify: Option<bool>) -> Result<(), String> {
+    require_permission(Permission::ManageContent)?;
     info!("Setting active content path to ID: {}", id);
-    let db_path = get_db_path()?;
-    let mut db = rusqlite::Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for setting active content path: {}", e);
-        e.to_string()
-    })?;
-
-    let tx = db.transaction().map_err(|e| {
-        error!("Failed to start database transaction for setting active content path: {}", e);
-        e.to_string()
-    })?;
+    database
+        .run_blocking(move |database| {
+            if verify.unwrap_or(false) {
+                let path: String = database
+                    .connection()?
+                    .query_row("SELECT path FROM ContentPaths WHERE id = ?1", params![id], |row| row.get(0))
+                    .map_err(|e| {
+                        warn!("Content path with id {} not found for verification: {}", id, e);
+                        format!("Content path with id {} not found.", id)
+                    })?;
+                integrity::quick_verify(Path::new(&path))?;
+            }
 
-    // Set all other paths to inactive
-    tx.execute(
-        "UPDATE ContentPaths SET is_active = 0 WHERE id != ?1",
-        params![id],
-    ).map_err(|e| {
-        error!("Failed to deactivate other content paths: {}", e);
-        e.to_string()
-    })?;
-    info!("Deactivated other content paths.");
+            let mut db = database.connection()?;
+            let tx = db.transaction().map_err(|e| {
+                error!("Failed to start database transaction for setting active content path: {}", e);
+                e.to_string()
+            })?;
+
+            // Set all other paths to inactive
+            tx.execute(
+                "UPDATE ContentPaths SET is_active = 0 WHERE id != ?1",
+                params![id],
+            ).map_err(|e| {
+                error!("Failed to deactivate other content paths: {}", e);
+                e.to_string()
+            })?;
+            info!("Deactivated other content paths.");
+
+            // Set the specified path to active
+            let updated_rows = tx.execute(
+                "UPDATE ContentPaths SET is_active = 1 WHERE id = ?1",
+                params![id],
+            ).map_err(|e| {
+                error!("Failed to set specified content path as active: {}", e);
+                e.to_string()
+            })?;
+
+            if updated_rows == 0 {
+                tx.rollback().map_err(|e| e.to_string())?;
+                warn!("Content path with id {} not found when setting as active.", id);
+                return Err(format!("Content path with id {} not found.", id));
+            }
 
-    // Set the specified path to active
-    let updated_rows = tx.execute(
-        "UPDATE ContentPaths SET is_active = 1 WHERE id = ?1",
-        params![id],
-    ).map_err(|e| {
-        error!("Failed to set specified content path as active: {}", e);
-        e.to_string()
-    })?;
+            tx.commit().map_err(|e| {
+                error!("Failed to commit database transaction for setting active content path: {}", e);
+                e.to_string()
+            })?;
+            info!("Successfully set active content path to ID: {}", id);
 
-    if updated_rows == 0 {
-        tx.rollback().map_err(|e| e.to_string())?;
-        warn!("Content path with id {} not found when setting as active.", id);
-        return Err(format!("Content path with id {} not found.", id));
-    }
+            watcher::notify_active_path_changed();
 
-    tx.commit().map_err(|e| {
-        error!("Failed to commit database transaction for setting active content path: {}", e);
-        e.to_string()
-    })?;
-    info!("Successfully set active content path to ID: {}", id);
-
-    Ok(())
+            Ok(())
+        })
+        .await
 }
 
 #[tauri::command]
-pub fn get_active_content_path() -> Result<Option<String>, String> {
+pub async fn get_active_content_path(database: State<'_, Database>) -> Result<Option<String>, String> {
     info!("Retrieving active content path.");
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for getting active content path: {}", e);
-        e.to_string()
-    })?;
+    database
+        .run(|db| {
+            let mut stmt = db.prepare_cached("SELECT path FROM ContentPaths WHERE is_active = 1 LIMIT 1").map_err(|e| {
+                error!("Failed to prepare statement for active content path: {}", e);
+                e.to_string()
+            })?;
+            let mut rows = stmt.query([]).map_err(|e| {
+                error!("Failed to query for active content path: {}", e);
+                e.to_string()
+            })?;
+
+            if let Some(row) = rows.next().map_err(|e| {
+                error!("Failed to read row for active content path: {}", e);
+                e.to_string()
+            })? {
+                let path: String = row.get(0).map_err(|e| {
+                    error!("Failed to get path from row for active content path: {}", e);
+                    e.to_string()
+                })?;
+                info!("Active content path found: {}", path);
+                Ok(Some(path))
+            } else {
+                info!("No active content path found.");
+                Ok(None)
+            }
+        })
+        .await
+}
 
-    let mut stmt = db.prepare("SELECT path FROM ContentPaths WHERE is_active = 1 LIMIT 1").map_err(|e| {
-        error!("Failed to prepare statement for active content path: {}", e);
+// Returns the id and root directory of the active content path, if one is set
+pub fn get_active_content_root(db: &Connection) -> Result<Option<(i64, String)>, String> {
+    let mut stmt = db.prepare_cached("SELECT id, path FROM ContentPaths WHERE is_active = 1 LIMIT 1").map_err(|e| {
+        error!("Failed to prepare statement for active content root: {}", e);
         e.to_string()
     })?;
     let mut rows = stmt.query([]).map_err(|e| {
-        error!("Failed to query for active content path: {}", e);
+        error!("Failed to query for active content root: {}", e);
         e.to_string()
     })?;
 
-    if let Some(row) = rows.next().map_err(|e| {
-        error!("Failed to read row for active content path: {}", e);
+    match rows.next().map_err(|e| {
+        error!("Failed to read row for active content root: {}", e);
         e.to_string()
     })? {
-        let path: String = row.get(0).map_err(|e| {
-            error!("Failed to get path from row for active content path: {}", e);
+        Some(row) => {
+            let id: i64 = row.get(0).map_err(|e| e.to_string())?;
+            let path: String = row.get(1).map_err(|e| e.to_string())?;
+            Ok(Some((id, path)))
+        }
+        None => Ok(None),
+    }
+}
+
+// Canonical forms of the registered content roots that are currently reachable
+fn canonical_content_roots(db: &Connection) -> Result<Vec<PathBuf>, String> {
+    let mut stmt = db.prepare_cached("SELECT path FROM ContentPaths").map_err(|e| e.to_string())?;
+    let roots = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|path| path.ok())
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect();
+    Ok(roots)
+}
+
+fn canonicalize_requested(path: &str) -> Result<PathBuf, String> {
+    fs::canonicalize(path).map_err(|e| {
+        warn!("Rejected path {}: {}", path, e);
+        format!("Path not found: {}", path)
+    })
+}
+
+// Resolves `path`, following symlinks, and checks it lies inside a registered content root.
+// Commands taking paths from the webview call this before touching the filesystem.
+pub fn resolve_in_content_roots(db: &Connection, path: &str) -> Result<PathBuf, String> {
+    let canonical = canonicalize_requested(path)?;
+    if canonical_content_roots(db)?.iter().any(|root| canonical.starts_with(root)) {
+        Ok(canonical)
+    } else {
+        warn!("Rejected path outside registered content paths: {} ({})", path, canonical.display());
+        Err(format!("Access denied: {} is outside the registered content paths.", path))
+    }
+}
+
+// Sort orders for directory listings. Pinned names and manifest order always come first.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Natural, // "class2" before "class10"
+    Name,
+    Modified,
+    Size,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ListOptions {
+    #[serde(default)]
+    pub sort: SortKey,
+    #[serde(default)]
+    pub descending: bool,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DirectoryPage {
+    pub entries: Vec<FileEntry>,
+    pub total: usize, // Entries in the folder before pagination
+}
+
+// Folder names listed first, in this order, unless an admin configures otherwise
+const DEFAULT_PINNED_ORDER: &[&str] = &["prenursery", "nursery", "lkg", "ukg"];
+const PINNED_ORDER_SETTING: &str = "pinned_order";
+
+// Lowercase with spaces, '-' and '_' removed, so "Pre Nursery" matches "pre-nursery"
+fn pin_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn read_pinned_order(db: &Connection) -> Result<Vec<String>, String> {
+    let stored: Option<String> = db
+        .query_row("SELECT value FROM AppSettings WHERE key = ?1", params![PINNED_ORDER_SETTING], |row| row.get(0))
+        .optional()
+        .map_err(|e| {
+            error!("Failed to read pinned order setting: {}", e);
             e.to_string()
         })?;
-        info!("Active content path found: {}", path);
-        Ok(Some(path))
-    } else {
-        info!("No active content path found.");
-        Ok(None)
+    match stored {
+        Some(json) => serde_json::from_str(&json).map_err(|e| {
+            error!("Invalid pinned order setting: {}", e);
+            e.to_string()
+        }),
+        None => Ok(DEFAULT_PINNED_ORDER.iter().map(|s| s.to_string()).collect()),
     }
 }
 
-#[tauri::command]
-pub fn list_directories_in_path(path: String) -> Result<Vec<FileEntry>, String> {
-    info!("Listing directories in path: {}", path);
-    let path = Path::new(&path);
+// Compares names chunk by chunk, numbers by value, ignoring case and whitespace
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    fn chunks(s: &str) -> Vec<(bool, String)> {
+        let mut chunks: Vec<(bool, String)> = Vec::new();
+        for c in s.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase) {
+            let digit = c.is_ascii_digit();
+            match chunks.last_mut() {
+                Some((is_digit, chunk)) if *is_digit == digit => chunk.push(c),
+                _ => chunks.push((digit, c.to_string())),
+            }
+        }
+        chunks
+    }
+    let (a_chunks, b_chunks) = (chunks(a), chunks(b));
+    for ((a_digit, a_chunk), (b_digit, b_chunk)) in a_chunks.iter().zip(&b_chunks) {
+        let ordering = if *a_digit && *b_digit {
+            let (a_num, b_num) = (a_chunk.trim_start_matches('0'), b_chunk.trim_start_matches('0'));
+            a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num))
+        } else {
+            a_chunk.cmp(b_chunk)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a_chunks.len().cmp(&b_chunks.len())
+}
+
+// Number of visible entries in a folder
+fn child_count(dir: &Path) -> Option<u64> {
+    let entries = fs::read_dir(dir).ok()?;
+    Some(
+        entries
+            .filter_map(|e| e.ok())
+            .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+            .count() as u64,
+    )
+}
+
+fn list_entries(db: &Connection, path: &str, language: Option<&str>, options: &ListOptions) -> Result<DirectoryPage, String> {
+    // The resolved path is listed, so a symlink swapped in after the check cannot redirect it
+    let canonical = resolve_in_content_roots(db, path)?;
+    let path = canonical.as_path();
 
     if !path.is_dir() {
         warn!("Path is not a directory: {}", path.display());
         return Err(format!("Path is not a directory: {}", path.display()));
     }
 
+    let pinned: HashMap<String, usize> = read_pinned_order(db)?
+        .iter()
+        .enumerate()
+        .map(|(position, name)| (pin_key(name), position))
+        .collect();
+
+    let titles = read_localized_titles(path, language);
+    // Manifest titles and order apply when listing inside the active content path
+    let active_root = get_active_content_root(db)?.map(|(_, root)| PathBuf::from(root));
+    let manifest_scope = active_root.as_ref().and_then(|root| {
+        let relative_dir = path.strip_prefix(fs::canonicalize(root).ok()?).ok()?.to_string_lossy().replace('\\', "/");
+        Some((manifest::load_cached(root)?, relative_dir))
+    });
     let mut entries = Vec::new();
     for entry in fs::read_dir(path).map_err(|e| {
         error!("Failed to read directory {}: {}", path.display(), e);
@@ -233,79 +475,266 @@ pub fn list_directories_in_path(path: String) -> Result<Vec<FileEntry>, String>
         let path = entry.path();
         if let Some(name) = path.file_name() {
             if let Some(name_str) = name.to_str() {
+                if name_str.starts_with('.')
+                    || name_str == TITLES_FILE
+                    || name_str == manifest::MANIFEST_JSON
+                    || name_str == manifest::MANIFEST_TOML
+                {
+                    continue;
+                }
+                let manifest_entry = manifest_scope.as_ref().map(|(loaded, relative_dir)| {
+                    let relative = if relative_dir.is_empty() {
+                        name_str.to_string()
+                    } else {
+                        format!("{}/{}", relative_dir, name_str)
+                    };
+                    (
+                        loaded.title_for(&relative, language),
+                        loaded.entry(&relative).and_then(|meta| meta.order),
+                        loaded.entry(&relative).and_then(|meta| meta.content_type),
+                    )
+                });
+                let (manifest_title, order, manifest_type) = manifest_entry.unwrap_or((None, None, None));
+                let is_directory = path.is_dir();
+                let metadata = entry.metadata().ok();
                 entries.push(FileEntry {
                     name: name_str.to_string(),
-                    is_directory: path.is_dir(),
+                    is_directory,
+                    title: manifest_title.or_else(|| titles.get(name_str).cloned()),
+                    order,
+                    size: metadata.as_ref().filter(|_| !is_directory).map(|m| m.len()),
+                    modified_at: metadata
+                        .as_ref()
+                        .and_then(|m| m.modified().ok())
+                        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                        .map(|d| d.as_secs() as i64),
+                    content_type: if !is_directory {
+                        manifest_type.or_else(|| classify_file(name_str))
+                    } else if is_package_dir(&path) {
+                        Some(ContentType::Interactive)
+                    } else {
+                        None
+                    },
+                    child_count: None,
+                    encrypted: false,
                 });
             }
         }
     }
-    // Optional: Sort entries alphabetically
-    entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    info!("Successfully listed {} entries in path: {}", entries.len(), path.display());
-    Ok(entries)
+
+    // Content categories hidden from the user's role are not listed
+    let visible = visible_content_types(db)?;
+    entries.retain(|entry| entry.content_type.is_none_or(|t| visible.contains(&t)));
+
+    // Manifest order first, then pinned names, then the requested order
+    entries.sort_by(|a, b| {
+        let pin = |entry: &FileEntry| pinned.get(&pin_key(&entry.name)).copied();
+        let requested = match options.sort {
+            SortKey::Natural => natural_cmp(&a.name, &b.name),
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Modified => a.modified_at.cmp(&b.modified_at),
+            SortKey::Size => a.size.cmp(&b.size),
+        };
+        let requested = if options.descending { requested.reverse() } else { requested };
+        match (a.order, b.order) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => match (pin(a), pin(b)) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+        .then(requested)
+        .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let total = entries.len();
+    let offset = options.offset.unwrap_or(0).min(total);
+    let limit = options.limit.unwrap_or(total);
+    let mut entries: Vec<FileEntry> = entries.into_iter().skip(offset).take(limit).collect();
+    // These need to open each entry, so they are only worked out for the requested page
+    for entry in entries.iter_mut() {
+        let entry_path = path.join(&entry.name);
+        if entry.is_directory {
+            entry.child_count = child_count(&entry_path);
+        } else if entry.content_type.is_some() {
+            entry.encrypted = encryption::looks_encrypted(&entry_path);
+        }
+    }
+    Ok(DirectoryPage { entries, total })
 }
 
 #[tauri::command]
-pub fn delete_content_path(id: i32) -> Result<(), String> {
-    info!("Deleting content path with ID: {}", id);
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for deleting content path: {}", e);
-        e.to_string()
-    })?;
+pub async fn list_directories_in_path(
+    database: State<'_, Database>,
+    path: String,
+    language: Option<String>,
+    options: Option<ListOptions>,
+) -> Result<Vec<FileEntry>, String> {
+    info!("Listing directories in path: {} (language: {:?})", path, language);
+    database
+        .run(move |db| {
+            let page = list_entries(db, &path, language.as_deref(), &options.unwrap_or_default())?;
+            info!("Successfully listed {} entries in path: {}", page.entries.len(), path);
+            Ok(page.entries)
+        })
+        .await
+}
 
-    // Check if the path is active before deleting
-    let mut stmt = db.prepare("SELECT is_active FROM ContentPaths WHERE id = ?1").map_err(|e| {
-        error!("Failed to prepare statement for checking active status of content path: {}", e);
-        e.to_string()
-    })?;
-    let is_active: bool = stmt.query_row(params![id], |row| row.get(0)).map_err(|e| {
-        error!("Failed to query for active status of content path: {}", e);
-        e.to_string()
-    })?;
+// Same as list_directories_in_path, with the folder's total for paging through large folders
+#[tauri::command]
+pub async fn list_directory_page(
+    database: State<'_, Database>,
+    path: String,
+    language: Option<String>,
+    options: Option<ListOptions>,
+) -> Result<DirectoryPage, String> {
+    info!("Listing page of {} with options {:?}", path, options);
+    database
+        .run(move |db| {
+            let page = list_entries(db, &path, language.as_deref(), &options.unwrap_or_default())?;
+            info!("Listed {} of {} entries in path: {}", page.entries.len(), page.total, path);
+            Ok(page)
+        })
+        .await
+}
 
-    if is_active {
-        warn!("Attempted to delete active content path with ID: {}", id);
-        return Err("Cannot delete the active content path.".to_string());
-    }
+#[tauri::command]
+pub async fn get_pinned_order(database: State<'_, Database>) -> Result<Vec<String>, String> {
+    info!("Retrieving pinned folder order.");
+    database.run(|db| read_pinned_order(db)).await
+}
 
-    let deleted_rows = db.execute(
-        "DELETE FROM ContentPaths WHERE id = ?1",
-        params![id],
-    ).map_err(|e| {
-        error!("Failed to delete content path: {}", e);
-        e.to_string()
-    })?;
+// Names of folders to list first, in order. Matching ignores case, spaces, '-' and '_'.
+#[tauri::command]
+pub async fn set_pinned_order(database: State<'_, Database>, names: Vec<String>) -> Result<(), String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Setting pinned folder order: {:?}", names);
+    database
+        .run(move |db| {
+            let value = serde_json::to_string(&names).map_err(|e| e.to_string())?;
+            db.execute(
+                "INSERT INTO AppSettings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![PINNED_ORDER_SETTING, value],
+            )
+            .map_err(|e| {
+                error!("Failed to store pinned order: {}", e);
+                e.to_string()
+            })?;
+            Ok(())
+        })
+        .await
+}
 
-    if deleted_rows == 0 {
-        warn!("Content path with id {} not found for deletion.", id);
-        return Err(format!("Content path with id {} not found.", id));
+fn read_localized_titles(dir: &Path, language: Option<&str>) -> HashMap<String, String> {
+    let titles_path = dir.join(TITLES_FILE);
+    let mut titles = HashMap::new();
+    let file = match fs::File::open(&titles_path) {
+        Ok(file) => file,
+        Err(_) => return titles,
+    };
+
+    let mut current_name: Option<String> = None;
+    let mut current_title = LocalizedText::default();
+    let mut in_title = false;
+    let mut lang_stack: Vec<Option<String>> = Vec::new();
+
+    for e in EventReader::new(BufReader::new(file)) {
+        match e {
+            Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                let inherited = lang_stack.last().cloned().flatten();
+                lang_stack.push(xml_lang(&attributes).or(inherited));
+                match name.local_name.as_str() {
+                    "entry" => {
+                        current_name = attributes
+                            .iter()
+                            .find(|attr| attr.name.local_name == "name")
+                            .map(|attr| attr.value.clone());
+                        current_title = LocalizedText::default();
+                    }
+                    "title" => in_title = true,
+                    _ => (),
+                }
+            }
+            Ok(XmlEvent::Characters(text)) if in_title => {
+                current_title.push(lang_stack.last().cloned().flatten(), text);
+            }
+            Ok(XmlEvent::EndElement { name }) => {
+                lang_stack.pop();
+                match name.local_name.as_str() {
+                    "title" => in_title = false,
+                    "entry" => {
+                        if let (Some(entry_name), Some(title)) = (current_name.take(), current_title.resolve(language)) {
+                            titles.insert(entry_name, title);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Err(e) => {
+                warn!("Ignoring malformed {}: {}", titles_path.display(), e);
+                return HashMap::new();
+            }
+            _ => (),
+        }
     }
-    info!("Successfully deleted content path with ID: {}", id);
 
-    Ok(())
+    info!("Loaded {} localized titles from {}", titles.len(), titles_path.display());
+    titles
 }
 
 #[tauri::command]
-pub fn open_file_in_system(path: String) -> Result<(), String> {
-    info!("Attempting to open file in system: {}", path);
-    let path = Path::new(&path);
+pub async fn delete_content_path(database: State<'_, Database>, id: i32) -> Result<(), String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Deleting content path with ID: {}", id);
+    database
+        .run(move |db| {
+            // Check if the path is active before deleting
+            let is_active: bool = db.query_row("SELECT is_active FROM ContentPaths WHERE id = ?1", params![id], |row| row.get(0)).map_err(|e| {
+                error!("Failed to query for active status of content path: {}", e);
+                e.to_string()
+            })?;
+
+            if is_active {
+                warn!("Attempted to delete active content path with ID: {}", id);
+                return Err("Cannot delete the active content path.".to_string());
+            }
 
-    if !path.is_file() {
-        warn!("Path is not a file: {}", path.display());
-        return Err(format!("Path is not a file: {}", path.display()));
-    }
+            let tx = db.transaction().map_err(|e| {
+                error!("Failed to start database transaction for deleting content path: {}", e);
+                e.to_string()
+            })?;
+
+            // Content items and their progress cascade; the search index has no foreign keys
+            tx.execute("DELETE FROM ContentSearch WHERE folder_id = ?1", params![id]).map_err(|e| {
+                error!("Failed to delete search index of content path {}: {}", id, e);
+                e.to_string()
+            })?;
+
+            let deleted_rows = tx.execute(
+                "DELETE FROM ContentPaths WHERE id = ?1",
+                params![id],
+            ).map_err(|e| {
+                error!("Failed to delete content path: {}", e);
+                e.to_string()
+            })?;
+
+            if deleted_rows == 0 {
+                warn!("Content path with id {} not found for deletion.", id);
+                return Err(format!("Content path with id {} not found.", id));
+            }
+
+            tx.commit().map_err(|e| {
+                error!("Failed to commit database transaction for deleting content path: {}", e);
+                e.to_string()
+            })?;
+            info!("Successfully deleted content path with ID: {}", id);
 
-    // Use the 'open' crate to open the file with the default application
-    match open::that(path) {
-        Ok(_) => {
-            info!("Successfully opened file: {}", path.display());
             Ok(())
-        },
-        Err(e) => {
-            error!("Failed to open file {}: {}", path.display(), e);
-            Err(format!("Failed to open file: {}", e))
-        },
-    }
-} 
\ No newline at end of file
+        })
+        .await
+}
@@ -1,20 +1,27 @@
 // src-tauri/src/db.rs
 
-use dirs;
+use r2d2::{ManageConnection, Pool, PooledConnection};
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
+use std::time::Duration;
+use tauri::State;
 use log::{info, error};
 
 const DB_NAME: &str = "app.db";
+// SQLite allows one writer at a time; extra connections let reads run next to it
+const POOL_SIZE: u32 = 4;
+// How long a command waits for a free connection from the pool
+const POOL_TIMEOUT: Duration = Duration::from_secs(30);
+// How long a write waits for another connection's write to finish before failing with SQLITE_BUSY
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+// Statements kept prepared per connection for prepare_cached
+const STATEMENT_CACHE_CAPACITY: usize = 64;
 
-// Function to get the database file path
-pub fn get_db_path() -> Result<PathBuf, String> {
-    info!("Attempting to get database path.");
+// Function to get the application data directory, which also holds imported content
+pub fn get_app_data_dir() -> Result<PathBuf, String> {
     dirs::data_dir()
         .map(|mut path| {
             path.push("com.osiris.app");
-            path.push(DB_NAME);
-            info!("Database path resolved to: {}", path.display());
             path
         })
         .ok_or_else(|| {
@@ -23,35 +30,137 @@ pub fn get_db_path() -> Result<PathBuf, String> {
         })
 }
 
-// Function to initialize the database (create file and tables if they don't exist)
-pub fn initialize_db() -> Result<(), String> {
-    info!("Initializing database.");
-    let db_path = get_db_path()?;
+// Function to get the database file path
+pub fn get_db_path() -> Result<PathBuf, String> {
+    info!("Attempting to get database path.");
+    let mut path = get_app_data_dir()?;
+    path.push(DB_NAME);
+    info!("Database path resolved to: {}", path.display());
+    Ok(path)
+}
 
-    // Check if the database file already exists
-    if db_path.exists() {
-        info!("Database file already exists. Skipping initialization.");
-        return Ok(());
+// Opens pool connections. SQLite applies these pragmas per connection: without
+// foreign_keys the ON DELETE rules below do nothing. WAL lets readers run while the
+// indexer rewrites large tables; it is stored in the file, so asking again is a no-op.
+pub struct SqliteConnectionManager {
+    path: PathBuf,
+}
+
+impl ManageConnection for SqliteConnectionManager {
+    type Connection = Connection;
+    type Error = rusqlite::Error;
+
+    fn connect(&self) -> Result<Connection, rusqlite::Error> {
+        let db = Connection::open(&self.path)?;
+        db.busy_timeout(BUSY_TIMEOUT)?;
+        db.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        db.query_row("PRAGMA journal_mode = WAL", [], |row| row.get::<_, String>(0))?;
+        db.execute_batch("PRAGMA foreign_keys = ON; PRAGMA synchronous = NORMAL;")?;
+        Ok(db)
     }
 
-    info!("Database file not found. Initializing database...");
+    fn is_valid(&self, db: &mut Connection) -> Result<(), rusqlite::Error> {
+        db.execute_batch("")
+    }
+
+    fn has_broken(&self, _db: &mut Connection) -> bool {
+        false
+    }
+}
+
+pub type DbConnection = PooledConnection<SqliteConnectionManager>;
+
+// The app database, kept in Tauri managed state. Cloning is cheap and shares the pool.
+#[derive(Clone)]
+pub struct Database {
+    pool: Pool<SqliteConnectionManager>,
+}
 
-    // Create directory if it doesn't exist
-    if let Some(parent) = db_path.parent() {
-        if !parent.exists() {
-            info!("Creating database directory: {}", parent.display());
-            std::fs::create_dir_all(parent).map_err(|e| {
-                error!("Failed to create database directory {}: {}", parent.display(), e);
+impl Database {
+    // Creates the database file if needed, brings the schema up to date and opens the pool
+    pub fn open() -> Result<Self, String> {
+        let db_path = get_db_path()?;
+        let is_new = !db_path.exists();
+        if let Some(parent) = db_path.parent() {
+            if !parent.exists() {
+                info!("Creating database directory: {}", parent.display());
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    error!("Failed to create database directory {}: {}", parent.display(), e);
+                    e.to_string()
+                })?;
+            }
+        }
+
+        let pool = Pool::builder()
+            .max_size(POOL_SIZE)
+            .connection_timeout(POOL_TIMEOUT)
+            .build(SqliteConnectionManager { path: db_path })
+            .map_err(|e| {
+                error!("Failed to open database connection pool: {}", e);
                 e.to_string()
             })?;
-        }
+        let database = Database { pool };
+
+        let mut db = database.connection()?;
+        initialize_db(&mut db, is_new)?;
+        Ok(database)
     }
 
-    // Open connection (this will create the file if it doesn't exist)
-    let mut db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database connection: {}", e);
-        e.to_string()
-    })?;
+    // Takes a connection from the pool, waiting up to POOL_TIMEOUT for one to be returned
+    pub fn connection(&self) -> Result<DbConnection, String> {
+        self.pool.get().map_err(|e| {
+            error!("Failed to get a database connection: {}", e);
+            e.to_string()
+        })
+    }
+
+    // Runs `task` with a pooled connection on the blocking thread pool, so commands never
+    // wait on the disk from the thread that serves the UI
+    pub async fn run<T, F>(&self, task: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, String> + Send + 'static,
+    {
+        let database = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut db = database.connection()?;
+            task(&mut db)
+        })
+        .await
+        .map_err(|e| {
+            error!("Database task failed: {}", e);
+            e.to_string()
+        })?
+    }
+
+    // Like `run`, but without a connection: for tasks that read files or walk trees, which
+    // take a connection only around their own queries and writes
+    pub async fn run_blocking<T, F>(&self, task: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Database) -> Result<T, String> + Send + 'static,
+    {
+        let database = self.clone();
+        tokio::task::spawn_blocking(move || task(&database))
+            .await
+            .map_err(|e| {
+                error!("Database task failed: {}", e);
+                e.to_string()
+            })?
+    }
+}
+
+// Function to initialize the database (create file and tables if they don't exist)
+fn initialize_db(db: &mut Connection, is_new: bool) -> Result<(), String> {
+    info!("Initializing database.");
+
+    // Check if the database file already existed
+    if !is_new {
+        info!("Database file already exists. Skipping table creation.");
+        return apply_migrations(db);
+    }
+
+    info!("Database file not found. Initializing database...");
 
     let table_creation_sqls = [
         "CREATE TABLE IF NOT EXISTS Users (
@@ -118,40 +227,420 @@ pub fn initialize_db() -> Result<(), String> {
         e.to_string()
     })?;
 
+    apply_migrations(db)?;
+
     info!("Database initialization complete.");
 
     Ok(())
 }
 
-// Check if the Activations table has any data
-#[tauri::command]
-pub fn has_activation() -> Result<bool, String> {
-    info!("Checking for activation in DB.");
-    let db_path = get_db_path()?;
-    // Check if the database file exists. If not, there's no activation.
-    if !db_path.exists() {
-        info!("Database file does not exist. No activation found.");
-        return Ok(false);
-    }
+// Schema changes applied on top of the base tables, in order. A database that has run
+// entry N has `PRAGMA user_version` = N + 1, so only newer entries run on startup.
+const MIGRATIONS: &[&str] = &[
+    // 1: Content index populated by indexer.rs
+    "ALTER TABLE ContentItems ADD COLUMN relative_path TEXT;
+     ALTER TABLE ContentItems ADD COLUMN content_type TEXT;
+     ALTER TABLE ContentItems ADD COLUMN class_name TEXT;
+     ALTER TABLE ContentItems ADD COLUMN subject TEXT;
+     ALTER TABLE ContentItems ADD COLUMN chapter TEXT;
+     ALTER TABLE ContentItems ADD COLUMN size INTEGER;
+     ALTER TABLE ContentItems ADD COLUMN modified_at INTEGER;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_content_items_path ON ContentItems(folder_id, relative_path);
+     CREATE INDEX IF NOT EXISTS idx_content_items_hierarchy ON ContentItems(folder_id, class_name, subject, chapter);",
+    // 2: Full-text search over content names and quiz text, maintained by search.rs
+    "CREATE VIRTUAL TABLE IF NOT EXISTS ContentSearch USING fts5(
+        title,
+        path_text,
+        body,
+        folder_id UNINDEXED,
+        relative_path UNINDEXED,
+        entry_type UNINDEXED,
+        class_name UNINDEXED,
+        subject UNINDEXED,
+        tokenize = 'unicode61 remove_diacritics 2'
+     );",
+    // 3: Per-item progress details recorded by progress.rs
+    "ALTER TABLE ContentProgress ADD COLUMN position_seconds REAL;
+     ALTER TABLE ContentProgress ADD COLUMN duration_seconds REAL;
+     ALTER TABLE ContentProgress ADD COLUMN pages_viewed TEXT; -- JSON array of page numbers
+     ALTER TABLE ContentProgress ADD COLUMN last_page INTEGER;
+     ALTER TABLE ContentProgress ADD COLUMN page_count INTEGER;
+     ALTER TABLE ContentProgress ADD COLUMN quiz_score INTEGER;
+     ALTER TABLE ContentProgress ADD COLUMN quiz_total INTEGER;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_content_progress_user_item ON ContentProgress(user_id, content_item_id);",
+    // 4: Package details from content manifests (manifest.rs)
+    "ALTER TABLE ContentPaths ADD COLUMN title TEXT;
+     ALTER TABLE ContentPaths ADD COLUMN board TEXT;
+     ALTER TABLE ContentPaths ADD COLUMN language TEXT;
+     ALTER TABLE ContentPaths ADD COLUMN version TEXT;
+     ALTER TABLE ContentItems ADD COLUMN sort_order INTEGER;",
+    // 5: Identity of content roots on removable drives (media.rs)
+    "ALTER TABLE ContentPaths ADD COLUMN volume_uuid TEXT;
+     ALTER TABLE ContentPaths ADD COLUMN volume_path TEXT;
+     ALTER TABLE ContentPaths ADD COLUMN library_id TEXT;",
+    // 6: Admin-editable settings such as the pinned folder order (content.rs)
+    "CREATE TABLE IF NOT EXISTS AppSettings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+     );",
+    // 7: Content hashes of files, reused while size and mtime are unchanged (thumbnail.rs)
+    "CREATE TABLE IF NOT EXISTS ContentHashes (
+        path TEXT PRIMARY KEY,
+        size INTEGER NOT NULL,
+        modified_at INTEGER NOT NULL,
+        sha256 TEXT NOT NULL
+     );",
+    // 8: Parsed media headers, reused while size and mtime are unchanged (mediainfo.rs)
+    "CREATE TABLE IF NOT EXISTS MediaInfo (
+        path TEXT PRIMARY KEY,
+        size INTEGER NOT NULL,
+        modified_at INTEGER NOT NULL,
+        info TEXT NOT NULL
+     );",
+    // 9: Extracted text of plain PDFs per page, reused while size and mtime are unchanged.
+    // Text of encrypted PDFs is only cached in memory (pdftext.rs).
+    "CREATE TABLE IF NOT EXISTS PdfDocuments (
+        path TEXT PRIMARY KEY,
+        size INTEGER NOT NULL,
+        modified_at INTEGER NOT NULL,
+        page_count INTEGER NOT NULL
+     );
+     CREATE TABLE IF NOT EXISTS PdfPages (
+        path TEXT NOT NULL REFERENCES PdfDocuments(path) ON DELETE CASCADE,
+        page INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        PRIMARY KEY (path, page)
+     );",
+    // 10: Viewing sessions from the in-app viewer (viewer.rs)
+    "ALTER TABLE ContentProgress ADD COLUMN open_count INTEGER DEFAULT 0;
+     ALTER TABLE ContentProgress ADD COLUMN time_spent_seconds REAL DEFAULT 0;
+     ALTER TABLE ContentProgress ADD COLUMN last_opened_at DATETIME;
+     ALTER TABLE ContentProgress ADD COLUMN last_closed_at DATETIME;",
+    // 11: SCORM runtime data per user and package (scorm.rs)
+    "CREATE TABLE IF NOT EXISTS ScormRegistrations (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id INTEGER NOT NULL REFERENCES Users(id),
+        package_path TEXT NOT NULL,
+        scorm_version TEXT NOT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (user_id, package_path)
+     );
+     CREATE TABLE IF NOT EXISTS ScormCmiData (
+        registration_id INTEGER NOT NULL REFERENCES ScormRegistrations(id) ON DELETE CASCADE,
+        element TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (registration_id, element)
+     );",
+    // 12: xAPI statements of the local Learning Record Store (xapi.rs)
+    "CREATE TABLE IF NOT EXISTS XapiStatements (
+        id TEXT PRIMARY KEY,
+        user_id INTEGER REFERENCES Users(id),
+        verb_id TEXT NOT NULL,
+        activity_id TEXT NOT NULL,
+        registration TEXT,
+        timestamp TEXT NOT NULL,
+        stored TEXT NOT NULL,
+        statement TEXT NOT NULL,
+        exported_at DATETIME
+     );
+     CREATE INDEX IF NOT EXISTS idx_xapi_statements_stored ON XapiStatements(stored);
+     CREATE INDEX IF NOT EXISTS idx_xapi_statements_user ON XapiStatements(user_id, stored);
+     CREATE INDEX IF NOT EXISTS idx_xapi_statements_activity ON XapiStatements(activity_id, stored);",
+    // 13: Classes, sections and rosters (roster.rs)
+    "CREATE TABLE IF NOT EXISTS Classes (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL UNIQUE,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+     );
+     CREATE TABLE IF NOT EXISTS Sections (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        class_id INTEGER NOT NULL REFERENCES Classes(id),
+        name TEXT NOT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (class_id, name)
+     );
+     CREATE TABLE IF NOT EXISTS TeacherSections (
+        teacher_id INTEGER NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+        section_id INTEGER NOT NULL REFERENCES Sections(id) ON DELETE CASCADE,
+        PRIMARY KEY (teacher_id, section_id)
+     );
+     ALTER TABLE Users ADD COLUMN section_id INTEGER REFERENCES Sections(id);
+     ALTER TABLE Users ADD COLUMN roll_number TEXT;
+     ALTER TABLE Users ADD COLUMN display_name TEXT;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_users_section_roll ON Users(section_id, roll_number);",
+    // 14: Roles limited to roles::Role, and the content categories each role sees (roles.rs).
+    // SQLite cannot add a CHECK to an existing column, so Users is rebuilt. Roles saved
+    // before this are mapped: admin spellings (including the translated labels the user
+    // form used to send) become admin, anything unrecognised becomes student.
+    "CREATE TABLE Users_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        username TEXT NOT NULL UNIQUE,
+        password TEXT NOT NULL,
+        role TEXT NOT NULL CHECK (role IN ('admin', 'teacher', 'student', 'guest')),
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        section_id INTEGER REFERENCES Sections(id),
+        roll_number TEXT,
+        display_name TEXT
+     );
+     INSERT INTO Users_new (id, username, password, role, created_at, updated_at, section_id, roll_number, display_name)
+        SELECT id, username, password,
+               CASE
+                   WHEN lower(trim(role)) IN ('admin', 'ನಿರ್ವಾಹಕ', 'व्यवस्थापक') THEN 'admin'
+                   WHEN lower(trim(role)) = 'teacher' THEN 'teacher'
+                   WHEN lower(trim(role)) IN ('guest', 'kiosk') THEN 'guest'
+                   ELSE 'student'
+               END,
+               created_at, updated_at, section_id, roll_number, display_name
+        FROM Users;
+     DROP TABLE Users;
+     ALTER TABLE Users_new RENAME TO Users;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_users_section_roll ON Users(section_id, roll_number);
+     CREATE TABLE IF NOT EXISTS RoleContentCategories (
+        role TEXT NOT NULL CHECK (role IN ('admin', 'teacher', 'student', 'guest')),
+        content_type TEXT NOT NULL,
+        visible INTEGER NOT NULL DEFAULT 1,
+        PRIMARY KEY (role, content_type)
+     );",
+    // 15: Offline admin recovery code (recovery.rs). One row; only the code's hash is kept.
+    "CREATE TABLE IF NOT EXISTS AdminRecovery (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        code_hash TEXT NOT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+     );",
+    // 16: Soft-deleted users (users.rs). Roll numbers only need to be unique among users
+    // who are not deleted, so a deleted student's roll number can be given to someone else.
+    "ALTER TABLE Users ADD COLUMN deleted_at DATETIME;
+     DROP INDEX IF EXISTS idx_users_section_roll;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_users_section_roll ON Users(section_id, roll_number)
+        WHERE deleted_at IS NULL;",
+    // 17: ON DELETE behaviour for every reference. Rows follow what they belong to
+    // (CASCADE); learning history outlives its user, and students their section (SET NULL).
+    // SQLite cannot change a foreign key in place, so the tables are rebuilt. Rows that
+    // already pointed at nothing are dropped, or lose the reference, as if the rule had
+    // always applied.
+    "CREATE TABLE ContentItems_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        folder_id INTEGER REFERENCES ContentPaths(id) ON DELETE CASCADE,
+        title TEXT NOT NULL,
+        description TEXT,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        relative_path TEXT,
+        content_type TEXT,
+        class_name TEXT,
+        subject TEXT,
+        chapter TEXT,
+        size INTEGER,
+        modified_at INTEGER,
+        sort_order INTEGER
+     );
+     INSERT INTO ContentItems_new
+        SELECT id, folder_id, title, description, created_at, updated_at, relative_path, content_type,
+               class_name, subject, chapter, size, modified_at, sort_order
+        FROM ContentItems
+        WHERE folder_id IS NULL OR folder_id IN (SELECT id FROM ContentPaths);
+     DROP TABLE ContentItems;
+     ALTER TABLE ContentItems_new RENAME TO ContentItems;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_content_items_path ON ContentItems(folder_id, relative_path);
+     CREATE INDEX IF NOT EXISTS idx_content_items_hierarchy ON ContentItems(folder_id, class_name, subject, chapter);
 
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for activation check: {}", e);
-        e.to_string()
-    })?;
+     CREATE TABLE Sections_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        class_id INTEGER NOT NULL REFERENCES Classes(id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (class_id, name)
+     );
+     INSERT INTO Sections_new SELECT id, class_id, name, created_at FROM Sections
+        WHERE class_id IN (SELECT id FROM Classes);
+     DROP TABLE Sections;
+     ALTER TABLE Sections_new RENAME TO Sections;
 
-    let mut stmt = db
-        .prepare("SELECT COUNT(*) FROM Activations")
+     CREATE TABLE Users_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        username TEXT NOT NULL UNIQUE,
+        password TEXT NOT NULL,
+        role TEXT NOT NULL CHECK (role IN ('admin', 'teacher', 'student', 'guest')),
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        section_id INTEGER REFERENCES Sections(id) ON DELETE SET NULL,
+        roll_number TEXT,
+        display_name TEXT,
+        deleted_at DATETIME
+     );
+     INSERT INTO Users_new
+        SELECT id, username, password, role, created_at, updated_at,
+               CASE WHEN section_id IN (SELECT id FROM Sections) THEN section_id END,
+               roll_number, display_name, deleted_at
+        FROM Users;
+     DROP TABLE Users;
+     ALTER TABLE Users_new RENAME TO Users;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_users_section_roll ON Users(section_id, roll_number)
+        WHERE deleted_at IS NULL;
+
+     CREATE TABLE ContentProgress_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id INTEGER REFERENCES Users(id) ON DELETE SET NULL,
+        folder_id INTEGER REFERENCES ContentPaths(id) ON DELETE CASCADE,
+        content_item_id INTEGER REFERENCES ContentItems(id) ON DELETE CASCADE,
+        completion_percentage INTEGER DEFAULT 0,
+        status TEXT DEFAULT 'in-progress',
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        position_seconds REAL,
+        duration_seconds REAL,
+        pages_viewed TEXT, -- JSON array of page numbers
+        last_page INTEGER,
+        page_count INTEGER,
+        quiz_score INTEGER,
+        quiz_total INTEGER,
+        open_count INTEGER DEFAULT 0,
+        time_spent_seconds REAL DEFAULT 0,
+        last_opened_at DATETIME,
+        last_closed_at DATETIME
+     );
+     INSERT INTO ContentProgress_new
+        SELECT id, CASE WHEN user_id IN (SELECT id FROM Users) THEN user_id END, folder_id, content_item_id,
+               completion_percentage, status, updated_at, position_seconds, duration_seconds, pages_viewed,
+               last_page, page_count, quiz_score, quiz_total, open_count, time_spent_seconds,
+               last_opened_at, last_closed_at
+        FROM ContentProgress
+        WHERE (folder_id IS NULL OR folder_id IN (SELECT id FROM ContentPaths))
+          AND (content_item_id IS NULL OR content_item_id IN (SELECT id FROM ContentItems));
+     DROP TABLE ContentProgress;
+     ALTER TABLE ContentProgress_new RENAME TO ContentProgress;
+     CREATE UNIQUE INDEX IF NOT EXISTS idx_content_progress_user_item ON ContentProgress(user_id, content_item_id);
+
+     CREATE TABLE ScormRegistrations_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id INTEGER NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+        package_path TEXT NOT NULL,
+        scorm_version TEXT NOT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (user_id, package_path)
+     );
+     INSERT INTO ScormRegistrations_new
+        SELECT id, user_id, package_path, scorm_version, created_at, updated_at FROM ScormRegistrations
+        WHERE user_id IN (SELECT id FROM Users);
+     DROP TABLE ScormRegistrations;
+     ALTER TABLE ScormRegistrations_new RENAME TO ScormRegistrations;
+     DELETE FROM ScormCmiData WHERE registration_id NOT IN (SELECT id FROM ScormRegistrations);
+
+     CREATE TABLE XapiStatements_new (
+        id TEXT PRIMARY KEY,
+        user_id INTEGER REFERENCES Users(id) ON DELETE SET NULL,
+        verb_id TEXT NOT NULL,
+        activity_id TEXT NOT NULL,
+        registration TEXT,
+        timestamp TEXT NOT NULL,
+        stored TEXT NOT NULL,
+        statement TEXT NOT NULL,
+        exported_at DATETIME
+     );
+     INSERT INTO XapiStatements_new
+        SELECT id, CASE WHEN user_id IN (SELECT id FROM Users) THEN user_id END, verb_id, activity_id,
+               registration, timestamp, stored, statement, exported_at
+        FROM XapiStatements;
+     DROP TABLE XapiStatements;
+     ALTER TABLE XapiStatements_new RENAME TO XapiStatements;
+     CREATE INDEX IF NOT EXISTS idx_xapi_statements_stored ON XapiStatements(stored);
+     CREATE INDEX IF NOT EXISTS idx_xapi_statements_user ON XapiStatements(user_id, stored);
+     CREATE INDEX IF NOT EXISTS idx_xapi_statements_activity ON XapiStatements(activity_id, stored);
+
+     DELETE FROM TeacherSections
+        WHERE teacher_id NOT IN (SELECT id FROM Users) OR section_id NOT IN (SELECT id FROM Sections);",
+    // 18: Items whose file disappeared are kept with their progress until the content path
+    // is deleted, and picked up again when the file returns or turns up elsewhere (indexer.rs)
+    "ALTER TABLE ContentItems ADD COLUMN missing_at DATETIME;
+     CREATE INDEX IF NOT EXISTS idx_content_items_missing ON ContentItems(folder_id, size, modified_at)
+        WHERE missing_at IS NOT NULL;",
+];
+
+// Brings the schema up to date with MIGRATIONS
+fn apply_migrations(db: &mut Connection) -> Result<(), String> {
+    let current_version: usize = db
+        .query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
         .map_err(|e| {
-            error!("Failed to prepare statement for activation count: {}", e);
+            error!("Failed to read database schema version: {}", e);
+            e.to_string()
+        })? as usize;
+
+    if current_version >= MIGRATIONS.len() {
+        info!("Database schema is up to date (version {}).", current_version);
+        return Ok(());
+    }
+
+    // Migrations that rebuild a table drop it while other tables still reference it, so
+    // foreign keys are switched off while they run (this has no effect inside a
+    // transaction) and checked before each commit instead. Broken references that predate
+    // a migration do not stop it; dbcheck.rs finds and repairs those.
+    db.execute_batch("PRAGMA foreign_keys = OFF").map_err(|e| e.to_string())?;
+    let result = run_migrations(db, current_version);
+    if let Err(e) = db.execute_batch("PRAGMA foreign_keys = ON") {
+        error!("Failed to re-enable foreign keys after migrations: {}", e);
+    }
+    result?;
+
+    info!("Database schema migrated to version {}.", MIGRATIONS.len());
+    Ok(())
+}
+
+fn run_migrations(db: &mut Connection, current_version: usize) -> Result<(), String> {
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current_version) {
+        let version = index + 1;
+        info!("Applying database migration {}.", version);
+        let tx = db.transaction().map_err(|e| {
+            error!("Failed to start transaction for migration {}: {}", version, e);
             e.to_string()
         })?;
-    let count: i64 = stmt
-        .query_row([], |row| row.get(0))
-        .map_err(|e| {
-            error!("Failed to query activation count: {}", e);
+        let violations_before = foreign_key_violations(&tx)?;
+        tx.execute_batch(sql).map_err(|e| {
+            error!("Failed to apply migration {}: {}\nSQL: {}", version, e, sql);
+            e.to_string()
+        })?;
+        let violations = foreign_key_violations(&tx)?;
+        if violations > violations_before {
+            error!("Migration {} would leave {} rows with broken references.", version, violations - violations_before);
+            return Err(format!(
+                "Migration {} left {} rows with broken references.",
+                version,
+                violations - violations_before
+            ));
+        }
+        tx.execute_batch(&format!("PRAGMA user_version = {}", version)).map_err(|e| {
+            error!("Failed to record schema version {}: {}", version, e);
+            e.to_string()
+        })?;
+        tx.commit().map_err(|e| {
+            error!("Failed to commit migration {}: {}", version, e);
             e.to_string()
         })?;
+    }
+    Ok(())
+}
+
+fn foreign_key_violations(db: &Connection) -> Result<i64, String> {
+    db.query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
 
-    info!("Activation count: {}", count);
-    Ok(count > 0)
+// Check if the Activations table has any data
+#[tauri::command]
+pub async fn has_activation(database: State<'_, Database>) -> Result<bool, String> {
+    info!("Checking for activation in DB.");
+    database
+        .run(|db| {
+            let count: i64 = db
+                .prepare_cached("SELECT COUNT(*) FROM Activations")
+                .and_then(|mut stmt| stmt.query_row([], |row| row.get(0)))
+                .map_err(|e| {
+                    error!("Failed to query activation count: {}", e);
+                    e.to_string()
+                })?;
+            info!("Activation count: {}", count);
+            Ok(count > 0)
+        })
+        .await
 }
@@ -0,0 +1,159 @@
+// src-tauri/src/dbcheck.rs
+
+use crate::db::Database;
+use crate::roles::{require_permission, Permission};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use tauri::State;
+
+// Repairs can expose new orphans (a deleted row may be the parent of others), so they run
+// in passes until nothing is left. Real chains are a few levels deep.
+const MAX_REPAIR_PASSES: usize = 8;
+
+// Rows of `table` whose `column` points at a missing row of `parent`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanGroup {
+    pub table: String,
+    pub column: String,
+    pub parent: String,
+    pub on_delete: String, // The relation's rule, which repair applies: "SET NULL" clears the column, anything else deletes the row
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatabaseCheckReport {
+    pub integrity_ok: bool,
+    pub integrity_messages: Vec<String>, // From PRAGMA integrity_check; corruption is reported, not repaired
+    pub orphans: Vec<OrphanGroup>,       // Found before any repair
+    pub repaired: usize, // Distinct rows deleted or updated by the repair
+}
+
+struct Violation {
+    table: String,
+    rowid: i64,
+    column: String,
+    parent: String,
+    on_delete: String,
+}
+
+fn integrity_messages(db: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = db.prepare("PRAGMA integrity_check").map_err(|e| e.to_string())?;
+    let messages = stmt
+        .query_map([], |row| row.get(0))
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<String>>>())
+        .map_err(|e| {
+            error!("Failed to run integrity check: {}", e);
+            e.to_string()
+        })?;
+    Ok(messages)
+}
+
+fn violations(db: &Connection) -> Result<Vec<Violation>, String> {
+    let mut stmt = db
+        .prepare(
+            "SELECT c.\"table\", c.rowid, f.\"from\", c.parent, f.on_delete
+             FROM pragma_foreign_key_check c
+             JOIN pragma_foreign_key_list(c.\"table\") f ON f.id = c.fkid
+             WHERE c.rowid IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let violations = stmt
+        .query_map([], |row| {
+            Ok(Violation {
+                table: row.get(0)?,
+                rowid: row.get(1)?,
+                column: row.get(2)?,
+                parent: row.get(3)?,
+                on_delete: row.get(4)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| {
+            error!("Failed to check foreign keys: {}", e);
+            e.to_string()
+        })?;
+    Ok(violations)
+}
+
+fn group_orphans(violations: &[Violation]) -> Vec<OrphanGroup> {
+    let mut groups: BTreeMap<(&str, &str, &str), OrphanGroup> = BTreeMap::new();
+    for v in violations {
+        groups
+            .entry((&v.table, &v.column, &v.parent))
+            .or_insert_with(|| OrphanGroup {
+                table: v.table.clone(),
+                column: v.column.clone(),
+                parent: v.parent.clone(),
+                on_delete: v.on_delete.clone(),
+                count: 0,
+            })
+            .count += 1;
+    }
+    groups.into_values().collect()
+}
+
+// Applies the relation's ON DELETE rule to an orphan, as if its parent had just been deleted.
+// Returns whether the row changed; one already deleted for another violation does not.
+fn repair(db: &Connection, violation: &Violation) -> Result<bool, String> {
+    let sql = if violation.on_delete.eq_ignore_ascii_case("SET NULL") {
+        format!("UPDATE \"{}\" SET \"{}\" = NULL WHERE rowid = ?1", violation.table, violation.column)
+    } else {
+        format!("DELETE FROM \"{}\" WHERE rowid = ?1", violation.table)
+    };
+    let changed = db.execute(&sql, params![violation.rowid]).map_err(|e| {
+        error!("Failed to repair row {} of {}: {}", violation.rowid, violation.table, e);
+        e.to_string()
+    })?;
+    Ok(changed > 0)
+}
+
+// Runs SQLite's integrity check and lists rows with broken references. With `repair`,
+// those rows are fixed following the ON DELETE rule of their relation.
+#[tauri::command]
+pub async fn check_database_integrity(database: State<'_, Database>, repair_orphans: bool) -> Result<DatabaseCheckReport, String> {
+    require_permission(Permission::ManageSettings)?;
+    info!("Checking database integrity (repair: {})", repair_orphans);
+    database
+        .run(move |db| {
+            let integrity_messages = integrity_messages(db)?;
+            let integrity_ok = integrity_messages.len() == 1 && integrity_messages[0] == "ok";
+            if !integrity_ok {
+                warn!("Database integrity check failed: {:?}", integrity_messages);
+            }
+
+            let found = violations(db)?;
+            let orphans = group_orphans(&found);
+            // Rows, not violations: one row can break several references
+            let mut repaired_rows = HashSet::new();
+            if repair_orphans && !found.is_empty() {
+                let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+                let mut pending = found;
+                for _ in 0..MAX_REPAIR_PASSES {
+                    if pending.is_empty() {
+                        break;
+                    }
+                    for violation in &pending {
+                        if repair(&tx, violation)? {
+                            repaired_rows.insert((violation.table.clone(), violation.rowid));
+                        }
+                    }
+                    pending = violations(&tx)?;
+                }
+                if !pending.is_empty() {
+                    error!("{} broken references remain after {} repair passes.", pending.len(), MAX_REPAIR_PASSES);
+                    return Err(format!("Could not repair {} broken references.", pending.len()));
+                }
+                tx.commit().map_err(|e| {
+                    error!("Failed to commit database repairs: {}", e);
+                    e.to_string()
+                })?;
+                info!("Repaired {} rows with broken references.", repaired_rows.len());
+            }
+            let repaired = repaired_rows.len();
+
+            Ok(DatabaseCheckReport { integrity_ok, integrity_messages, orphans, repaired })
+        })
+        .await
+}
@@ -1,17 +1,25 @@
 use std::fs::File;
-use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
 use aes::Aes256;
 use cbc::cipher::{KeyIvInit, BlockDecryptMut};
 use sha2::{Sha256, Digest};
 use aes::cipher::generic_array::GenericArray;
+use aes::cipher::consts::U32;
+use aes::cipher::{BlockDecrypt, KeyInit};
 use std::env;
 use log::{info, error};
-use tokio::fs;
 use serde_json::json;
 use serde::{Serialize, Deserialize};
 use xml::reader::{EventReader, XmlEvent};
 use std::io::BufReader;
+use crate::content::resolve_in_content_roots;
+use crate::db::Database;
+use crate::roles::require_visible;
+use tauri::State;
+use crate::i18n::{xml_lang, LocalizedText};
+
+const DECRYPTED_DIR: &str = "osiris-decrypted";
 
 // Rust structs mirroring the frontend interfaces
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -30,32 +38,17 @@ pub struct QuizData {
 
 type Aes256Cbc = cbc::Decryptor<Aes256>;
 
-#[tauri::command]
-pub async fn decrypt_file(file_path: String) -> Result<String, String> {
-    info!("Decrypting file: {}", file_path);
-    if file_path.is_empty() {
-        error!("File path is required for decryption.");
-        return Err("File path is required.".into());
-    }
-
-    // Generate secret key using SHA-256
+// Generate secret key using SHA-256
+fn secret_key() -> GenericArray<u8, U32> {
     let key = "iactive@2024";
     let mut hasher = Sha256::new();
     hasher.update(key.as_bytes());
-    let secret_key = hasher.finalize();
-
-    // Read the input file
-    let mut input_file = File::open(&file_path).map_err(|e| {
-        error!("Failed to open file {}: {}", file_path, e);
-        format!("Failed to open file: {}", e)
-    })?;
-    let mut input_data = Vec::new();
-    input_file.read_to_end(&mut input_data).map_err(|e| {
-        error!("Failed to read file {}: {}", file_path, e);
-        format!("Failed to read file: {}", e)
-    })?;
+    hasher.finalize()
+}
 
-    info!("File size: {} bytes", input_data.len());
+// Decrypts content data in memory: a 16-byte IV followed by AES-256-CBC ciphertext
+pub fn decrypt_bytes(input_data: &[u8]) -> Result<Vec<u8>, String> {
+    let secret_key = secret_key();
 
     // Extract IV from the beginning of the encrypted data
     if input_data.len() < 16 {
@@ -93,94 +86,251 @@ pub async fn decrypt_file(file_path: String) -> Result<String, String> {
         }
     }
 
-    info!("Decryption successful, writing {} bytes", decrypted_data.len());
-
-    // Get the original filename
-    let original_path = PathBuf::from(&file_path);
-    let filename = original_path.file_name()
-        .ok_or_else(|| {
-            error!("Invalid file path: Could not extract filename from {}", file_path);
-            "Invalid file path".to_string()
-        })?
-        .to_string_lossy();
-
-    // Create temp file path
-    let temp_dir = env::temp_dir();
-    let temp_file_path = temp_dir.join(format!("decrypted_{}", filename));
-
-    // Write the decrypted data to temp file
-    let mut output_file = File::create(&temp_file_path).map_err(|e| {
-        error!("Failed to create output file {}: {}", temp_file_path.display(), e);
-        format!("Failed to create output file: {}", e)
-    })?;
-    output_file.write_all(&decrypted_data).map_err(|e| {
-        error!("Failed to write decrypted data to {}: {}", temp_file_path.display(), e);
-        format!("Failed to write decrypted data: {}", e)
-    })?;
+    Ok(decrypted_data)
+}
 
-    info!("File decrypted successfully to: {}", temp_file_path.display());
-    Ok(temp_file_path.to_string_lossy().into_owned())
+// Seekable view of the plaintext of an encrypted file. CBC lets any block be decrypted
+// from itself and the ciphertext block before it, so large videos can be read in place
+// without decrypting the whole file. Output matches decrypt_bytes byte for byte.
+pub struct DecryptingReader {
+    file: File,
+    cipher: Aes256,
+    len: u64,
+    pos: u64,
 }
 
-#[tauri::command]
-pub async fn parse_xml_quiz(file_path: String) -> Result<serde_json::Value, String> {
-    info!("Attempting to parse XML quiz from: {}", file_path);
-
-    let contents = match fs::read_to_string(&file_path).await {
-        Ok(content) => content,
-        Err(e) => {
-            let error_message = format!("Failed to read file {}: {}", file_path, e);
-            error!("{}", error_message);
-            return Err(error_message);
+// Largest span decrypted per read call
+const DECRYPT_CHUNK_BLOCKS: u64 = 4096;
+
+impl DecryptingReader {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len().saturating_sub(16);
+        Ok(DecryptingReader { file, cipher: Aes256::new(&secret_key()), len, pos: 0 })
+    }
+}
+
+impl Read for DecryptingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Ok(0);
         }
-    };
+        let first_block = self.pos / 16;
+        let skip = (self.pos % 16) as usize;
+        let wanted = (buf.len() as u64).min(self.len - self.pos);
+        let blocks = ((skip as u64 + wanted).div_ceil(16)).min(DECRYPT_CHUNK_BLOCKS);
 
-    // Find the end of the </quiz> tag and truncate the content
-    let contents = if let Some(end_index) = contents.rfind("</quiz>") {
-        contents[..(end_index + "</quiz>".len())].to_string()
+        // The IV or previous ciphertext block, followed by the blocks to decrypt
+        let mut data = Vec::with_capacity(((blocks + 1) * 16) as usize);
+        self.file.seek(SeekFrom::Start(first_block * 16))?;
+        (&mut self.file).take((blocks + 1) * 16).read_to_end(&mut data)?;
+
+        let mut plain = Vec::with_capacity(data.len());
+        for i in 0..blocks as usize {
+            let start = (i + 1) * 16;
+            if start >= data.len() {
+                break;
+            }
+            let end = (start + 16).min(data.len());
+            // A trailing partial block is zero-padded, as decrypt_bytes does
+            let mut block = [0u8; 16];
+            block[..end - start].copy_from_slice(&data[start..end]);
+            let mut block = GenericArray::from(block);
+            self.cipher.decrypt_block(&mut block);
+            for (byte, prev) in block.iter_mut().zip(&data[i * 16..start]) {
+                *byte ^= prev;
+            }
+            plain.extend_from_slice(&block[..end - start]);
+        }
+
+        let available = plain.len().saturating_sub(skip).min(wanted as usize);
+        buf[..available].copy_from_slice(&plain[skip..skip + available]);
+        self.pos += available as u64;
+        Ok(available)
+    }
+}
+
+impl Seek for DecryptingReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        match target {
+            Some(target) => {
+                self.pos = target;
+                Ok(target)
+            }
+            None => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek before start of file")),
+        }
+    }
+}
+
+pub trait ReadSeek: Read + Seek + Send {}
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+// Opens a content file for reading its plaintext, decrypting on the fly when needed
+pub fn open_content(path: &Path) -> Result<Box<dyn ReadSeek>, String> {
+    let reader: Box<dyn ReadSeek> = if looks_encrypted(path) {
+        Box::new(DecryptingReader::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?)
     } else {
-        let error_message = format!("Invalid XML format: Missing </quiz> tag in {}", file_path);
-        error!("{}", error_message);
-        return Err(error_message);
+        Box::new(File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?)
     };
+    Ok(reader)
+}
 
-    info!("Contents: {}", contents);
+// True when a content file does not start like a plain file of its type, which for
+// shipped content means it was encrypted. Unknown types are reported as plain.
+pub fn looks_encrypted(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut header = [0u8; 16];
+    let read = match File::open(path).and_then(|mut file| file.read(&mut header)) {
+        Ok(read) => read,
+        Err(_) => return false,
+    };
+    let header = &header[..read];
+    let plain = match extension.as_str() {
+        "pdf" => header.starts_with(b"%PDF"),
+        "mp4" | "m4v" | "mov" => {
+            header.len() >= 8 && [b"ftyp", b"moov", b"mdat", b"free", b"wide"].contains(&&[header[4], header[5], header[6], header[7]])
+        }
+        "webm" => header.starts_with(&[0x1a, 0x45, 0xdf, 0xa3]),
+        "xml" => is_plain_xml(header),
+        _ => return false,
+    };
+    !plain && !header.is_empty()
+}
+
+// True when `data` starts like an XML document: a declaration, a comment or the <quiz> root,
+// after an optional byte order mark. Encrypted files start with a random IV, whose first
+// byte alone is '<' once in 256 files, so a single character is not enough.
+fn is_plain_xml(data: &[u8]) -> bool {
+    let data = data.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(data);
+    let start = data.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(data.len());
+    [b"<?xml".as_slice(), b"<!--", b"<quiz"].iter().any(|prefix| data[start..].starts_with(prefix))
+}
+
+// Reads a quiz file that may be shipped either encrypted or as plain XML,
+// returning the XML up to the closing </quiz> tag
+pub fn read_quiz_xml(path: &Path) -> Result<String, String> {
+    let data = std::fs::read(path).map_err(|e| {
+        error!("Failed to read quiz file {}: {}", path.display(), e);
+        format!("Failed to read file {}: {}", path.display(), e)
+    })?;
+    let data = if is_plain_xml(&data) { data } else { decrypt_bytes(&data)? };
+    let contents = String::from_utf8_lossy(&data);
+    truncate_to_quiz_end(&contents)
+        .map(|xml| xml.to_string())
+        .ok_or_else(|| format!("Invalid XML format: Missing </quiz> tag in {}", path.display()))
+}
+
+// Decrypted quiz files carry trailing padding after the closing tag
+fn truncate_to_quiz_end(contents: &str) -> Option<&str> {
+    contents
+        .rfind("</quiz>")
+        .map(|end_index| &contents[..(end_index + "</quiz>".len())])
+}
+
+// Decrypted copies were written here before content opened in the in-app viewer. Any
+// left behind are removed at startup, so plain copies do not outlive an upgrade.
+pub fn remove_decrypted_copies() {
+    let dir = env::temp_dir().join(DECRYPTED_DIR);
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            error!("Failed to remove decrypted copies in {}: {}", dir.display(), e);
+        }
+    }
+}
+
+// Parses a quiz inside the registered content roots, decrypting it in memory if needed
+#[tauri::command]
+pub async fn parse_xml_quiz(
+    database: State<'_, Database>,
+    file_path: String,
+    language: Option<String>,
+) -> Result<serde_json::Value, String> {
+    info!("Attempting to parse XML quiz from: {} (language: {:?})", file_path, language);
+
+    let contents = database
+        .run(move |db| {
+            let source = resolve_in_content_roots(db, &file_path)?;
+            require_visible(db, &source)?;
+            read_quiz_xml(&source)
+        })
+        .await?;
+
+    let quiz_data = parse_quiz_contents(&contents, language.as_deref())?;
+
+    info!("Successfully parsed XML quiz with {} questions.", quiz_data.questions.len());
+    Ok(json!(quiz_data))
+}
+
+// Alternatives for one question, collected before the language is resolved
+#[derive(Default)]
+struct LocalizedQuestion {
+    text: LocalizedText,
+    options: LocalizedText,
+    correct_answer: LocalizedText,
+    description: LocalizedText,
+}
+
+impl LocalizedQuestion {
+    fn resolve(&self, language: Option<&str>) -> Question {
+        // The answer is matched against the options by text, so both fall back together
+        let (options, correct_answer) = match self.options.resolve_together(&self.correct_answer, language) {
+            Some((options, answers)) => (options, answers.into_iter().next().unwrap_or_default()),
+            // No options, or no language the options share with the answer
+            None => (self.options.resolve_all(language), self.correct_answer.resolve(language).unwrap_or_default()),
+        };
+        Question {
+            text: self.text.resolve(language).unwrap_or_default(),
+            options,
+            correct_answer,
+            description: self.description.resolve(language),
+        }
+    }
+}
+
+// Parses quiz XML, picking `xml:lang` alternatives for the requested language.
+// `xml:lang` is inherited, so it may be set on <quiz>, <question>, <options> or the leaf elements.
+pub fn parse_quiz_contents(contents: &str, language: Option<&str>) -> Result<QuizData, String> {
     let reader = EventReader::new(BufReader::new(contents.trim().as_bytes()));
     let mut quiz_data = QuizData::default();
-    let mut current_question = Question::default();
+    let mut current_question = LocalizedQuestion::default();
     let mut parsing_options = false;
     let mut current_element = String::new();
+    let mut lang_stack: Vec<Option<String>> = Vec::new();
 
     for e in reader {
         match e {
-            Ok(XmlEvent::StartElement { name, .. }) => {
+            Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                let inherited = lang_stack.last().cloned().flatten();
+                lang_stack.push(xml_lang(&attributes).or(inherited));
                 current_element = name.local_name;
                 if current_element == "question" {
-                    current_question = Question::default();
+                    current_question = LocalizedQuestion::default();
                 } else if current_element == "options" {
                     parsing_options = true;
                 }
             }
             Ok(XmlEvent::Characters(text)) => {
+                let lang = lang_stack.last().cloned().flatten();
                 match current_element.as_str() {
-                    "text" => current_question.text = text,
-                    "option" if parsing_options => {
-                        if text != "-" {
-                            current_question.options.push(text);
-                        }
-                    }
-                    "correctAnswer" => current_question.correct_answer = text,
-                    "description" => {
-                        if text != "-" {
-                            current_question.description = Some(text);
-                        }
-                    }
+                    "text" => current_question.text.push(lang, text),
+                    "option" if parsing_options && text != "-" => current_question.options.push(lang, text),
+                    "correctAnswer" => current_question.correct_answer.push(lang, text),
+                    "description" if text != "-" => current_question.description.push(lang, text),
                     _ => (),
                 }
             }
             Ok(XmlEvent::EndElement { name }) => {
+                lang_stack.pop();
                 if name.local_name == "question" {
-                    quiz_data.questions.push(current_question.clone());
+                    quiz_data.questions.push(current_question.resolve(language));
                 } else if name.local_name == "options" {
                     parsing_options = false;
                 }
@@ -193,6 +343,5 @@ pub async fn parse_xml_quiz(file_path: String) -> Result<serde_json::Value, Stri
         }
     }
 
-    info!("Successfully parsed XML quiz with {} questions.", quiz_data.questions.len());
-    Ok(json!(quiz_data))
-} 
\ No newline at end of file
+    Ok(quiz_data)
+}
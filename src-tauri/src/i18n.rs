@@ -0,0 +1,210 @@
+// src-tauri/src/i18n.rs
+
+use xml::attribute::OwnedAttribute;
+
+// Language used when the caller does not ask for one, and the last tagged fallback
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// Text with alternatives tagged by `xml:lang`. Untagged text is stored with a `None` language.
+#[derive(Debug, Default, Clone)]
+pub struct LocalizedText {
+    variants: Vec<(Option<String>, String)>,
+}
+
+impl LocalizedText {
+    pub fn push(&mut self, lang: Option<String>, text: String) {
+        self.variants.push((lang.map(|l| normalize_tag(&l)), text));
+    }
+
+    // Picks the single best variant for the requested language
+    pub fn resolve(&self, language: Option<&str>) -> Option<String> {
+        self.resolve_all(language).into_iter().next()
+    }
+
+    // Picks every variant of the best matching language, preserving document order.
+    // Used for lists such as quiz options where each language carries its own set.
+    pub fn resolve_all(&self, language: Option<&str>) -> Vec<String> {
+        for candidate in fallback_chain(language) {
+            let matches = self.matching(candidate.as_deref());
+            if !matches.is_empty() {
+                return matches;
+            }
+        }
+
+        // Nothing in the chain matched, so use whatever language appears first
+        match self.variants.first() {
+            Some((first_lang, _)) => self
+                .variants
+                .iter()
+                .filter(|(lang, _)| lang == first_lang)
+                .map(|(_, text)| text.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Resolves two texts that are compared with each other, such as quiz options and the
+    // correct answer, so both come from the same language. None when they share no language.
+    pub fn resolve_together(&self, other: &LocalizedText, language: Option<&str>) -> Option<(Vec<String>, Vec<String>)> {
+        let mut candidates = fallback_chain(language);
+        // After the chain, any language both carry, in document order
+        candidates.extend(self.variants.iter().map(|(lang, _)| lang.clone()));
+        candidates.into_iter().find_map(|candidate| {
+            let ours = self.matching(candidate.as_deref());
+            let theirs = other.matching(candidate.as_deref());
+            (!ours.is_empty() && !theirs.is_empty()).then_some((ours, theirs))
+        })
+    }
+
+    fn matching(&self, candidate: Option<&str>) -> Vec<String> {
+        self.variants
+            .iter()
+            .filter(|(lang, _)| lang_matches(lang.as_deref(), candidate))
+            .map(|(_, text)| text.clone())
+            .collect()
+    }
+}
+
+// Order in which languages are tried: exact tag, primary subtag, untagged text, then English.
+pub fn fallback_chain(language: Option<&str>) -> Vec<Option<String>> {
+    let mut chain: Vec<Option<String>> = Vec::new();
+    if let Some(requested) = language.map(normalize_tag).filter(|l| !l.is_empty()) {
+        let primary = primary_subtag(&requested).to_string();
+        chain.push(Some(requested.clone()));
+        if primary != requested {
+            chain.push(Some(primary));
+        }
+    }
+    chain.push(None);
+    if !chain.contains(&Some(DEFAULT_LANGUAGE.to_string())) {
+        chain.push(Some(DEFAULT_LANGUAGE.to_string()));
+    }
+    chain
+}
+
+// Reads the `xml:lang` attribute of an element, if present
+pub fn xml_lang(attributes: &[OwnedAttribute]) -> Option<String> {
+    attributes
+        .iter()
+        .find(|attr| attr.name.prefix.as_deref() == Some("xml") && attr.name.local_name == "lang")
+        .map(|attr| normalize_tag(&attr.value))
+        .filter(|lang| !lang.is_empty())
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().replace('_', "-").to_lowercase()
+}
+
+fn primary_subtag(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+// A tagged variant such as "kn-in" also satisfies a request for the bare "kn"
+fn lang_matches(variant: Option<&str>, candidate: Option<&str>) -> bool {
+    match (variant, candidate) {
+        (None, None) => true,
+        (Some(variant), Some(candidate)) => {
+            variant == candidate || (!candidate.contains('-') && primary_subtag(variant) == candidate)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xml::reader::{EventReader, XmlEvent};
+
+    fn text(variants: &[(Option<&str>, &str)]) -> LocalizedText {
+        let mut text = LocalizedText::default();
+        for (lang, value) in variants {
+            text.push(lang.map(str::to_string), value.to_string());
+        }
+        text
+    }
+
+    fn first_element_lang(xml: &str) -> Option<String> {
+        EventReader::new(xml.as_bytes())
+            .into_iter()
+            .find_map(|event| match event {
+                Ok(XmlEvent::StartElement { attributes, .. }) => Some(xml_lang(&attributes)),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    #[test]
+    fn xml_lang_reads_and_normalizes_the_attribute() {
+        assert_eq!(first_element_lang(r#"<option xml:lang="kn_IN">a</option>"#), Some("kn-in".to_string()));
+        assert_eq!(first_element_lang(r#"<option xml:lang=" HI ">a</option>"#), Some("hi".to_string()));
+        assert_eq!(first_element_lang(r#"<option xml:lang="">a</option>"#), None);
+        // Only the xml: prefix counts
+        assert_eq!(first_element_lang(r#"<option lang="kn">a</option>"#), None);
+    }
+
+    #[test]
+    fn fallback_chain_goes_from_exact_tag_to_english() {
+        assert_eq!(
+            fallback_chain(Some("kn_IN")),
+            vec![Some("kn-in".to_string()), Some("kn".to_string()), None, Some("en".to_string())]
+        );
+        assert_eq!(fallback_chain(Some("en")), vec![Some("en".to_string()), None]);
+        assert_eq!(fallback_chain(None), vec![None, Some("en".to_string())]);
+    }
+
+    #[test]
+    fn resolve_prefers_exact_tag_then_primary_subtag() {
+        let question = text(&[(None, "plain"), (Some("kn"), "kannada"), (Some("kn-IN"), "kannada india")]);
+        assert_eq!(question.resolve(Some("kn-IN")).as_deref(), Some("kannada india"));
+        assert_eq!(question.resolve(Some("kn")).as_deref(), Some("kannada"));
+        assert_eq!(question.resolve(Some("hi")).as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn bare_request_matches_regional_variant() {
+        let question = text(&[(Some("en"), "english"), (Some("kn-IN"), "kannada")]);
+        assert_eq!(question.resolve(Some("kn")).as_deref(), Some("kannada"));
+        // Another region of the same language is found through the primary subtag
+        assert_eq!(question.resolve(Some("kn-XX")).as_deref(), Some("kannada"));
+        assert_eq!(question.resolve(Some("ta")).as_deref(), Some("english"));
+    }
+
+    #[test]
+    fn resolve_falls_back_to_first_language_in_the_document() {
+        let question = text(&[(Some("hi"), "one"), (Some("ta"), "two"), (Some("hi"), "three")]);
+        assert_eq!(question.resolve_all(Some("kn")), vec!["one", "three"]);
+        assert!(LocalizedText::default().resolve(Some("kn")).is_none());
+    }
+
+    #[test]
+    fn resolve_all_keeps_every_option_of_one_language() {
+        let options = text(&[(Some("en"), "A"), (Some("kn"), "ಎ"), (Some("en"), "B"), (Some("kn"), "ಬಿ")]);
+        assert_eq!(options.resolve_all(Some("kn")), vec!["ಎ", "ಬಿ"]);
+        assert_eq!(options.resolve_all(None), vec!["A", "B"]);
+    }
+
+    #[test]
+    fn resolve_together_picks_a_language_both_texts_have() {
+        let options = text(&[(Some("en"), "A"), (Some("en"), "B"), (Some("kn"), "ಎ"), (Some("kn"), "ಬಿ")]);
+        // The answer has no Kannada variant, so both sides use English
+        let answer = text(&[(Some("en"), "B"), (Some("hi"), "बी")]);
+        assert_eq!(
+            options.resolve_together(&answer, Some("kn")),
+            Some((vec!["A".to_string(), "B".to_string()], vec!["B".to_string()]))
+        );
+
+        let answer = text(&[(Some("kn"), "ಬಿ"), (Some("en"), "B")]);
+        assert_eq!(
+            options.resolve_together(&answer, Some("kn")),
+            Some((vec!["ಎ".to_string(), "ಬಿ".to_string()], vec!["ಬಿ".to_string()]))
+        );
+    }
+
+    #[test]
+    fn resolve_together_uses_any_shared_language_outside_the_chain() {
+        let options = text(&[(Some("ta"), "அ"), (Some("hi"), "अ")]);
+        let answer = text(&[(Some("hi"), "अ")]);
+        assert_eq!(options.resolve_together(&answer, Some("kn")), Some((vec!["अ".to_string()], vec!["अ".to_string()])));
+        assert_eq!(options.resolve_together(&text(&[(Some("kn"), "x")]), Some("kn")), None);
+    }
+}
@@ -0,0 +1,727 @@
+// src-tauri/src/indexer.rs
+
+use crate::content::get_active_content_root;
+use crate::db::Database;
+use crate::manifest::{self, LoadedManifest};
+use crate::roles::{require_permission, visible_content_types, Permission};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::State;
+use walkdir::WalkDir;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "webm", "m4v"];
+// A folder holding either of these is an HTML5 or SCORM package, indexed as one item
+pub const SCORM_MANIFEST: &str = "imsmanifest.xml";
+pub const PACKAGE_ENTRY_PAGE: &str = "index.html";
+
+// Categories the content browser shows as tabs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    Video,
+    Animation,
+    Notes,
+    Textbook,
+    Quiz,
+    Activity,
+    Assessment,
+    Interactive, // HTML5 or SCORM package folder
+}
+
+impl ContentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentType::Video => "video",
+            ContentType::Animation => "animation",
+            ContentType::Notes => "notes",
+            ContentType::Textbook => "textbook",
+            ContentType::Quiz => "quiz",
+            ContentType::Activity => "activity",
+            ContentType::Assessment => "assessment",
+            ContentType::Interactive => "interactive",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<ContentType> {
+        match value {
+            "video" => Some(ContentType::Video),
+            "animation" => Some(ContentType::Animation),
+            "notes" => Some(ContentType::Notes),
+            "textbook" => Some(ContentType::Textbook),
+            "quiz" => Some(ContentType::Quiz),
+            "activity" => Some(ContentType::Activity),
+            "assessment" => Some(ContentType::Assessment),
+            "interactive" => Some(ContentType::Interactive),
+            _ => None,
+        }
+    }
+}
+
+// Classifies a file by extension and the filename keywords used by content publishers.
+// Returns None for files that are not content (images, sidecar XML, etc.).
+pub fn classify_file(file_name: &str) -> Option<ContentType> {
+    let lower = file_name.to_lowercase();
+    let extension = Path::new(&lower).extension()?.to_str()?.to_string();
+
+    if extension == "pdf" {
+        if lower.contains("textbook") {
+            Some(ContentType::Textbook)
+        } else if lower.contains("assessment") {
+            Some(ContentType::Assessment)
+        } else if lower.contains("activities") {
+            Some(ContentType::Activity)
+        } else {
+            Some(ContentType::Notes)
+        }
+    } else if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        if lower.contains("animated") {
+            Some(ContentType::Animation)
+        } else {
+            Some(ContentType::Video)
+        }
+    } else if extension == "xml" && lower != "titles.xml" && lower != SCORM_MANIFEST {
+        Some(ContentType::Quiz)
+    } else {
+        None
+    }
+}
+
+// True for package folders, whose files are not content items of their own
+pub fn is_package_dir(path: &Path) -> bool {
+    path.join(SCORM_MANIFEST).is_file() || path.join(PACKAGE_ENTRY_PAGE).is_file()
+}
+
+// Display title derived from a filename: extension and category keywords removed
+pub fn title_from_file_name(file_name: &str) -> String {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name);
+    let mut title = stem.replace('.', " ");
+    for keyword in ["animated", "keypoints", "assessment", "textbook", "activities"] {
+        title = replace_ignore_case(&title, keyword);
+    }
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        stem.to_string()
+    } else {
+        title
+    }
+}
+
+fn replace_ignore_case(text: &str, keyword: &str) -> String {
+    let lower = text.to_lowercase();
+    // Keywords are ASCII, so byte offsets in the lowercased copy line up with the original
+    if lower.len() != text.len() {
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in lower.match_indices(keyword) {
+        result.push_str(&text[last..start]);
+        last = start + keyword.len();
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentItem {
+    pub id: i64,
+    pub folder_id: i64,
+    pub title: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+    pub content_type: ContentType,
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub chapter: Option<String>,
+    pub size: i64,
+    pub modified_at: i64,
+    pub description: Option<String>,
+    pub sort_order: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ContentItemFilter {
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub chapter: Option<String>,
+    pub content_type: Option<ContentType>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContentHierarchyEntry {
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub chapter: Option<String>,
+    pub item_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct IndexSummary {
+    pub added: usize,
+    pub updated: usize,
+    // Items no longer on disk. Their rows are marked missing, not deleted.
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+// A file found on disk, before it is written to ContentItems
+pub(crate) struct ScannedFile {
+    pub relative_path: String,
+    pub title: String,
+    pub content_type: ContentType,
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub chapter: Option<String>,
+    pub size: i64,
+    pub modified_at: i64,
+    pub description: Option<String>,
+    pub sort_order: Option<i64>,
+}
+
+// Columns selected by content_item_from_row, in order
+pub(crate) const ITEM_COLUMNS: &str =
+    "id, folder_id, title, relative_path, content_type, class_name, subject, chapter, size, modified_at, description, sort_order";
+
+// Splits a path relative to the content root into class/subject/chapter.
+// Layout is <class>/<subject>/.../<chapter>/<file>; any folder deeper than the subject
+// that directly holds the file is treated as the chapter.
+fn hierarchy_from_relative(relative: &Path) -> (Option<String>, Option<String>, Option<String>) {
+    let dirs: Vec<String> = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    let class_name = dirs.first().cloned();
+    let subject = dirs.get(1).cloned();
+    let chapter = if dirs.len() > 2 { dirs.last().cloned() } else { None };
+    (class_name, subject, chapter)
+}
+
+// Reads metadata for a single file under `root`, returning None for non-content files.
+// Titles, types and order declared in the manifest win over filename conventions.
+pub(crate) fn scan_file(root: &Path, path: &Path, manifest: Option<&LoadedManifest>) -> Option<ScannedFile> {
+    let file_name = path.file_name()?.to_str()?;
+    if file_name.starts_with('.') {
+        return None;
+    }
+    let relative = path.strip_prefix(root).ok()?;
+    let relative_path = relative.to_string_lossy().replace('\\', "/");
+    let declared = manifest.and_then(|m| m.entry(&relative_path));
+    let content_type = declared
+        .and_then(|meta| meta.content_type)
+        .or_else(|| classify_file(file_name))?;
+    let metadata = path.metadata().ok()?;
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let (class_name, subject, chapter) = hierarchy_from_relative(relative);
+
+    Some(ScannedFile {
+        title: declared
+            .and_then(|meta| meta.title.resolve(None))
+            .unwrap_or_else(|| title_from_file_name(file_name)),
+        relative_path,
+        content_type,
+        class_name,
+        subject,
+        chapter,
+        size: metadata.len() as i64,
+        modified_at,
+        description: declared.and_then(|meta| meta.description.clone()),
+        sort_order: declared.and_then(|meta| meta.order),
+    })
+}
+
+// Reads metadata for a package folder under `root`. Size and modification time are those
+// of its SCORM manifest or entry page, which change whenever the package is republished.
+fn scan_package(root: &Path, path: &Path, manifest: Option<&LoadedManifest>) -> Option<ScannedFile> {
+    let folder_name = path.file_name()?.to_str()?;
+    let relative = path.strip_prefix(root).ok()?;
+    let relative_path = relative.to_string_lossy().replace('\\', "/");
+    let declared = manifest.and_then(|m| m.entry(&relative_path));
+    let marker = [SCORM_MANIFEST, PACKAGE_ENTRY_PAGE].iter().map(|name| path.join(name)).find(|p| p.is_file())?;
+    let metadata = marker.metadata().ok()?;
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let (class_name, subject, chapter) = hierarchy_from_relative(relative);
+
+    Some(ScannedFile {
+        title: declared
+            .and_then(|meta| meta.title.resolve(None))
+            .unwrap_or_else(|| folder_name.replace('_', " ")),
+        relative_path,
+        content_type: ContentType::Interactive,
+        class_name,
+        subject,
+        chapter,
+        size: metadata.len() as i64,
+        modified_at,
+        description: declared.and_then(|meta| meta.description.clone()),
+        sort_order: declared.and_then(|meta| meta.order),
+    })
+}
+
+// Walks `dir` (the content root or a subtree of it) and returns every content file found.
+// Package folders are returned as one item and not walked into.
+pub(crate) fn scan_tree(root: &Path, dir: &Path) -> Vec<ScannedFile> {
+    let manifest = manifest::load_cached(root);
+    WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || (!entry.file_name().to_string_lossy().starts_with('.')
+                    && !entry.path().parent().is_some_and(|parent| parent != root && is_package_dir(parent)))
+        })
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping unreadable entry while indexing: {}", e);
+                None
+            }
+        })
+        .filter_map(|entry| {
+            if entry.file_type().is_file() {
+                scan_file(root, entry.path(), manifest.as_deref())
+            } else if entry.file_type().is_dir() && entry.path() != root && is_package_dir(entry.path()) {
+                scan_package(root, entry.path(), manifest.as_deref())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// What ContentItems already holds for a file, to skip rows that have not changed
+struct StoredItem {
+    id: i64,
+    size: i64,
+    modified_at: i64,
+    title: String,
+    content_type: Option<String>,
+    description: Option<String>,
+    sort_order: Option<i64>,
+    missing: bool,
+}
+
+impl StoredItem {
+    fn matches(&self, file: &ScannedFile) -> bool {
+        self.size == file.size
+            && self.modified_at == file.modified_at
+            && self.title == file.title
+            && self.content_type.as_deref() == Some(file.content_type.as_str())
+            && self.description == file.description
+            && self.sort_order == file.sort_order
+    }
+}
+
+// Writes scanned files into ContentItems and marks rows under `prefix` that were not seen
+// as missing. An empty prefix covers the whole content root.
+pub(crate) fn sync_items(
+    db: &mut Connection,
+    folder_id: i64,
+    prefix: &str,
+    files: &[ScannedFile],
+) -> Result<IndexSummary, String> {
+    let mut summary = IndexSummary::default();
+    let tx = db.transaction().map_err(|e| {
+        error!("Failed to start transaction for content indexing: {}", e);
+        e.to_string()
+    })?;
+
+    {
+        let mut existing: HashMap<String, StoredItem> = HashMap::new();
+        let mut stmt = tx
+            .prepare_cached(
+                "SELECT id, relative_path, size, modified_at, title, content_type, description, sort_order,
+                        missing_at IS NOT NULL
+                 FROM ContentItems
+                 WHERE folder_id = ?1
+                   AND (?2 = '' OR substr(relative_path, 1, length(?2) + 1) = ?2 || '/')",
+            )
+            .map_err(|e| {
+                error!("Failed to prepare statement for existing content items: {}", e);
+                e.to_string()
+            })?;
+        let rows = stmt
+            .query_map(params![folder_id, prefix], |row| {
+                Ok((
+                    row.get::<_, String>(1)?,
+                    StoredItem {
+                        id: row.get(0)?,
+                        size: row.get::<_, Option<i64>>(2)?.unwrap_or(-1),
+                        modified_at: row.get::<_, Option<i64>>(3)?.unwrap_or(-1),
+                        title: row.get(4)?,
+                        content_type: row.get(5)?,
+                        description: row.get(6)?,
+                        sort_order: row.get(7)?,
+                        missing: row.get(8)?,
+                    },
+                ))
+            })
+            .map_err(|e| {
+                error!("Failed to query existing content items: {}", e);
+                e.to_string()
+            })?;
+        for row in rows {
+            let (path, values) = row.map_err(|e| {
+                error!("Failed to read existing content item: {}", e);
+                e.to_string()
+            })?;
+            existing.insert(path, values);
+        }
+
+        // Rows whose file is gone are kept, with the progress recorded on them, so a rename,
+        // a move or a drive that briefly failed to mount loses nothing. They are only
+        // deleted along with their content path.
+        let seen: HashSet<&str> = files.iter().map(|file| file.relative_path.as_str()).collect();
+        for (path, stored) in &existing {
+            if stored.missing || seen.contains(path.as_str()) {
+                continue;
+            }
+            tx.execute("UPDATE ContentItems SET missing_at = CURRENT_TIMESTAMP WHERE id = ?1", params![stored.id])
+                .map_err(|e| {
+                    error!("Failed to mark content item {} as missing: {}", path, e);
+                    e.to_string()
+                })?;
+            summary.removed += 1;
+        }
+
+        for file in files {
+            // Whether the file already has a row, found by its path or as a moved item
+            let known = match existing.remove(&file.relative_path) {
+                Some(stored) if !stored.missing && stored.matches(file) => {
+                    summary.unchanged += 1;
+                    continue;
+                }
+                Some(_) => true,
+                None => match find_moved_item(&tx, folder_id, file)? {
+                    Some(id) => {
+                        info!("Content item {} moved to {}", id, file.relative_path);
+                        tx.execute(
+                            "UPDATE ContentItems SET relative_path = ?2 WHERE id = ?1",
+                            params![id, file.relative_path],
+                        )
+                        .map_err(|e| {
+                            error!("Failed to move content item {} to {}: {}", id, file.relative_path, e);
+                            e.to_string()
+                        })?;
+                        true
+                    }
+                    None => false,
+                },
+            };
+            tx.execute(
+                "INSERT INTO ContentItems
+                    (folder_id, title, relative_path, content_type, class_name, subject, chapter, size, modified_at,
+                     description, sort_order)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT(folder_id, relative_path) DO UPDATE SET
+                    title = excluded.title,
+                    content_type = excluded.content_type,
+                    class_name = excluded.class_name,
+                    subject = excluded.subject,
+                    chapter = excluded.chapter,
+                    size = excluded.size,
+                    modified_at = excluded.modified_at,
+                    description = excluded.description,
+                    sort_order = excluded.sort_order,
+                    missing_at = NULL,
+                    updated_at = CURRENT_TIMESTAMP",
+                params![
+                    folder_id,
+                    file.title,
+                    file.relative_path,
+                    file.content_type.as_str(),
+                    file.class_name,
+                    file.subject,
+                    file.chapter,
+                    file.size,
+                    file.modified_at,
+                    file.description,
+                    file.sort_order,
+                ],
+            )
+            .map_err(|e| {
+                error!("Failed to write content item {}: {}", file.relative_path, e);
+                e.to_string()
+            })?;
+            if known {
+                summary.updated += 1;
+            } else {
+                summary.added += 1;
+            }
+        }
+    }
+
+    tx.commit().map_err(|e| {
+        error!("Failed to commit content index transaction: {}", e);
+        e.to_string()
+    })?;
+    Ok(summary)
+}
+
+// Missing item that `file` is most likely a move or rename of: one with the same type, size
+// and modification time, which a move keeps. Nothing is matched when several items qualify.
+fn find_moved_item(db: &Connection, folder_id: i64, file: &ScannedFile) -> Result<Option<i64>, String> {
+    let mut stmt = db
+        .prepare_cached(
+            "SELECT id FROM ContentItems
+             WHERE folder_id = ?1 AND missing_at IS NOT NULL AND content_type = ?2 AND size = ?3 AND modified_at = ?4
+             LIMIT 2",
+        )
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map(params![folder_id, file.content_type.as_str(), file.size, file.modified_at], |row| row.get(0))
+        .and_then(|rows| rows.collect::<Result<Vec<i64>>>())
+        .map_err(|e| {
+            error!("Failed to look for a moved content item for {}: {}", file.relative_path, e);
+            e.to_string()
+        })?;
+    Ok(match ids.as_slice() {
+        [id] => Some(*id),
+        _ => None,
+    })
+}
+
+// Rebuilds the index for one registered content path. The tree is scanned before a
+// connection is taken, so the pool is only held for the writes.
+pub fn index_content_path(database: &Database, folder_id: i64, root: &Path) -> Result<IndexSummary, String> {
+    info!("Indexing content path {} ({})", folder_id, root.display());
+    if !root.is_dir() {
+        warn!("Content path is not a directory: {}", root.display());
+        return Err(format!("Content path is not a directory: {}", root.display()));
+    }
+
+    let files = scan_tree(root, root);
+    let summary = sync_items(&mut *database.connection()?, folder_id, "", &files)?;
+    info!(
+        "Indexed content path {}: {} added, {} updated, {} removed, {} unchanged",
+        folder_id, summary.added, summary.updated, summary.removed, summary.unchanged
+    );
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn index_active_content_path(database: State<'_, Database>) -> Result<IndexSummary, String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Indexing active content path.");
+    database
+        .run_blocking(|database| {
+            let (folder_id, root) = get_active_content_root(&*database.connection()?)?
+                .ok_or_else(|| "No active content path.".to_string())?;
+            index_content_path(database, folder_id, Path::new(&root))
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_content_items(database: State<'_, Database>, filter: Option<ContentItemFilter>) -> Result<Vec<ContentItem>, String> {
+    let filter = filter.unwrap_or_default();
+    info!("Retrieving content items with filter: {:?}", filter);
+    database
+        .run(move |db| {
+            let (folder_id, root) = match get_active_content_root(db)? {
+                Some(active) => active,
+                None => return Ok(Vec::new()),
+            };
+
+            let mut stmt = db
+                .prepare(&format!(
+                    "SELECT {} FROM ContentItems
+                     WHERE folder_id = ?1 AND missing_at IS NULL
+                       AND (?2 IS NULL OR class_name = ?2)
+                       AND (?3 IS NULL OR subject = ?3)
+                       AND (?4 IS NULL OR chapter = ?4)
+                       AND (?5 IS NULL OR content_type = ?5)
+                     ORDER BY class_name, subject, chapter, sort_order IS NULL, sort_order, title",
+                    ITEM_COLUMNS
+                ))
+                .map_err(|e| {
+                    error!("Failed to prepare statement for getting content items: {}", e);
+                    e.to_string()
+                })?;
+
+            let visible = visible_content_types(db)?;
+            let items = stmt
+                .query_map(
+                    params![
+                        folder_id,
+                        filter.class_name,
+                        filter.subject,
+                        filter.chapter,
+                        filter.content_type.map(|t| t.as_str()),
+                    ],
+                    |row| content_item_from_row(row, &root),
+                )
+                .map_err(|e| {
+                    error!("Failed to query map for content items: {}", e);
+                    e.to_string()
+                })?
+                .filter(|item| item.as_ref().map_or(true, |item| visible.contains(&item.content_type)))
+                .collect::<Result<Vec<ContentItem>, _>>()
+                .map_err(|e| {
+                    error!("Failed to collect content items: {}", e);
+                    e.to_string()
+                })?;
+
+            info!("Successfully retrieved {} content items.", items.len());
+            Ok(items)
+        })
+        .await
+}
+
+// Maps a row selected with ITEM_COLUMNS
+pub(crate) fn content_item_from_row(row: &rusqlite::Row, root: &str) -> Result<ContentItem> {
+    let relative_path: String = row.get(3)?;
+    let content_type: String = row.get(4)?;
+    Ok(ContentItem {
+        id: row.get(0)?,
+        folder_id: row.get(1)?,
+        title: row.get(2)?,
+        absolute_path: Path::new(root).join(&relative_path).to_string_lossy().into_owned(),
+        relative_path,
+        content_type: ContentType::parse(&content_type).ok_or_else(|| {
+            rusqlite::Error::InvalidColumnType(4, "content_type".to_string(), rusqlite::types::Type::Text)
+        })?,
+        class_name: row.get(5)?,
+        subject: row.get(6)?,
+        chapter: row.get(7)?,
+        size: row.get::<_, Option<i64>>(8)?.unwrap_or(0),
+        modified_at: row.get::<_, Option<i64>>(9)?.unwrap_or(0),
+        description: row.get(10)?,
+        sort_order: row.get(11)?,
+    })
+}
+
+#[tauri::command]
+pub async fn get_content_item(database: State<'_, Database>, id: i64) -> Result<Option<ContentItem>, String> {
+    info!("Retrieving content item with ID: {}", id);
+    database
+        .run(move |db| {
+            let (folder_id, root) = match get_active_content_root(db)? {
+                Some(active) => active,
+                None => return Ok(None),
+            };
+            let item = db
+                .query_row(
+                    &format!("SELECT {} FROM ContentItems WHERE id = ?1 AND folder_id = ?2 AND missing_at IS NULL", ITEM_COLUMNS),
+                    params![id, folder_id],
+                    |row| content_item_from_row(row, &root),
+                )
+                .optional()
+                .map_err(|e| {
+                    error!("Failed to query content item {}: {}", id, e);
+                    e.to_string()
+                })?;
+            // Items in categories hidden from the user's role are not found
+            let visible = visible_content_types(db)?;
+            Ok(item.filter(|item| visible.contains(&item.content_type)))
+        })
+        .await
+}
+
+// Indexed item of the active content path stored at `path`, which must be canonical
+pub fn find_content_item_id(db: &Connection, path: &Path) -> Result<Option<i64>, String> {
+    let Some((folder_id, root)) = get_active_content_root(db)? else {
+        return Ok(None);
+    };
+    let root = Path::new(&root).canonicalize().unwrap_or_else(|_| Path::new(&root).to_path_buf());
+    let Ok(relative) = path.strip_prefix(&root) else {
+        return Ok(None);
+    };
+    let relative_path = relative.to_string_lossy().replace('\\', "/");
+    db.query_row(
+        "SELECT id FROM ContentItems WHERE folder_id = ?1 AND relative_path = ?2 AND missing_at IS NULL",
+        params![folder_id, relative_path],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| {
+        error!("Failed to look up content item {}: {}", relative_path, e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn get_content_hierarchy(database: State<'_, Database>) -> Result<Vec<ContentHierarchyEntry>, String> {
+    info!("Retrieving content hierarchy.");
+    database
+        .run(|db| {
+            let (folder_id, _) = match get_active_content_root(db)? {
+                Some(active) => active,
+                None => return Ok(Vec::new()),
+            };
+            let mut stmt = db
+                .prepare_cached(
+                    "SELECT class_name, subject, chapter, content_type, COUNT(*) FROM ContentItems
+                     WHERE folder_id = ?1 AND missing_at IS NULL
+                     GROUP BY class_name, subject, chapter, content_type
+                     ORDER BY class_name, subject, chapter",
+                )
+                .map_err(|e| {
+                    error!("Failed to prepare statement for content hierarchy: {}", e);
+                    e.to_string()
+                })?;
+
+            // Counted per content type so categories hidden from the user's role can be left out
+            let visible = visible_content_types(db)?;
+            let counts = stmt
+                .query_map(params![folder_id], |row| {
+                    let content_type: String = row.get(3)?;
+                    let entry = ContentHierarchyEntry {
+                        class_name: row.get(0)?,
+                        subject: row.get(1)?,
+                        chapter: row.get(2)?,
+                        item_count: row.get(4)?,
+                    };
+                    Ok((ContentType::parse(&content_type), entry))
+                })
+                .map_err(|e| {
+                    error!("Failed to query map for content hierarchy: {}", e);
+                    e.to_string()
+                })?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| {
+                    error!("Failed to collect content hierarchy: {}", e);
+                    e.to_string()
+                })?;
+
+            let mut entries: Vec<ContentHierarchyEntry> = Vec::new();
+            for (content_type, entry) in counts {
+                if !content_type.is_some_and(|t| visible.contains(&t)) {
+                    continue;
+                }
+                match entries.last_mut() {
+                    Some(last)
+                        if last.class_name == entry.class_name && last.subject == entry.subject && last.chapter == entry.chapter =>
+                    {
+                        last.item_count += entry.item_count;
+                    }
+                    _ => entries.push(entry),
+                }
+            }
+
+            info!("Successfully retrieved {} hierarchy entries.", entries.len());
+            Ok(entries)
+        })
+        .await
+}
@@ -0,0 +1,305 @@
+// src-tauri/src/integrity.rs
+
+use crate::db::Database;
+use crate::manifest::{self, MANIFEST_JSON, MANIFEST_TOML};
+use log::{error, info, warn};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use walkdir::WalkDir;
+
+// Checksum list in `sha256sum` output format, shipped at the content root
+pub const CHECKSUM_FILE: &str = "SHA256SUMS";
+pub const VERIFY_PROGRESS_EVENT: &str = "content-verify-progress";
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifyMode {
+    // Hash every file
+    Full,
+    // Only check that listed files exist, with the declared size where the manifest gives one
+    Quick,
+}
+
+// What a file is expected to look like
+#[derive(Debug, Clone)]
+pub struct ExpectedFile {
+    pub sha256: String,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VerifyProgress {
+    pub root: String,
+    pub checked: usize,
+    pub total: usize,
+    pub current: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VerificationReport {
+    pub checked: usize,
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+    pub corrupt: Vec<String>,
+}
+
+impl VerificationReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.corrupt.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} missing, {} corrupt, {} extra of {} checked",
+            self.missing.len(),
+            self.corrupt.len(),
+            self.extra.len(),
+            self.checked
+        )
+    }
+}
+
+// Parses "<hex>  <path>" lines; a '*' before the path marks binary mode and is ignored
+pub fn parse_checksum_list(text: &str) -> Result<BTreeMap<String, ExpectedFile>, String> {
+    let mut expected = BTreeMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (hash, path) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("Malformed checksum line {}: {}", number + 1, line))?;
+        let path = path.trim_start().trim_start_matches('*');
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) || path.is_empty() {
+            return Err(format!("Malformed checksum line {}: {}", number + 1, line));
+        }
+        let path = path.replace('\\', "/").trim_start_matches("./").to_string();
+        // Paths are joined onto the root, so they must stay inside it
+        if !manifest::is_safe_relative(&path) {
+            return Err(format!("Checksum line {} names a path outside the package: {}", number + 1, path));
+        }
+        expected.insert(path, ExpectedFile { sha256: hash.to_lowercase(), size: None });
+    }
+    Ok(expected)
+}
+
+// Reads the expected checksums of a content root from SHA256SUMS, or failing that from
+// the manifest's per-file entries. Ok(None) means the root ships no checksums at all.
+pub fn load_expected(root: &Path) -> Result<Option<BTreeMap<String, ExpectedFile>>, String> {
+    let list_path = root.join(CHECKSUM_FILE);
+    let manifest = manifest::read_manifest(root).unwrap_or_else(|e| {
+        warn!("Ignoring manifest during verification: {}", e);
+        None
+    });
+    let manifest_sizes: BTreeMap<String, u64> = manifest
+        .as_ref()
+        .map(|m| {
+            m.files
+                .iter()
+                .filter_map(|f| Some((f.path.replace('\\', "/"), f.size?)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if list_path.is_file() {
+        let text = std::fs::read_to_string(&list_path).map_err(|e| {
+            error!("Failed to read checksum list {}: {}", list_path.display(), e);
+            format!("Failed to read checksum list: {}", e)
+        })?;
+        let mut expected = parse_checksum_list(&text)?;
+        for (path, file) in expected.iter_mut() {
+            file.size = manifest_sizes.get(path).copied();
+        }
+        return Ok(Some(expected));
+    }
+
+    let mut from_manifest = BTreeMap::new();
+    for file in manifest.map(|m| m.files).unwrap_or_default() {
+        let Some(sha256) = file.sha256 else { continue };
+        let path = file.path.replace('\\', "/");
+        if !manifest::is_safe_relative(&path) {
+            return Err(format!("The manifest names a file outside the package: {}", file.path));
+        }
+        from_manifest.insert(path, ExpectedFile { sha256: sha256.to_lowercase(), size: file.size });
+    }
+    Ok(if from_manifest.is_empty() { None } else { Some(from_manifest) })
+}
+
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+// Files that describe the package rather than belong to it
+fn is_package_metadata(relative: &str) -> bool {
+    matches!(relative, CHECKSUM_FILE | MANIFEST_JSON | MANIFEST_TOML)
+}
+
+// Compares the files under `root` against `expected`, reporting through `on_progress`
+pub fn verify_tree(
+    root: &Path,
+    expected: &BTreeMap<String, ExpectedFile>,
+    mode: VerifyMode,
+    on_progress: &mut dyn FnMut(&VerifyProgress),
+) -> VerificationReport {
+    let mut report = VerificationReport::default();
+    let total = expected.len();
+    let mut last_progress = Instant::now();
+    let root_display = root.to_string_lossy().into_owned();
+
+    for (relative, file) in expected {
+        let path = root.join(relative);
+        report.checked += 1;
+        match path.metadata() {
+            Ok(metadata) if metadata.is_file() => {
+                if file.size.is_some_and(|size| size != metadata.len()) {
+                    report.corrupt.push(relative.clone());
+                } else if mode == VerifyMode::Full {
+                    match sha256_file(&path) {
+                        Ok(hash) if hash == file.sha256 => (),
+                        Ok(_) => report.corrupt.push(relative.clone()),
+                        Err(e) => {
+                            warn!("Failed to hash {}: {}", path.display(), e);
+                            report.corrupt.push(relative.clone());
+                        }
+                    }
+                }
+            }
+            _ => report.missing.push(relative.clone()),
+        }
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL || report.checked == total {
+            last_progress = Instant::now();
+            on_progress(&VerifyProgress {
+                root: root_display.clone(),
+                checked: report.checked,
+                total,
+                current: Some(relative.clone()),
+            });
+        }
+    }
+
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+    {
+        if let Ok(relative) = entry.path().strip_prefix(root) {
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if !expected.contains_key(&relative) && !is_package_metadata(&relative) {
+                report.extra.push(relative);
+            }
+        }
+    }
+
+    report
+}
+
+// Quick check run before a content root is activated. Roots without checksums pass.
+pub fn quick_verify(root: &Path) -> Result<(), String> {
+    let Some(expected) = load_expected(root)? else {
+        info!("No checksums shipped with {}; skipping quick verification.", root.display());
+        return Ok(());
+    };
+    let report = verify_tree(root, &expected, VerifyMode::Quick, &mut |_| ());
+    info!("Quick verification of {}: {}", root.display(), report.summary());
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(format!("Content verification failed: {}", report.summary()))
+    }
+}
+
+#[tauri::command]
+pub async fn verify_content_path(
+    app: AppHandle,
+    database: State<'_, Database>,
+    id: i64,
+    mode: Option<VerifyMode>,
+) -> Result<VerificationReport, String> {
+    let mode = mode.unwrap_or(VerifyMode::Full);
+    info!("Verifying content path {} ({:?})", id, mode);
+    let root: String = database
+        .run(move |db| {
+            db.query_row("SELECT path FROM ContentPaths WHERE id = ?1", params![id], |row| row.get(0))
+                .map_err(|e| {
+                    warn!("Content path {} not found for verification: {}", id, e);
+                    format!("Content path with id {} not found.", id)
+                })
+        })
+        .await?;
+
+    // Hashing can take minutes, so it runs without holding a database connection
+    tokio::task::spawn_blocking(move || {
+        let root = Path::new(&root);
+        let expected = load_expected(root)?
+            .ok_or_else(|| format!("No {} or manifest checksums found in {}", CHECKSUM_FILE, root.display()))?;
+        let report = verify_tree(root, &expected, mode, &mut |progress| {
+            if let Err(e) = app.emit(VERIFY_PROGRESS_EVENT, progress.clone()) {
+                warn!("Failed to emit {} event: {}", VERIFY_PROGRESS_EVENT, e);
+            }
+        });
+        info!("Verification of {}: {}", root.display(), report.summary());
+        Ok(report)
+    })
+    .await
+    .map_err(|e| {
+        error!("Content verification task failed: {}", e);
+        e.to_string()
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn parse_checksum_list_reads_text_and_binary_lines() {
+        let text = format!("# made by sha256sum\r\n{}  Class 8/notes.pdf\r\n{} *.\\Class 8\\video.mp4\n\n", HASH, HASH.to_uppercase());
+        let expected = parse_checksum_list(&text).unwrap();
+        let paths: Vec<&str> = expected.keys().map(String::as_str).collect();
+        assert_eq!(paths, vec!["Class 8/notes.pdf", "Class 8/video.mp4"]);
+        assert!(expected.values().all(|file| file.sha256 == HASH));
+    }
+
+    #[test]
+    fn parse_checksum_list_refuses_paths_outside_the_package() {
+        for path in ["../outside.txt", "/etc/shadow", "Class 8/../../x", "/dev/zero"] {
+            let error = parse_checksum_list(&format!("{}  {}", HASH, path)).unwrap_err();
+            assert!(error.contains("outside the package"), "{}: {}", path, error);
+        }
+    }
+
+    #[test]
+    fn parse_checksum_list_refuses_malformed_lines() {
+        assert!(parse_checksum_list("abc  notes.pdf").is_err());
+        assert!(parse_checksum_list(HASH).is_err());
+    }
+}
@@ -4,17 +4,49 @@
 mod auth;
 mod content;
 mod db;
+mod dbcheck;
 mod encryption;
+mod i18n;
+mod indexer;
+mod integrity;
+mod manifest;
+mod media;
+mod mediainfo;
+mod package;
+mod pdftext;
+mod progress;
+mod recovery;
+mod roles;
+mod roster;
+mod scorm;
+mod search;
+mod thumbnail;
+mod update;
+mod usercsv;
 mod users;
+mod viewer;
+mod watcher;
+mod xapi;
 
 use log::{error, LevelFilter};
 use tauri_plugin_log::{Target, TargetKind, RotationStrategy, TimezoneStrategy};
 
 fn main() {
-    if let Err(e) = db::initialize_db() {
-        error!("Failed to initialize database: {}", e);
-        return;
+    let database = match db::Database::open() {
+        Ok(database) => database,
+        Err(e) => {
+            error!("Failed to initialize database: {}", e);
+            return;
+        }
+    };
+    // Before the watcher starts, so a half-updated tree is never indexed or served
+    if let Err(e) = update::recover_interrupted_updates(&database) {
+        error!("Failed to recover interrupted content updates: {}", e);
     }
+    if let Err(e) = package::recover_interrupted_imports() {
+        error!("Failed to recover interrupted content imports: {}", e);
+    }
+    encryption::remove_decrypted_copies();
 
     tauri::Builder::default()
         .plugin(
@@ -36,27 +68,87 @@ fn main() {
                 .build(),
         )
         .plugin(tauri_plugin_dialog::init())
+        .manage(database)
+        .register_uri_scheme_protocol(thumbnail::THUMBNAIL_SCHEME, thumbnail::handle_thumbnail_request)
+        .register_asynchronous_uri_scheme_protocol(viewer::STREAM_SCHEME, viewer::handle_stream_request)
+        .setup(|app| {
+            watcher::start_content_watcher(app.handle().clone());
+            media::start_drive_monitor(app.handle().clone());
+            thumbnail::start_thumbnail_service(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             db::has_activation,
+            dbcheck::check_database_integrity,
             auth::get_mac_address,
             auth::perform_new_activation,
             auth::perform_reactivation,
             auth::check_activation_expiry,
             auth::perform_login,
+            auth::logout,
             content::has_active_content_path,
             content::add_and_set_active_content_path,
             content::get_content_paths,
             content::set_active_content_path,
             content::get_active_content_path,
             content::list_directories_in_path,
+            content::list_directory_page,
+            content::get_pinned_order,
+            content::set_pinned_order,
             content::delete_content_path,
-            content::open_file_in_system,
-            encryption::decrypt_file,
             encryption::parse_xml_quiz,
+            indexer::index_active_content_path,
+            indexer::get_content_items,
+            indexer::get_content_item,
+            indexer::get_content_hierarchy,
+            manifest::get_content_manifest,
+            integrity::verify_content_path,
+            package::import_content_package,
+            update::apply_content_update,
+            update::get_content_rollback_point,
+            update::rollback_content_update,
+            media::scan_removable_media,
+            thumbnail::get_thumbnail,
+            mediainfo::get_media_info,
+            pdftext::get_pdf_page_count,
+            pdftext::get_pdf_page_text,
+            viewer::open_content_viewer,
+            viewer::get_viewer_session,
+            viewer::record_viewer_position,
+            scorm::commit_scorm_values,
+            xapi::get_xapi_statements,
+            xapi::export_xapi_statements,
+            search::rebuild_active_search_index,
+            search::search_content,
+            progress::record_video_progress,
+            progress::record_pdf_page_view,
+            progress::record_quiz_completion,
+            progress::get_content_progress,
+            progress::get_resume_item,
+            progress::get_progress_rollup,
+            progress::get_section_progress,
+            recovery::regenerate_recovery_code,
+            recovery::recover_admin_access,
+            roles::get_role_content_categories,
+            roles::set_role_content_categories,
+            roles::get_my_permissions,
+            roster::add_class,
+            roster::add_section,
+            roster::delete_section,
+            roster::get_sections,
+            roster::get_section_roster,
+            roster::assign_teacher_to_section,
+            roster::remove_teacher_from_section,
+            roster::get_teacher_sections,
+            usercsv::import_users_csv,
+            usercsv::export_users_csv,
             users::add_user,
             users::get_users,
             users::update_user,
-            users::delete_user
+            users::delete_user,
+            users::get_deleted_users,
+            users::restore_user,
+            users::purge_user
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,286 @@
+// src-tauri/src/manifest.rs
+
+use crate::content::get_active_content_root;
+use crate::db::Database;
+use crate::i18n::LocalizedText;
+use crate::indexer::ContentType;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::State;
+
+pub const MANIFEST_JSON: &str = "manifest.json";
+pub const MANIFEST_TOML: &str = "manifest.toml";
+
+// Declares a content package: what it is and how its folders and files should be shown.
+// Anything the manifest leaves out falls back to folder names and filename keywords.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Manifest {
+    pub title: String,
+    pub board: Option<String>,
+    pub language: Option<String>,
+    pub version: String,
+    #[serde(default)]
+    pub classes: Vec<ManifestNode>,
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+}
+
+// A folder in the hierarchy. Top-level nodes are classes, their children subjects,
+// and the subjects' children chapters. `path` is the folder name at that level.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManifestNode {
+    pub path: String,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub titles: HashMap<String, String>, // Extra titles keyed by language tag
+    pub order: Option<i64>,
+    #[serde(default)]
+    pub children: Vec<ManifestNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManifestFile {
+    pub path: String, // Relative to the content root, with '/' separators
+    pub title: Option<String>,
+    #[serde(default)]
+    pub titles: HashMap<String, String>,
+    pub order: Option<i64>,
+    pub content_type: Option<ContentType>,
+    pub description: Option<String>,
+    pub sha256: Option<String>,
+    pub size: Option<u64>,
+}
+
+// Display metadata for one folder or file, keyed by its path relative to the root
+#[derive(Debug, Clone, Default)]
+pub struct EntryMeta {
+    pub title: LocalizedText,
+    pub order: Option<i64>,
+    pub content_type: Option<ContentType>,
+    pub description: Option<String>,
+}
+
+// A parsed manifest with its entries flattened for lookups by relative path
+#[derive(Debug)]
+pub struct LoadedManifest {
+    pub manifest: Manifest,
+    entries: HashMap<String, EntryMeta>,
+}
+
+impl LoadedManifest {
+    fn new(manifest: Manifest) -> Self {
+        let mut entries = HashMap::new();
+        let language = manifest.language.clone();
+        fn add_nodes(
+            nodes: &[ManifestNode],
+            parent: &str,
+            language: &Option<String>,
+            entries: &mut HashMap<String, EntryMeta>,
+        ) {
+            for node in nodes {
+                let relative = join_relative(parent, &node.path);
+                entries.insert(
+                    relative.clone(),
+                    EntryMeta {
+                        title: localized(language, &node.title, &node.titles),
+                        order: node.order,
+                        ..Default::default()
+                    },
+                );
+                add_nodes(&node.children, &relative, language, entries);
+            }
+        }
+        add_nodes(&manifest.classes, "", &language, &mut entries);
+        for file in &manifest.files {
+            entries.insert(
+                normalize_relative(&file.path),
+                EntryMeta {
+                    title: localized(&language, &file.title, &file.titles),
+                    order: file.order,
+                    content_type: file.content_type,
+                    description: file.description.clone(),
+                },
+            );
+        }
+        LoadedManifest { manifest, entries }
+    }
+
+    pub fn entry(&self, relative_path: &str) -> Option<&EntryMeta> {
+        self.entries.get(&normalize_relative(relative_path))
+    }
+
+    pub fn title_for(&self, relative_path: &str, language: Option<&str>) -> Option<String> {
+        self.entry(relative_path).and_then(|meta| meta.title.resolve(language))
+    }
+}
+
+fn localized(language: &Option<String>, title: &Option<String>, titles: &HashMap<String, String>) -> LocalizedText {
+    let mut text = LocalizedText::default();
+    if let Some(title) = title {
+        text.push(language.clone(), title.clone());
+    }
+    for (lang, title) in titles {
+        text.push(Some(lang.clone()), title.clone());
+    }
+    text
+}
+
+fn join_relative(parent: &str, name: &str) -> String {
+    let name = normalize_relative(name);
+    if parent.is_empty() {
+        name
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+fn normalize_relative(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_string()
+}
+
+// True when a manifest path stays inside the content root
+pub(crate) fn is_safe_relative(path: &str) -> bool {
+    let path = Path::new(path);
+    !path.as_os_str().is_empty()
+        && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn manifest_file(root: &Path) -> Option<PathBuf> {
+    [MANIFEST_JSON, MANIFEST_TOML]
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_file())
+}
+
+// Reads the manifest at the root of a content path. Ok(None) means the root has no manifest.
+pub fn read_manifest(root: &Path) -> Result<Option<Manifest>, String> {
+    let Some(path) = manifest_file(root) else {
+        return Ok(None);
+    };
+    let contents = std::fs::read_to_string(&path).map_err(|e| {
+        error!("Failed to read manifest {}: {}", path.display(), e);
+        format!("Failed to read manifest {}: {}", path.display(), e)
+    })?;
+    let manifest: Manifest = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&contents).map_err(|e| format!("Invalid manifest {}: {}", path.display(), e))?
+    } else {
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid manifest {}: {}", path.display(), e))?
+    };
+    Ok(Some(manifest))
+}
+
+// Checks a manifest against the folder it describes. Structural problems are errors;
+// declared folders or files that are missing on disk are only logged, since integrity
+// checks report those in detail.
+pub fn validate_manifest(root: &Path, manifest: &Manifest) -> Result<(), String> {
+    let mut problems = Vec::new();
+    if manifest.title.trim().is_empty() {
+        problems.push("title must not be empty".to_string());
+    }
+    if manifest.version.trim().is_empty() {
+        problems.push("version must not be empty".to_string());
+    }
+
+    fn check_nodes(nodes: &[ManifestNode], parent: &str, root: &Path, problems: &mut Vec<String>) {
+        for node in nodes {
+            if !is_safe_relative(&node.path) || node.path.contains('/') || node.path.contains('\\') {
+                problems.push(format!("folder '{}' under '{}' must be a single folder name", node.path, parent));
+                continue;
+            }
+            let relative = join_relative(parent, &node.path);
+            if !root.join(&relative).is_dir() {
+                warn!("Manifest folder '{}' is missing under {}", relative, root.display());
+            }
+            check_nodes(&node.children, &relative, root, problems);
+        }
+    }
+    check_nodes(&manifest.classes, "", root, &mut problems);
+
+    for file in &manifest.files {
+        if !is_safe_relative(&file.path) {
+            problems.push(format!("file path '{}' must be relative to the content root", file.path));
+            continue;
+        }
+        if let Some(sha256) = &file.sha256 {
+            if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                problems.push(format!("file '{}' has an invalid sha256 checksum", file.path));
+            }
+        }
+        if !root.join(normalize_relative(&file.path)).is_file() {
+            warn!("Manifest file '{}' is missing under {}", file.path, root.display());
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        error!("Manifest at {} failed validation: {:?}", root.display(), problems);
+        Err(format!("Invalid content manifest: {}", problems.join("; ")))
+    }
+}
+
+// Reads and validates the manifest of a content root that is being registered
+pub fn read_and_validate(root: &Path) -> Result<Option<Manifest>, String> {
+    let Some(manifest) = read_manifest(root)? else {
+        info!("No manifest found at {}", root.display());
+        return Ok(None);
+    };
+    validate_manifest(root, &manifest)?;
+    info!(
+        "Loaded manifest '{}' version {} with {} classes and {} files",
+        manifest.title,
+        manifest.version,
+        manifest.classes.len(),
+        manifest.files.len()
+    );
+    Ok(Some(manifest))
+}
+
+struct CachedManifest {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    loaded: Option<Arc<LoadedManifest>>,
+}
+
+static MANIFEST_CACHE: Mutex<Option<CachedManifest>> = Mutex::new(None);
+
+// Returns the manifest of `root`, re-reading it only when the file changes.
+// Listings call this on every navigation, so a large manifest is parsed once.
+pub fn load_cached(root: &Path) -> Option<Arc<LoadedManifest>> {
+    let path = manifest_file(root)?;
+    let modified = path.metadata().and_then(|m| m.modified()).ok();
+    let mut cache = MANIFEST_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cache.as_ref() {
+        if cached.path == path && cached.modified == modified {
+            return cached.loaded.clone();
+        }
+    }
+
+    let loaded = match read_manifest(root) {
+        Ok(manifest) => manifest.map(|manifest| Arc::new(LoadedManifest::new(manifest))),
+        Err(e) => {
+            warn!("Ignoring manifest: {}", e);
+            None
+        }
+    };
+    *cache = Some(CachedManifest { path, modified, loaded: loaded.clone() });
+    loaded
+}
+
+// Manifest of the active content path, if it ships one
+#[tauri::command]
+pub async fn get_content_manifest(database: State<'_, Database>) -> Result<Option<Manifest>, String> {
+    info!("Retrieving manifest of the active content path.");
+    database
+        .run(|db| {
+            let Some((_, root)) = get_active_content_root(db)? else {
+                return Ok(None);
+            };
+            Ok(load_cached(Path::new(&root)).map(|loaded| loaded.manifest.clone()))
+        })
+        .await
+}
@@ -0,0 +1,369 @@
+// src-tauri/src/media.rs
+
+use crate::db::Database;
+use crate::watcher;
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+// Hidden file at a content root holding the id of the library stored there
+pub const LIBRARY_MARKER_FILE: &str = ".osiris-library";
+
+pub const DRIVE_REMOVED_EVENT: &str = "content-drive-removed";
+pub const DRIVE_CONNECTED_EVENT: &str = "content-drive-connected";
+pub const DRIVE_RELINKED_EVENT: &str = "content-drive-relinked";
+
+// Where desktop environments mount USB drives
+#[cfg(target_os = "linux")]
+const REMOVABLE_MOUNT_ROOTS: &[&str] = &["/media", "/run/media"];
+
+const DRIVE_CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+// How a content root is recognised when its drive is mounted somewhere else
+#[derive(Debug, Clone, Default)]
+pub struct ContentRootIdentity {
+    pub volume_uuid: Option<String>,
+    pub volume_path: Option<String>, // Root relative to the volume's mount point
+    pub library_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DriveEventPayload {
+    pub id: i64,
+    pub path: String,
+    pub previous_path: Option<String>,
+}
+
+// A library found on an attached drive
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetectedLibrary {
+    pub path: String,
+    pub mount_point: String,
+    pub volume_uuid: Option<String>,
+    pub library_id: String,
+    pub content_path_id: Option<i64>, // Set when the library is already registered
+}
+
+#[derive(Debug, Clone)]
+struct Mount {
+    mount_point: PathBuf,
+    uuid: Option<String>,
+}
+
+struct RegisteredRoot {
+    id: i64,
+    path: String,
+    is_active: bool,
+    identity: ContentRootIdentity,
+}
+
+pub fn read_library_id(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join(LIBRARY_MARKER_FILE))
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+// Returns the library id of `root`, writing a new marker if there is none yet.
+// Read-only media without a marker are recognised by volume UUID alone.
+fn ensure_library_id(root: &Path) -> Option<String> {
+    if let Some(id) = read_library_id(root) {
+        return Some(id);
+    }
+    let id: String = rand::random::<[u8; 16]>().iter().map(|b| format!("{:02x}", b)).collect();
+    match fs::write(root.join(LIBRARY_MARKER_FILE), &id) {
+        Ok(()) => Some(id),
+        Err(e) => {
+            warn!("Could not write library marker to {}: {}", root.display(), e);
+            None
+        }
+    }
+}
+
+// Undoes the octal escapes /proc/mounts uses for spaces and other special characters
+#[cfg(target_os = "linux")]
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+            let digits = std::str::from_utf8(&bytes[i + 1..i + 4]).unwrap_or("");
+            if let Ok(code) = u8::from_str_radix(digits, 8) {
+                out.push(code);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Mounted filesystems with their volume UUIDs, from /proc/mounts and /dev/disk/by-uuid
+#[cfg(target_os = "linux")]
+fn mounts() -> Vec<Mount> {
+    let uuids: HashMap<PathBuf, String> = fs::read_dir("/dev/disk/by-uuid")
+        .map(|dir| {
+            dir.filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let device = fs::canonicalize(entry.path()).ok()?;
+                    Some((device, entry.file_name().to_string_lossy().into_owned()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Ok(contents) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = unescape_mount_field(fields.next()?);
+            let mount_point = PathBuf::from(unescape_mount_field(fields.next()?));
+            let uuid = fs::canonicalize(&device).ok().and_then(|d| uuids.get(&d).cloned());
+            Some(Mount { mount_point, uuid })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mounts() -> Vec<Mount> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+fn removable_mounts() -> Vec<Mount> {
+    mounts()
+        .into_iter()
+        .filter(|m| REMOVABLE_MOUNT_ROOTS.iter().any(|root| m.mount_point.starts_with(root)))
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn removable_mounts() -> Vec<Mount> {
+    Vec::new()
+}
+
+// Works out the volume, relative path and library id of a content root being registered
+pub fn identify_content_root(root: &Path) -> ContentRootIdentity {
+    let canonical = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let volume = mounts()
+        .into_iter()
+        .filter(|m| canonical.starts_with(&m.mount_point))
+        .max_by_key(|m| m.mount_point.as_os_str().len());
+    let (volume_uuid, volume_path) = match volume {
+        Some(mount) => (
+            mount.uuid,
+            canonical
+                .strip_prefix(&mount.mount_point)
+                .ok()
+                .map(|p| p.to_string_lossy().replace('\\', "/")),
+        ),
+        None => (None, None),
+    };
+    ContentRootIdentity { volume_uuid, volume_path, library_id: ensure_library_id(root) }
+}
+
+pub fn store_identity(db: &Connection, id: i64, identity: &ContentRootIdentity) -> Result<(), String> {
+    db.execute(
+        "UPDATE ContentPaths SET volume_uuid = ?1, volume_path = ?2, library_id = ?3 WHERE id = ?4",
+        params![identity.volume_uuid, identity.volume_path, identity.library_id, id],
+    )
+    .map_err(|e| {
+        error!("Failed to store drive identity of content path {}: {}", id, e);
+        e.to_string()
+    })?;
+    Ok(())
+}
+
+fn registered_roots(db: &Connection) -> Result<Vec<RegisteredRoot>, String> {
+    let mut stmt = db
+        .prepare_cached("SELECT id, path, is_active, volume_uuid, volume_path, library_id FROM ContentPaths")
+        .map_err(|e| e.to_string())?;
+    let roots = stmt
+        .query_map([], |row| {
+            Ok(RegisteredRoot {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                is_active: row.get(2)?,
+                identity: ContentRootIdentity {
+                    volume_uuid: row.get(3)?,
+                    volume_path: row.get(4)?,
+                    library_id: row.get(5)?,
+                },
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(roots)
+}
+
+// True when `path` holds the library described by `identity`
+fn holds_library(path: &Path, identity: &ContentRootIdentity) -> bool {
+    path.is_dir()
+        && match &identity.library_id {
+            Some(id) => read_library_id(path).as_deref() == Some(id.as_str()),
+            None => true,
+        }
+}
+
+// Looks for a registered library on the attached drives
+fn find_relocated(root: &RegisteredRoot, mounts: &[Mount], taken: &HashSet<String>) -> Option<PathBuf> {
+    let identity = &root.identity;
+    if identity.volume_uuid.is_none() && identity.library_id.is_none() {
+        return None;
+    }
+    let relative = identity.volume_path.clone().unwrap_or_default();
+    mounts
+        .iter()
+        .filter(|m| match (&identity.volume_uuid, &m.uuid) {
+            (Some(expected), Some(uuid)) => expected.eq_ignore_ascii_case(uuid),
+            // Without a matching UUID, only a marker can prove it is the same library
+            _ => identity.library_id.is_some(),
+        })
+        .map(|m| m.mount_point.join(&relative))
+        // A path registered under another id was added by hand and is left alone
+        .find(|candidate| !taken.contains(candidate.to_string_lossy().as_ref()) && holds_library(candidate, identity))
+}
+
+fn emit(app: &AppHandle, event: &str, payload: DriveEventPayload) {
+    if let Err(e) = app.emit(event, payload) {
+        warn!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+// Relinks libraries that moved to a new mount point and reports drives coming and going.
+// `available` carries what was seen on the previous check. Probing a slow or sleeping drive
+// can take seconds, so a pooled connection is only taken for the lookups and updates.
+fn check_content_drives(app: &AppHandle, available: &mut HashMap<i64, bool>) -> Result<(), String> {
+    let database = app.state::<Database>();
+    let roots = {
+        let db = database.connection()?;
+        registered_roots(&db)?
+    };
+    let mounts = removable_mounts();
+
+    let taken: HashSet<String> = roots.iter().map(|r| r.path.clone()).collect();
+
+    for root in roots {
+        let mut path = root.path.clone();
+        let mut present = holds_library(Path::new(&path), &root.identity);
+
+        if !present {
+            if let Some(new_path) = find_relocated(&root, &mounts, &taken) {
+                let new_path = new_path.to_string_lossy().into_owned();
+                let relinked = database.connection().and_then(|db| {
+                    db.execute(
+                        "UPDATE ContentPaths SET path = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                        params![new_path, root.id],
+                    )
+                    .map_err(|e| e.to_string())
+                });
+                match relinked {
+                    Ok(_) => {
+                        info!("Content path {} moved from {} to {}", root.id, root.path, new_path);
+                        if root.is_active {
+                            watcher::notify_active_path_changed();
+                        }
+                        emit(
+                            app,
+                            DRIVE_RELINKED_EVENT,
+                            DriveEventPayload { id: root.id, path: new_path.clone(), previous_path: Some(root.path.clone()) },
+                        );
+                        path = new_path;
+                        present = true;
+                    }
+                    Err(e) => warn!("Could not relink content path {} to {}: {}", root.id, new_path, e),
+                }
+            }
+        } else if root.identity.volume_uuid.is_none() && root.identity.library_id.is_none() {
+            // Registered before drives were tracked
+            let identity = identify_content_root(Path::new(&path));
+            let db = database.connection()?;
+            store_identity(&db, root.id, &identity)?;
+        }
+
+        let was_present = available.insert(root.id, present);
+        match (was_present, present) {
+            (Some(true), false) => {
+                warn!("Drive holding content path {} ({}) was removed", root.id, path);
+                if root.is_active {
+                    emit(app, DRIVE_REMOVED_EVENT, DriveEventPayload { id: root.id, path, previous_path: None });
+                }
+            }
+            (Some(false), true) => {
+                info!("Drive holding content path {} ({}) is back", root.id, path);
+                if root.is_active {
+                    watcher::notify_active_path_changed();
+                }
+                emit(app, DRIVE_CONNECTED_EVENT, DriveEventPayload { id: root.id, path, previous_path: None });
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+// Starts the background thread that follows content drives as they are plugged in and out
+pub fn start_drive_monitor(app: AppHandle) {
+    std::thread::spawn(move || {
+        info!("Content drive monitor started.");
+        let mut available = HashMap::new();
+        loop {
+            if let Err(e) = check_content_drives(&app, &mut available) {
+                warn!("Content drive check failed: {}", e);
+            }
+            std::thread::sleep(DRIVE_CHECK_INTERVAL);
+        }
+    });
+}
+
+// Libraries on currently attached removable drives, registered or not
+#[tauri::command]
+pub async fn scan_removable_media(database: State<'_, Database>) -> Result<Vec<DetectedLibrary>, String> {
+    info!("Scanning removable media for content libraries.");
+    let registered = database.run(|db| registered_roots(db)).await?;
+    // Drives are probed without holding a pooled connection
+    tokio::task::spawn_blocking(move || {
+        let mut found = Vec::new();
+        for mount in removable_mounts() {
+            // Libraries sit at the top of the drive or one folder down
+            let mut candidates = vec![mount.mount_point.clone()];
+            if let Ok(dir) = fs::read_dir(&mount.mount_point) {
+                candidates.extend(dir.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()));
+            }
+            for candidate in candidates {
+                let Some(library_id) = read_library_id(&candidate) else { continue };
+                let path = candidate.to_string_lossy().into_owned();
+                let content_path_id = registered
+                    .iter()
+                    .find(|r| r.path == path || r.identity.library_id.as_deref() == Some(library_id.as_str()))
+                    .map(|r| r.id);
+                found.push(DetectedLibrary {
+                    path,
+                    mount_point: mount.mount_point.to_string_lossy().into_owned(),
+                    volume_uuid: mount.uuid.clone(),
+                    library_id,
+                    content_path_id,
+                });
+            }
+        }
+        info!("Found {} content libraries on removable media.", found.len());
+        found
+    })
+    .await
+    .map_err(|e| {
+        error!("Removable media scan failed: {}", e);
+        e.to_string()
+    })
+}
@@ -0,0 +1,837 @@
+// src-tauri/src/mediainfo.rs
+
+use crate::content::resolve_in_content_roots;
+use crate::db::Database;
+use crate::encryption::{open_content, ReadSeek};
+use crate::thumbnail::file_stamp;
+use log::{error, info, warn};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{Read, SeekFrom};
+use std::path::Path;
+use tauri::State;
+
+// Upper bounds on header data read into memory, so a damaged file cannot exhaust memory
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_EBML_SECTION_SIZE: u64 = 16 * 1024 * 1024;
+const MAX_CHAPTERS: usize = 1000;
+
+// Matroska/WebM element IDs, with their length marker bits kept
+const EBML_HEADER: u32 = 0x1A45DFA3;
+const EBML_DOC_TYPE: u32 = 0x4282;
+const SEGMENT: u32 = 0x18538067;
+const SEEK_HEAD: u32 = 0x114D9B74;
+const SEEK: u32 = 0x4DBB;
+const SEEK_ID: u32 = 0x53AB;
+const SEEK_POSITION: u32 = 0x53AC;
+const SEGMENT_INFO: u32 = 0x1549A966;
+const TIMECODE_SCALE: u32 = 0x2AD7B1;
+const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CHAPTERS: u32 = 0x1043A770;
+const EDITION_ENTRY: u32 = 0x45B9;
+const CHAPTER_ATOM: u32 = 0xB6;
+const CHAPTER_TIME_START: u32 = 0x91;
+const CHAPTER_DISPLAY: u32 = 0x80;
+const CHAP_STRING: u32 = 0x85;
+const CLUSTER: u32 = 0x1F43B675;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Chapter {
+    pub title: String,
+    pub start_seconds: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MediaInfo {
+    pub container: String, // "mp4", "mov", "webm" or "mkv"
+    pub duration_seconds: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub chapters: Vec<Chapter>,
+}
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    data.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn be_u64(data: &[u8], at: usize) -> Option<u64> {
+    data.get(at..at + 8).map(|b| u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+
+fn read_at(reader: &mut dyn ReadSeek, pos: u64, len: u64) -> Result<Vec<u8>, String> {
+    reader.seek(SeekFrom::Start(pos)).map_err(|e| e.to_string())?;
+    let mut data = Vec::new();
+    (&mut *reader).take(len).read_to_end(&mut data).map_err(|e| e.to_string())?;
+    Ok(data)
+}
+
+// Chapter titles are UTF-8, or UTF-16 when they start with a byte order mark
+fn decode_title(data: &[u8]) -> String {
+    if data.starts_with(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = data[2..].chunks_exact(2).map(|b| u16::from_be_bytes([b[0], b[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if data.starts_with(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = data[2..].chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    String::from_utf8_lossy(data).into_owned()
+}
+
+// MP4 / QuickTime
+
+// Child boxes of an in-memory box body, as (type, body)
+fn boxes(mut data: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> + '_ {
+    std::iter::from_fn(move || {
+        let size = be_u32(data, 0)? as u64;
+        let kind: [u8; 4] = data.get(4..8)?.try_into().ok()?;
+        let (header, size) = match size {
+            1 => (16, be_u64(data, 8)?),
+            0 => (8, data.len() as u64),
+            size => (8, size),
+        };
+        if size < header as u64 || size > data.len() as u64 {
+            return None;
+        }
+        let body = &data[header..size as usize];
+        data = &data[size as usize..];
+        Some((kind, body))
+    })
+}
+
+fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    boxes(data).find(|(k, _)| k == kind).map(|(_, body)| body)
+}
+
+struct Mp4Track<'a> {
+    id: u32,
+    handler: [u8; 4],
+    timescale: u32,
+    codec: Option<[u8; 4]>,
+    width: u32,
+    height: u32,
+    chapter_tracks: Vec<u32>,
+    stbl: &'a [u8],
+}
+
+fn mp4_codec_name(fourcc: &[u8; 4]) -> String {
+    let name = match fourcc {
+        b"avc1" | b"avc3" => "h264",
+        b"hvc1" | b"hev1" => "hevc",
+        b"mp4v" => "mpeg4",
+        b"vp08" => "vp8",
+        b"vp09" => "vp9",
+        b"av01" => "av1",
+        b"mp4a" => "aac",
+        b"ac-3" => "ac3",
+        b"ec-3" => "eac3",
+        b"Opus" => "opus",
+        b"fLaC" => "flac",
+        b".mp3" => "mp3",
+        _ => return String::from_utf8_lossy(fourcc).trim().to_lowercase(),
+    };
+    name.to_string()
+}
+
+fn parse_trak(trak: &[u8]) -> Option<Mp4Track<'_>> {
+    let tkhd = child(trak, b"tkhd")?;
+    let id = be_u32(tkhd, if tkhd.first() == Some(&1) { 20 } else { 12 })?;
+    // Track width and height are 16.16 fixed point values ending the tkhd box
+    let width = be_u32(tkhd, tkhd.len().checked_sub(8)?)? >> 16;
+    let height = be_u32(tkhd, tkhd.len().checked_sub(4)?)? >> 16;
+
+    let mdia = child(trak, b"mdia")?;
+    let mdhd = child(mdia, b"mdhd")?;
+    let timescale = be_u32(mdhd, if mdhd.first() == Some(&1) { 20 } else { 12 })?;
+    let handler: [u8; 4] = child(mdia, b"hdlr")?.get(8..12)?.try_into().ok()?;
+    let stbl = child(child(mdia, b"minf")?, b"stbl")?;
+
+    // The first sample description names the codec; video descriptions also carry
+    // the coded size, used when tkhd leaves it at zero
+    let entry = child(stbl, b"stsd").and_then(|stsd| stsd.get(8..));
+    let codec = entry.and_then(|e| e.get(4..8)).and_then(|c| c.try_into().ok());
+    let (width, height) = match (width, height, &handler) {
+        (0, 0, b"vide") => (
+            entry.and_then(|e| be_u16(e, 32)).unwrap_or(0) as u32,
+            entry.and_then(|e| be_u16(e, 34)).unwrap_or(0) as u32,
+        ),
+        size => (size.0, size.1),
+    };
+
+    let chapter_tracks = child(trak, b"tref")
+        .and_then(|tref| child(tref, b"chap"))
+        .map(|chap| chap.chunks_exact(4).map(|id| u32::from_be_bytes([id[0], id[1], id[2], id[3]])).collect())
+        .unwrap_or_default();
+
+    Some(Mp4Track { id, handler, timescale, codec, width, height, chapter_tracks, stbl })
+}
+
+// Nero-style chapter list (udta/chpl): start times in 100ns units and length-prefixed titles
+fn nero_chapters(chpl: &[u8]) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut at = if chpl.first() == Some(&1) { 8 } else { 4 };
+    let Some(&count) = chpl.get(at) else {
+        return chapters;
+    };
+    at += 1;
+    for _ in 0..count {
+        let (Some(start), Some(&len)) = (be_u64(chpl, at), chpl.get(at + 8)) else {
+            break;
+        };
+        let Some(title) = chpl.get(at + 9..at + 9 + len as usize) else {
+            break;
+        };
+        chapters.push(Chapter { title: decode_title(title), start_seconds: start as f64 / 10_000_000.0 });
+        at += 9 + len as usize;
+    }
+    chapters
+}
+
+// QuickTime chapters: a text track referenced through tref/chap, where each sample is a
+// 16-bit length followed by the title and the sample's start time is the chapter start
+fn text_track_chapters(reader: &mut dyn ReadSeek, track: &Mp4Track) -> Vec<Chapter> {
+    let stbl = track.stbl;
+    let (Some(stts), Some(stsz), Some(stsc)) = (child(stbl, b"stts"), child(stbl, b"stsz"), child(stbl, b"stsc")) else {
+        return Vec::new();
+    };
+    let chunk_offsets: Vec<u64> = if let Some(stco) = child(stbl, b"stco") {
+        let count = be_u32(stco, 4).unwrap_or(0) as usize;
+        (0..count).map_while(|i| be_u32(stco, 8 + i * 4).map(u64::from)).collect()
+    } else if let Some(co64) = child(stbl, b"co64") {
+        let count = be_u32(co64, 4).unwrap_or(0) as usize;
+        (0..count).map_while(|i| be_u64(co64, 8 + i * 8)).collect()
+    } else {
+        return Vec::new();
+    };
+
+    let uniform_size = be_u32(stsz, 4).unwrap_or(0);
+    let sample_count = (be_u32(stsz, 8).unwrap_or(0) as usize).min(MAX_CHAPTERS);
+    let sample_size = |i: usize| if uniform_size != 0 { Some(uniform_size) } else { be_u32(stsz, 12 + i * 4) };
+
+    let mut starts = Vec::with_capacity(sample_count);
+    let mut time = 0u64;
+    for i in 0..be_u32(stts, 4).unwrap_or(0) as usize {
+        let (Some(count), Some(delta)) = (be_u32(stts, 8 + i * 8), be_u32(stts, 12 + i * 8)) else {
+            break;
+        };
+        for _ in 0..count {
+            if starts.len() == sample_count {
+                break;
+            }
+            starts.push(time);
+            time = time.saturating_add(delta as u64);
+        }
+    }
+
+    // Sample offsets follow from the chunk offsets and the samples-per-chunk runs in stsc
+    let runs: Vec<(usize, usize)> = (0..be_u32(stsc, 4).unwrap_or(0) as usize)
+        .map_while(|i| Some((be_u32(stsc, 8 + i * 12)? as usize, be_u32(stsc, 12 + i * 12)? as usize)))
+        .collect();
+    let mut offsets = Vec::with_capacity(sample_count);
+    'chunks: for (index, chunk_offset) in chunk_offsets.iter().enumerate() {
+        let per_chunk = runs.iter().rev().find(|(first, _)| *first <= index + 1).map_or(1, |(_, n)| *n);
+        let mut offset = *chunk_offset;
+        for _ in 0..per_chunk {
+            if offsets.len() == sample_count {
+                break 'chunks;
+            }
+            let Some(size) = sample_size(offsets.len()) else {
+                break 'chunks;
+            };
+            offsets.push((offset, size));
+            // Offsets come from the file, so a damaged table must not overflow
+            let Some(next) = offset.checked_add(size as u64) else {
+                break 'chunks;
+            };
+            offset = next;
+        }
+    }
+
+    let timescale = track.timescale.max(1) as f64;
+    let mut chapters = Vec::new();
+    for ((offset, size), start) in offsets.into_iter().zip(starts) {
+        let Ok(sample) = read_at(reader, offset, size.min(2 + 1024) as u64) else {
+            break;
+        };
+        let Some(len) = be_u16(&sample, 0) else {
+            continue;
+        };
+        let title = &sample[2..(2 + len as usize).min(sample.len())];
+        chapters.push(Chapter { title: decode_title(title), start_seconds: start as f64 / timescale });
+    }
+    chapters
+}
+
+fn parse_mp4(reader: &mut dyn ReadSeek) -> Result<MediaInfo, String> {
+    let end = reader.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+    let mut brand = None;
+    let mut moov = None;
+    let mut pos = 0;
+    // Top-level boxes are walked on disk so the media data itself is never read
+    while pos + 8 <= end && (moov.is_none() || brand.is_none()) {
+        let header = read_at(reader, pos, 16)?;
+        let (Some(size), Some(kind)) = (be_u32(&header, 0), header.get(4..8)) else {
+            break;
+        };
+        let (header_len, size) = match size {
+            1 => (16, be_u64(&header, 8).ok_or("Truncated box header")?),
+            0 => (8, end - pos),
+            size => (8, size as u64),
+        };
+        // A box running past the end of the file is damaged, and walking on would overflow
+        if size < header_len || size > end - pos {
+            break;
+        }
+        match kind {
+            b"ftyp" => brand = Some(read_at(reader, pos + header_len, 4)?),
+            b"moov" => {
+                if size - header_len > MAX_MOOV_SIZE {
+                    return Err("Movie header is too large".to_string());
+                }
+                moov = Some(read_at(reader, pos + header_len, size - header_len)?);
+            }
+            _ => {}
+        }
+        pos += size;
+    }
+    let moov = moov.ok_or("No movie header (moov) found")?;
+
+    let mut info = MediaInfo {
+        container: if brand.as_deref() == Some(&b"qt  "[..]) { "mov" } else { "mp4" }.to_string(),
+        ..Default::default()
+    };
+    if let Some(mvhd) = child(&moov, b"mvhd") {
+        let (timescale, duration) = if mvhd.first() == Some(&1) {
+            (be_u32(mvhd, 20), be_u64(mvhd, 24))
+        } else {
+            (be_u32(mvhd, 12), be_u32(mvhd, 16).filter(|d| *d != u32::MAX).map(u64::from))
+        };
+        if let (Some(timescale), Some(duration)) = (timescale, duration) {
+            // Fragmented files leave the duration at zero
+            if timescale > 0 && duration > 0 {
+                info.duration_seconds = Some(duration as f64 / timescale as f64);
+            }
+        }
+    }
+
+    let tracks: Vec<Mp4Track> = boxes(&moov).filter(|(kind, _)| kind == b"trak").filter_map(|(_, trak)| parse_trak(trak)).collect();
+    if let Some(video) = tracks.iter().find(|t| &t.handler == b"vide") {
+        info.video_codec = video.codec.as_ref().map(mp4_codec_name);
+        info.width = Some(video.width).filter(|w| *w > 0);
+        info.height = Some(video.height).filter(|h| *h > 0);
+    }
+    if let Some(audio) = tracks.iter().find(|t| &t.handler == b"soun") {
+        info.audio_codec = audio.codec.as_ref().map(mp4_codec_name);
+    }
+
+    info.chapters = child(&moov, b"udta").and_then(|udta| child(udta, b"chpl")).map(nero_chapters).unwrap_or_default();
+    if info.chapters.is_empty() {
+        let chapter_ids: Vec<u32> = tracks.iter().flat_map(|t| t.chapter_tracks.iter().copied()).collect();
+        if let Some(track) = tracks.iter().find(|t| chapter_ids.contains(&t.id)) {
+            info.chapters = text_track_chapters(reader, track);
+        }
+    }
+    Ok(info)
+}
+
+// Matroska / WebM
+
+// Variable-length integer; IDs keep their length marker bits, sizes drop them
+fn vint(data: &[u8], keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *data.first()?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 || data.len() < len {
+        return None;
+    }
+    let mut value = first as u64 & if keep_marker { 0xFF } else { 0xFF >> len };
+    for byte in &data[1..len] {
+        value = value << 8 | *byte as u64;
+    }
+    Some((value, len))
+}
+
+// Element ID, body size (None when unknown) and header length
+fn ebml_element(data: &[u8]) -> Option<(u32, Option<u64>, usize)> {
+    let (id, id_len) = vint(data, true)?;
+    if id_len > 4 {
+        return None;
+    }
+    let (size, size_len) = vint(&data[id_len..], false)?;
+    let unknown = size == (1u64 << (7 * size_len)) - 1;
+    Some((id as u32, if unknown { None } else { Some(size) }, id_len + size_len))
+}
+
+// Child elements of an in-memory element body, as (id, body)
+fn elements(mut data: &[u8]) -> impl Iterator<Item = (u32, &[u8])> + '_ {
+    std::iter::from_fn(move || {
+        let (id, size, header) = ebml_element(data)?;
+        let end = size.map_or(data.len() as u64, |size| header as u64 + size).min(data.len() as u64) as usize;
+        let body = &data[header..end];
+        data = &data[end..];
+        Some((id, body))
+    })
+}
+
+fn ebml_child(data: &[u8], id: u32) -> Option<&[u8]> {
+    elements(data).find(|(child_id, _)| *child_id == id).map(|(_, body)| body)
+}
+
+fn ebml_uint(data: &[u8]) -> u64 {
+    data.iter().take(8).fold(0, |value, byte| value << 8 | *byte as u64)
+}
+
+fn ebml_float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_be_bytes(data.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(data.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+fn ebml_string(data: &[u8]) -> String {
+    String::from_utf8_lossy(data).trim_end_matches('\0').to_string()
+}
+
+fn matroska_codec_name(codec_id: &str) -> String {
+    let name = match codec_id {
+        "V_VP8" => "vp8",
+        "V_VP9" => "vp9",
+        "V_AV1" => "av1",
+        "V_MPEG4/ISO/AVC" => "h264",
+        "V_MPEGH/ISO/HEVC" => "hevc",
+        "A_OPUS" => "opus",
+        "A_VORBIS" => "vorbis",
+        "A_AAC" => "aac",
+        "A_MPEG/L3" => "mp3",
+        "A_FLAC" => "flac",
+        "A_AC3" => "ac3",
+        _ => return codec_id.to_lowercase(),
+    };
+    name.to_string()
+}
+
+// Keeps the first copy of a header section, skipping oversized or unknown-size ones
+fn read_section(
+    reader: &mut dyn ReadSeek,
+    sections: &mut HashMap<u32, Vec<u8>>,
+    id: u32,
+    size: Option<u64>,
+    body: u64,
+) -> Result<(), String> {
+    if let Some(size) = size.filter(|s| *s <= MAX_EBML_SECTION_SIZE) {
+        if let Entry::Vacant(entry) = sections.entry(id) {
+            entry.insert(read_at(reader, body, size)?);
+        }
+    }
+    Ok(())
+}
+
+// Reads the element header at `pos`: (id, body size, body start)
+fn read_element_header(reader: &mut dyn ReadSeek, pos: u64) -> Result<Option<(u32, Option<u64>, u64)>, String> {
+    let header = read_at(reader, pos, 12)?;
+    Ok(ebml_element(&header).map(|(id, size, len)| (id, size, pos + len as u64)))
+}
+
+fn parse_webm(reader: &mut dyn ReadSeek) -> Result<MediaInfo, String> {
+    let end = reader.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+    let (id, size, body) = read_element_header(reader, 0)?.ok_or("Truncated EBML header")?;
+    let size = size.filter(|s| id == EBML_HEADER && *s <= 4096).ok_or("Not an EBML file")?;
+    let doc_type = ebml_child(&read_at(reader, body, size)?, EBML_DOC_TYPE).map(ebml_string).unwrap_or_default();
+    let container = match doc_type.as_str() {
+        "webm" => "webm",
+        "matroska" => "mkv",
+        other => return Err(format!("Unsupported EBML document type '{}'", other)),
+    };
+
+    let (id, size, segment_start) = read_element_header(reader, body + size)?.ok_or("Truncated segment header")?;
+    if id != SEGMENT {
+        return Err("No Matroska segment found".to_string());
+    }
+    let segment_end = size.map_or(end, |size| segment_start + size).min(end);
+
+    // Header sections normally precede the first cluster; anything written after the
+    // media data (often Chapters, or everything when the muxer went back to fill in the
+    // SeekHead) is found through the SeekHead instead of scanning the clusters
+    let wanted = [SEGMENT_INFO, TRACKS, CHAPTERS, SEEK_HEAD];
+    let mut sections: HashMap<u32, Vec<u8>> = HashMap::new();
+
+    let mut pos = segment_start;
+    while pos < segment_end {
+        let Some((id, size, body)) = read_element_header(reader, pos)? else {
+            break;
+        };
+        if id == CLUSTER {
+            break;
+        }
+        if wanted.contains(&id) {
+            read_section(reader, &mut sections, id, size, body)?;
+        }
+        match size {
+            Some(size) => pos = body + size,
+            None => break,
+        }
+    }
+
+    if let Some(seek_head) = sections.get(&SEEK_HEAD).cloned() {
+        for (_, seek) in elements(&seek_head).filter(|(id, _)| *id == SEEK) {
+            let (Some(id), Some(position)) = (ebml_child(seek, SEEK_ID), ebml_child(seek, SEEK_POSITION)) else {
+                continue;
+            };
+            let id = ebml_uint(id) as u32;
+            if !wanted.contains(&id) || sections.contains_key(&id) {
+                continue;
+            }
+            let Some(pos) = segment_start.checked_add(ebml_uint(position)).filter(|pos| *pos < segment_end) else {
+                continue;
+            };
+            if let Some((found, size, body)) = read_element_header(reader, pos)? {
+                if found == id {
+                    read_section(reader, &mut sections, id, size, body)?;
+                }
+            }
+        }
+    }
+
+    let mut info = MediaInfo { container: container.to_string(), ..Default::default() };
+    if let Some(segment_info) = sections.get(&SEGMENT_INFO) {
+        let scale = ebml_child(segment_info, TIMECODE_SCALE).map(ebml_uint).unwrap_or(1_000_000);
+        info.duration_seconds = ebml_child(segment_info, DURATION)
+            .and_then(ebml_float)
+            .filter(|d| *d > 0.0)
+            .map(|d| d * scale as f64 / 1_000_000_000.0);
+    }
+    if let Some(tracks) = sections.get(&TRACKS) {
+        for (_, entry) in elements(tracks).filter(|(id, _)| *id == TRACK_ENTRY) {
+            let codec = ebml_child(entry, CODEC_ID).map(|id| matroska_codec_name(&ebml_string(id)));
+            match ebml_child(entry, TRACK_TYPE).map(ebml_uint) {
+                Some(1) if info.video_codec.is_none() => {
+                    info.video_codec = codec;
+                    if let Some(video) = ebml_child(entry, VIDEO) {
+                        info.width = ebml_child(video, PIXEL_WIDTH).map(|w| ebml_uint(w) as u32);
+                        info.height = ebml_child(video, PIXEL_HEIGHT).map(|h| ebml_uint(h) as u32);
+                    }
+                }
+                Some(2) if info.audio_codec.is_none() => info.audio_codec = codec,
+                _ => {}
+            }
+        }
+    }
+    if let Some(chapters) = sections.get(&CHAPTERS) {
+        // Only the first edition is listed; further editions are alternate cuts
+        if let Some(edition) = ebml_child(chapters, EDITION_ENTRY) {
+            for (_, atom) in elements(edition).filter(|(id, _)| *id == CHAPTER_ATOM).take(MAX_CHAPTERS) {
+                let start = ebml_child(atom, CHAPTER_TIME_START).map(ebml_uint).unwrap_or(0);
+                let title = ebml_child(atom, CHAPTER_DISPLAY)
+                    .and_then(|display| ebml_child(display, CHAP_STRING))
+                    .map(ebml_string)
+                    .unwrap_or_default();
+                info.chapters.push(Chapter { title, start_seconds: start as f64 / 1_000_000_000.0 });
+            }
+        }
+    }
+    Ok(info)
+}
+
+// Reads container headers of an MP4, MOV or WebM/Matroska file, decrypting on the fly
+pub fn read_media_info(path: &Path) -> Result<MediaInfo, String> {
+    let mut reader = open_content(path)?;
+    let head = read_at(reader.as_mut(), 0, 8)?;
+    let mut info = if head.starts_with(&EBML_HEADER.to_be_bytes()) {
+        parse_webm(reader.as_mut())?
+    } else if matches!(head.get(4..8), Some(b"ftyp" | b"moov" | b"mdat" | b"free" | b"wide" | b"skip")) {
+        parse_mp4(reader.as_mut())?
+    } else {
+        return Err(format!("{} is not an MP4, MOV or WebM file", path.display()));
+    };
+    info.chapters.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+    Ok(info)
+}
+
+// Media info for `path`, parsed once and reused until the file's size or mtime changes.
+// A pooled connection is only held for the lookup and the insert, not while parsing.
+fn cached_media_info(database: &Database, path: &Path) -> Result<MediaInfo, String> {
+    let (size, modified_at) = file_stamp(path)?;
+    let key = path.to_string_lossy();
+    let cached: Option<String> = database
+        .connection()?
+        .query_row(
+            "SELECT info FROM MediaInfo WHERE path = ?1 AND size = ?2 AND modified_at = ?3",
+            params![key, size, modified_at],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| {
+            error!("Failed to look up media info of {}: {}", path.display(), e);
+            e.to_string()
+        })?;
+    if let Some(info) = cached.and_then(|json| serde_json::from_str(&json).ok()) {
+        return Ok(info);
+    }
+
+    let info = read_media_info(path).map_err(|e| {
+        warn!("Failed to read media info of {}: {}", path.display(), e);
+        e
+    })?;
+    let json = serde_json::to_string(&info).map_err(|e| e.to_string())?;
+    database.connection()?.execute(
+        "INSERT INTO MediaInfo (path, size, modified_at, info) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(path) DO UPDATE SET size = excluded.size, modified_at = excluded.modified_at, info = excluded.info",
+        params![key, size, modified_at, json],
+    )
+    .map_err(|e| {
+        error!("Failed to cache media info of {}: {}", path.display(), e);
+        e.to_string()
+    })?;
+    info!("Read media info of {}", path.display());
+    Ok(info)
+}
+
+// Duration, resolution, codecs and chapters of a video, read from its container headers
+#[tauri::command]
+pub async fn get_media_info(database: State<'_, Database>, path: String) -> Result<MediaInfo, String> {
+    database
+        .run_blocking(move |database| {
+            let source = resolve_in_content_roots(&*database.connection()?, &path)?;
+            cached_media_info(database, &source)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn full_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        mp4_box(kind, &[&[0u8; 4][..], body].concat())
+    }
+
+    // Track with the given handler and codec; tkhd sizes are 16.16 fixed point
+    fn trak(id: u32, handler: &[u8; 4], codec: &[u8; 4], size: (u32, u32), coded_size: (u16, u16)) -> Vec<u8> {
+        let mut tkhd = vec![0u8; 80];
+        tkhd[8..12].copy_from_slice(&id.to_be_bytes());
+        tkhd[72..76].copy_from_slice(&(size.0 << 16).to_be_bytes());
+        tkhd[76..80].copy_from_slice(&(size.1 << 16).to_be_bytes());
+        let mut mdhd = vec![0u8; 20];
+        mdhd[8..12].copy_from_slice(&1000u32.to_be_bytes());
+        let mut entry = vec![0u8; 78];
+        entry[24..26].copy_from_slice(&coded_size.0.to_be_bytes());
+        entry[26..28].copy_from_slice(&coded_size.1.to_be_bytes());
+        let stsd = full_box(b"stsd", &[&1u32.to_be_bytes()[..], &mp4_box(codec, &entry)].concat());
+        let hdlr = full_box(b"hdlr", &[&[0u8; 4][..], handler, &[0u8; 13]].concat());
+        let minf = mp4_box(b"minf", &mp4_box(b"stbl", &stsd));
+        let mdia = mp4_box(b"mdia", &[full_box(b"mdhd", &mdhd), hdlr, minf].concat());
+        mp4_box(b"trak", &[full_box(b"tkhd", &tkhd), mdia].concat())
+    }
+
+    fn mvhd(timescale: u32, duration: u32) -> Vec<u8> {
+        let mut body = vec![0u8; 96];
+        body[8..12].copy_from_slice(&timescale.to_be_bytes());
+        body[12..16].copy_from_slice(&duration.to_be_bytes());
+        full_box(b"mvhd", &body)
+    }
+
+    fn nero_chapter_list(chapters: &[(u64, &str)]) -> Vec<u8> {
+        let mut body = vec![chapters.len() as u8];
+        for (start, title) in chapters {
+            body.extend_from_slice(&start.to_be_bytes());
+            body.push(title.len() as u8);
+            body.extend_from_slice(title.as_bytes());
+        }
+        mp4_box(b"udta", &full_box(b"chpl", &body))
+    }
+
+    fn mp4_file(brand: &[u8; 4], moov_children: &[Vec<u8>]) -> Vec<u8> {
+        [
+            mp4_box(b"ftyp", &[&brand[..], &[0u8; 4]].concat()),
+            mp4_box(b"mdat", &[0u8; 64]),
+            mp4_box(b"moov", &moov_children.concat()),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn parse_mp4_reads_duration_tracks_and_chapters() {
+        let file = mp4_file(
+            b"isom",
+            &[
+                mvhd(1000, 125_500),
+                trak(1, b"vide", b"avc1", (1280, 720), (0, 0)),
+                trak(2, b"soun", b"mp4a", (0, 0), (0, 0)),
+                nero_chapter_list(&[(0, "Intro"), (600_000_000, "Photosynthesis")]),
+            ],
+        );
+        let info = parse_mp4(&mut Cursor::new(file)).unwrap();
+        assert_eq!(info.container, "mp4");
+        assert_eq!(info.duration_seconds, Some(125.5));
+        assert_eq!((info.width, info.height), (Some(1280), Some(720)));
+        assert_eq!(info.video_codec.as_deref(), Some("h264"));
+        assert_eq!(info.audio_codec.as_deref(), Some("aac"));
+        let chapters: Vec<(&str, f64)> = info.chapters.iter().map(|c| (c.title.as_str(), c.start_seconds)).collect();
+        assert_eq!(chapters, vec![("Intro", 0.0), ("Photosynthesis", 60.0)]);
+    }
+
+    #[test]
+    fn parse_mp4_takes_the_coded_size_when_the_track_header_has_none() {
+        let file = mp4_file(b"qt  ", &[mvhd(600, 0), trak(1, b"vide", b"hvc1", (0, 0), (640, 360))]);
+        let info = parse_mp4(&mut Cursor::new(file)).unwrap();
+        assert_eq!(info.container, "mov");
+        // Fragmented files leave the duration at zero
+        assert_eq!(info.duration_seconds, None);
+        assert_eq!((info.width, info.height), (Some(640), Some(360)));
+        assert_eq!(info.video_codec.as_deref(), Some("hevc"));
+        assert_eq!(info.audio_codec, None);
+    }
+
+    #[test]
+    fn parse_mp4_fails_without_a_movie_header() {
+        let file = [mp4_box(b"ftyp", b"isom\0\0\0\0"), mp4_box(b"mdat", &[0u8; 16])].concat();
+        assert!(parse_mp4(&mut Cursor::new(file)).is_err());
+    }
+
+    #[test]
+    fn parse_mp4_stops_at_a_box_larger_than_the_file() {
+        let mut huge = 1u32.to_be_bytes().to_vec();
+        huge.extend_from_slice(b"mdat");
+        huge.extend_from_slice(&(u64::MAX - 4).to_be_bytes());
+        let file = [mp4_box(b"ftyp", b"isom\0\0\0\0"), huge, mp4_file(b"isom", &[mvhd(1000, 1000)])].concat();
+        assert_eq!(parse_mp4(&mut Cursor::new(file)).unwrap_err(), "No movie header (moov) found");
+    }
+
+    #[test]
+    fn boxes_stop_at_a_box_larger_than_its_parent() {
+        let mut data = mp4_box(b"free", &[0u8; 4]);
+        data.extend_from_slice(&1000u32.to_be_bytes());
+        data.extend_from_slice(b"skip");
+        let kinds: Vec<[u8; 4]> = boxes(&data).map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, vec![*b"free"]);
+    }
+
+    // Element with an 8-byte size, so every header fits read_element_header's 12 bytes
+    fn ebml(id: u32, body: &[u8]) -> Vec<u8> {
+        let id_bytes = id.to_be_bytes();
+        let skip = id_bytes.iter().position(|b| *b != 0).unwrap_or(3);
+        let mut data = id_bytes[skip..].to_vec();
+        data.push(0x01);
+        data.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn ebml_uint_body(value: u64) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    fn webm_file(doc_type: &str, segment_children: &[Vec<u8>]) -> Vec<u8> {
+        let header = ebml(EBML_HEADER, &ebml(EBML_DOC_TYPE, doc_type.as_bytes()));
+        [header, ebml(SEGMENT, &segment_children.concat())].concat()
+    }
+
+    fn tracks() -> Vec<u8> {
+        let video = ebml(
+            TRACK_ENTRY,
+            &[
+                ebml(TRACK_TYPE, &ebml_uint_body(1)),
+                ebml(CODEC_ID, b"V_VP9"),
+                ebml(VIDEO, &[ebml(PIXEL_WIDTH, &ebml_uint_body(854)), ebml(PIXEL_HEIGHT, &ebml_uint_body(480))].concat()),
+            ]
+            .concat(),
+        );
+        let audio = ebml(TRACK_ENTRY, &[ebml(TRACK_TYPE, &ebml_uint_body(2)), ebml(CODEC_ID, b"A_OPUS")].concat());
+        ebml(TRACKS, &[video, audio].concat())
+    }
+
+    fn chapters(atoms: &[(u64, &str)]) -> Vec<u8> {
+        let atoms: Vec<u8> = atoms
+            .iter()
+            .flat_map(|(start, title)| {
+                ebml(
+                    CHAPTER_ATOM,
+                    &[ebml(CHAPTER_TIME_START, &ebml_uint_body(*start)), ebml(CHAPTER_DISPLAY, &ebml(CHAP_STRING, title.as_bytes()))]
+                        .concat(),
+                )
+            })
+            .collect();
+        ebml(CHAPTERS, &ebml(EDITION_ENTRY, &atoms))
+    }
+
+    fn segment_info(duration_ms: f64) -> Vec<u8> {
+        ebml(
+            SEGMENT_INFO,
+            &[ebml(TIMECODE_SCALE, &ebml_uint_body(1_000_000)), ebml(DURATION, &duration_ms.to_be_bytes())].concat(),
+        )
+    }
+
+    #[test]
+    fn parse_webm_reads_header_sections_before_the_first_cluster() {
+        let file = webm_file(
+            "webm",
+            &[segment_info(2500.0), tracks(), chapters(&[(0, "Start"), (1_500_000_000, "Middle")]), ebml(CLUSTER, &[0u8; 8])],
+        );
+        let info = parse_webm(&mut Cursor::new(file)).unwrap();
+        assert_eq!(info.container, "webm");
+        assert_eq!(info.duration_seconds, Some(2.5));
+        assert_eq!((info.width, info.height), (Some(854), Some(480)));
+        assert_eq!(info.video_codec.as_deref(), Some("vp9"));
+        assert_eq!(info.audio_codec.as_deref(), Some("opus"));
+        let chapters: Vec<(&str, f64)> = info.chapters.iter().map(|c| (c.title.as_str(), c.start_seconds)).collect();
+        assert_eq!(chapters, vec![("Start", 0.0), ("Middle", 1.5)]);
+    }
+
+    #[test]
+    fn parse_webm_finds_sections_after_the_clusters_through_the_seek_head() {
+        let cluster = ebml(CLUSTER, &[0u8; 32]);
+        let info_section = segment_info(1000.0);
+        let seek_head_for = |position: u64| {
+            let seek = ebml(SEEK, &[ebml(SEEK_ID, &CHAPTERS.to_be_bytes()), ebml(SEEK_POSITION, &ebml_uint_body(position))].concat());
+            ebml(SEEK_HEAD, &seek)
+        };
+        // The SeekHead has a fixed size, so the position of Chapters can be worked out first
+        let chapters_at = (seek_head_for(0).len() + info_section.len() + cluster.len()) as u64;
+        let file = webm_file("matroska", &[seek_head_for(chapters_at), info_section, cluster, chapters(&[(0, "Only")])]);
+        let info = parse_webm(&mut Cursor::new(file)).unwrap();
+        assert_eq!(info.container, "mkv");
+        assert_eq!(info.duration_seconds, Some(1.0));
+        assert_eq!(info.chapters.len(), 1);
+        assert_eq!(info.chapters[0].title, "Only");
+    }
+
+    #[test]
+    fn parse_webm_rejects_other_document_types() {
+        let file = webm_file("notmkv", &[segment_info(1.0)]);
+        assert!(parse_webm(&mut Cursor::new(file)).is_err());
+    }
+
+    #[test]
+    fn vint_reads_sizes_and_ids() {
+        assert_eq!(vint(&[0x81], false), Some((1, 1)));
+        assert_eq!(vint(&[0x40, 0x02], false), Some((2, 2)));
+        assert_eq!(vint(&[0x1A, 0x45, 0xDF, 0xA3], true), Some((0x1A45DFA3, 4)));
+        assert_eq!(vint(&[0x00], false), None);
+        assert_eq!(vint(&[0x40], false), None);
+        // All value bits set means the size is unknown
+        assert_eq!(ebml_element(&[0xA3, 0xFF]), Some((0xA3, None, 2)));
+    }
+}
@@ -0,0 +1,571 @@
+// src-tauri/src/package.rs
+
+use crate::content::register_content_path;
+use crate::db::{get_app_data_dir, Database};
+use crate::integrity::{self, VerifyMode, CHECKSUM_FILE};
+use crate::manifest;
+use crate::roles::{require_permission, Permission};
+use crate::watcher::pause_watcher;
+use flate2::read::GzDecoder;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+pub const IMPORT_PROGRESS_EVENT: &str = "content-import-progress";
+
+// Imported packages live under <app data>/content/<name>; staging sits beside them so
+// the final move is a rename on the same filesystem
+const CONTENT_DIR: &str = "content";
+const STAGING_DIR: &str = ".staging";
+// Installs being replaced, kept outside the staging area so clearing it never loses one
+const PREVIOUS_DIR: &str = ".previous";
+// Prefix of a backup whose replacement was registered, so it is deleted and never restored.
+// Package names never start with a dot.
+const DISCARDED_PREFIX: &str = ".discarded-";
+
+// One import at a time, so the staging area can be cleared safely before each run
+static IMPORT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStage {
+    Checking,
+    Extracting,
+    Verifying,
+    Installing,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportProgress {
+    pub stage: ImportStage,
+    pub checked: Option<usize>,
+    pub total: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub path: String,
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub files: usize,
+}
+
+// Removes a staging directory on drop unless the import got as far as moving it away
+pub(crate) struct StagingDir(pub PathBuf);
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        if self.0.exists() {
+            if let Err(e) = fs::remove_dir_all(&self.0) {
+                warn!("Failed to clean up staging directory {}: {}", self.0.display(), e);
+            }
+        }
+    }
+}
+
+pub(crate) fn archive_kind(path: &Path) -> Result<ArchiveKind, String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if name.ends_with(".zip") {
+        Ok(ArchiveKind::Zip)
+    } else if name.ends_with(".tar") {
+        Ok(ArchiveKind::Tar)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Ok(ArchiveKind::TarGz)
+    } else {
+        Err(format!("Unsupported package format: {}. Use .zip, .tar or .tar.gz.", path.display()))
+    }
+}
+
+// Folder name for an imported package, derived from the archive name
+fn package_name(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let lower = name.to_lowercase();
+    let stem_len = [".tar.gz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map_or(name.len(), |ext| name.len() - ext.len());
+    let cleaned: String = name[..stem_len]
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_matches('.').to_string();
+    if cleaned.is_empty() {
+        "package".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn open_tar(path: &Path, kind: ArchiveKind) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?;
+    let reader: Box<dyn Read> = if kind == ArchiveKind::TarGz {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+// Total uncompressed size of the files in an archive
+pub(crate) fn unpacked_size(path: &Path, kind: ArchiveKind) -> Result<u64, String> {
+    if kind == ArchiveKind::Zip {
+        let file = File::open(path).map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?;
+        let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip package: {}", e))?;
+        let mut total = 0;
+        for i in 0..archive.len() {
+            total += archive.by_index_raw(i).map_err(|e| format!("Invalid zip package: {}", e))?.size();
+        }
+        return Ok(total);
+    }
+
+    let mut archive = open_tar(path, kind)?;
+    let mut total = 0;
+    for entry in archive.entries().map_err(|e| format!("Invalid tar package: {}", e))? {
+        total += entry.map_err(|e| format!("Invalid tar package: {}", e))?.header().size().unwrap_or(0);
+    }
+    Ok(total)
+}
+
+// Unpacks an archive into `dest`, refusing entries that would land outside it
+pub(crate) fn extract(path: &Path, kind: ArchiveKind, dest: &Path) -> Result<(), String> {
+    if kind == ArchiveKind::Zip {
+        let file = File::open(path).map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?;
+        let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip package: {}", e))?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| format!("Invalid zip package: {}", e))?;
+            let Some(relative) = entry.enclosed_name().map(Path::to_path_buf) else {
+                return Err(format!("Package entry '{}' points outside the package", entry.name()));
+            };
+            let out = dest.join(relative);
+            if entry.is_dir() {
+                fs::create_dir_all(&out).map_err(|e| e.to_string())?;
+                continue;
+            }
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let mut file = File::create(&out).map_err(|e| format!("Failed to create {}: {}", out.display(), e))?;
+            io::copy(&mut entry, &mut file).map_err(|e| format!("Failed to extract {}: {}", entry.name(), e))?;
+        }
+        return Ok(());
+    }
+
+    let mut archive = open_tar(path, kind)?;
+    for entry in archive.entries().map_err(|e| format!("Invalid tar package: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("Invalid tar package: {}", e))?;
+        let entry_type = entry.header().entry_type();
+        let name = entry.path().map(|p| p.display().to_string()).unwrap_or_default();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(format!("Package entry '{}' is a link, which packages may not contain", name));
+        }
+        let inside = entry
+            .unpack_in(dest)
+            .map_err(|e| format!("Failed to extract {}: {}", name, e))?;
+        if !inside {
+            return Err(format!("Package entry '{}' points outside the package", name));
+        }
+    }
+    Ok(())
+}
+
+// Archives often wrap everything in one top-level folder; the package root is wherever
+// `marker` (the checksum list, or the descriptor of an update) sits
+pub(crate) fn find_package_root(extracted: &Path, marker: &str) -> PathBuf {
+    if extracted.join(marker).is_file() {
+        return extracted.to_path_buf();
+    }
+    let entries: Vec<PathBuf> = fs::read_dir(extracted)
+        .map(|dir| dir.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [only] if only.is_dir() => only.clone(),
+        _ => extracted.to_path_buf(),
+    }
+}
+
+pub(crate) fn ensure_free_space(dir: &Path, needed: u64) -> Result<(), String> {
+    // Leave headroom for filesystem overhead on many small files
+    let needed = needed + needed / 20;
+    let available = fs2::available_space(dir).map_err(|e| {
+        error!("Failed to read free disk space for {}: {}", dir.display(), e);
+        format!("Failed to read free disk space: {}", e)
+    })?;
+    if available < needed {
+        return Err(format!(
+            "Not enough free disk space: the package needs {} MB but only {} MB is available.",
+            needed / (1024 * 1024) + 1,
+            available / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+// Moves `staged` to `target`, keeping any previous version at `backup` until `commit`
+// succeeds. On failure the previous version is put back; if the app stops midway, the
+// backup is put back at the next start (see `restore_previous_installs`).
+pub(crate) fn swap_into_place(
+    staged: &Path,
+    target: &Path,
+    backup: &Path,
+    commit: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let had_previous = target.exists();
+    if had_previous {
+        fs::rename(target, backup).map_err(|e| {
+            error!("Failed to move {} aside: {}", target.display(), e);
+            format!("Failed to replace existing content: {}", e)
+        })?;
+    }
+
+    let restore = |reason: String| -> String {
+        if target.exists() {
+            if let Err(e) = fs::remove_dir_all(target) {
+                error!("Failed to remove partially installed {}: {}", target.display(), e);
+            }
+        }
+        if had_previous {
+            if let Err(e) = fs::rename(backup, target) {
+                error!("Failed to restore {} from {}: {}", target.display(), backup.display(), e);
+            }
+        }
+        reason
+    };
+
+    fs::rename(staged, target).map_err(|e| restore(format!("Failed to move content into place: {}", e)))?;
+    commit().map_err(restore)?;
+
+    if had_previous {
+        // Marked before removal starts, so a half-removed backup is never restored
+        let name = backup.file_name().unwrap_or_default().to_string_lossy();
+        let discarded = backup.with_file_name(format!("{}{}", DISCARDED_PREFIX, name));
+        match fs::rename(backup, &discarded) {
+            Ok(()) => {
+                if let Err(e) = fs::remove_dir_all(&discarded) {
+                    warn!("Failed to remove previous content at {}: {}", discarded.display(), e);
+                }
+            }
+            Err(e) => error!("Failed to mark previous content at {} for removal: {}", backup.display(), e),
+        }
+    }
+    Ok(())
+}
+
+// Directory holding imported content, created if needed
+pub(crate) fn content_dir() -> Result<PathBuf, String> {
+    let dir = get_app_data_dir()?.join(CONTENT_DIR);
+    for sub_dir in [STAGING_DIR, PREVIOUS_DIR] {
+        fs::create_dir_all(dir.join(sub_dir)).map_err(|e| {
+            error!("Failed to create content directory {}: {}", dir.display(), e);
+            e.to_string()
+        })?;
+    }
+    Ok(dir)
+}
+
+// A fresh, empty directory in the staging area
+pub(crate) fn new_staging_dir(content_dir: &Path, label: &str) -> Result<StagingDir, String> {
+    let path = content_dir
+        .join(STAGING_DIR)
+        .join(format!("{}-{}", label, chrono::Utc::now().format("%Y%m%d%H%M%S%f")));
+    fs::create_dir_all(&path).map_err(|e| {
+        error!("Failed to create staging directory {}: {}", path.display(), e);
+        e.to_string()
+    })?;
+    Ok(StagingDir(path))
+}
+
+// Leftovers from an import that was interrupted, e.g. by a power cut
+fn clear_staging(content_dir: &Path) {
+    let staging = content_dir.join(STAGING_DIR);
+    if let Ok(entries) = fs::read_dir(&staging) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if let Err(e) = fs::remove_dir_all(entry.path()) {
+                warn!("Failed to remove stale staging entry {}: {}", entry.path().display(), e);
+            }
+        }
+    }
+}
+
+// Puts back installs whose replacement was interrupted. A backup is only marked discarded
+// once its replacement is registered, so any other backup means the swap never finished.
+fn restore_previous_installs(content_dir: &Path) {
+    let Ok(entries) = fs::read_dir(content_dir.join(PREVIOUS_DIR)) else { return };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let backup = entry.path();
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with(DISCARDED_PREFIX) {
+            if let Err(e) = fs::remove_dir_all(&backup) {
+                warn!("Failed to remove previous content at {}: {}", backup.display(), e);
+            }
+            continue;
+        }
+        let target = content_dir.join(&name);
+        warn!("Restoring {} after an interrupted import", target.display());
+        if target.exists() {
+            if let Err(e) = fs::remove_dir_all(&target) {
+                error!("Failed to remove partially installed {}: {}", target.display(), e);
+                continue;
+            }
+        }
+        if let Err(e) = fs::rename(&backup, &target) {
+            error!("Failed to restore {} from {}: {}", target.display(), backup.display(), e);
+        }
+    }
+}
+
+// Runs at startup, before the watcher and indexer see the trees
+pub fn recover_interrupted_imports() -> Result<(), String> {
+    let _guard = IMPORT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    restore_previous_installs(&content_dir()?);
+    Ok(())
+}
+
+fn import_package(
+    database: &Database,
+    archive_path: &Path,
+    activate: bool,
+    on_progress: &dyn Fn(ImportProgress),
+) -> Result<ImportResult, String> {
+    let _guard = IMPORT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let kind = archive_kind(archive_path)?;
+    let content_dir = content_dir()?;
+    clear_staging(&content_dir);
+
+    on_progress(ImportProgress { stage: ImportStage::Checking, checked: None, total: None });
+    let size = unpacked_size(archive_path, kind)?;
+    ensure_free_space(&content_dir, size)?;
+
+    on_progress(ImportProgress { stage: ImportStage::Extracting, checked: None, total: None });
+    let staging = new_staging_dir(&content_dir, "import")?;
+    extract(archive_path, kind, &staging.0)?;
+    let package_root = find_package_root(&staging.0, CHECKSUM_FILE);
+
+    on_progress(ImportProgress { stage: ImportStage::Verifying, checked: None, total: None });
+    let manifest = manifest::read_and_validate(&package_root)?;
+    let expected = integrity::load_expected(&package_root)?
+        .ok_or_else(|| format!("The package has no {} checksum list.", CHECKSUM_FILE))?;
+    let report = integrity::verify_tree(&package_root, &expected, VerifyMode::Full, &mut |progress| {
+        on_progress(ImportProgress {
+            stage: ImportStage::Verifying,
+            checked: Some(progress.checked),
+            total: Some(progress.total),
+        })
+    });
+    if !report.is_ok() {
+        error!("Package {} failed verification: {:?}", archive_path.display(), report);
+        return Err(format!("The package is damaged: {}", report.summary()));
+    }
+
+    on_progress(ImportProgress { stage: ImportStage::Installing, checked: None, total: None });
+    let target = content_dir.join(package_name(archive_path));
+    let backup = content_dir.join(PREVIOUS_DIR).join(package_name(archive_path));
+    let target_str = target.to_string_lossy().into_owned();
+    // The watcher would otherwise index the tree while it is half swapped
+    let _paused = pause_watcher(&target);
+    swap_into_place(&package_root, &target, &backup, || {
+        let mut db = database.connection()?;
+        register_content_path(&mut db, &target_str, manifest.as_ref(), activate)
+    })?;
+
+    info!("Imported {} into {} ({} files).", archive_path.display(), target.display(), report.checked);
+    Ok(ImportResult {
+        path: target_str,
+        title: manifest.as_ref().map(|m| m.title.clone()),
+        version: manifest.map(|m| m.version),
+        files: report.checked,
+    })
+}
+
+// Installs a zip or tar content package under the app data directory and registers it
+// as a content path. Nothing is left behind if any step fails.
+#[tauri::command]
+pub async fn import_content_package(
+    app: AppHandle,
+    database: State<'_, Database>,
+    archive_path: String,
+    activate: Option<bool>,
+) -> Result<ImportResult, String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Importing content package: {}", archive_path);
+    let database = database.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        import_package(&database, Path::new(&archive_path), activate.unwrap_or(false), &|progress| {
+            if let Err(e) = app.emit(IMPORT_PROGRESS_EVENT, progress) {
+                warn!("Failed to emit {} event: {}", IMPORT_PROGRESS_EVENT, e);
+            }
+        })
+        .inspect_err(|e| error!("Failed to import {}: {}", archive_path, e))
+    })
+    .await
+    .map_err(|e| {
+        error!("Content import task failed: {}", e);
+        e.to_string()
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // Fresh scratch directory for one test, with the archive beside an empty `dest`
+    fn scratch(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("osiris-test-package-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let dest = dir.join("dest");
+        fs::create_dir_all(&dest).unwrap();
+        (dir, dest)
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, body) in entries {
+            zip.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    // Names are written straight into the header, since `set_path` refuses ".." itself
+    fn tar_header(name: &str, entry_type: tar::EntryType, size: u64) -> tar::Header {
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_mode(0o644);
+        header.set_size(size);
+        header.set_cksum();
+        header
+    }
+
+    fn write_tar(path: &Path, entries: &[(&str, &str)]) {
+        let mut tar = tar::Builder::new(File::create(path).unwrap());
+        for (name, body) in entries {
+            tar.append(&tar_header(name, tar::EntryType::Regular, body.len() as u64), body.as_bytes()).unwrap();
+        }
+        tar.finish().unwrap();
+    }
+
+    #[test]
+    fn extract_unpacks_zip_and_tar_packages() {
+        let (dir, dest) = scratch("extract");
+        let entries = [("Class 8/notes.txt", "leaf"), ("checksums.sha256", "")];
+
+        write_zip(&dir.join("package.zip"), &entries);
+        extract(&dir.join("package.zip"), ArchiveKind::Zip, &dest.join("zip")).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("zip/Class 8/notes.txt")).unwrap(), "leaf");
+
+        write_tar(&dir.join("package.tar"), &entries);
+        fs::create_dir(dest.join("tar")).unwrap();
+        extract(&dir.join("package.tar"), ArchiveKind::Tar, &dest.join("tar")).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("tar/Class 8/notes.txt")).unwrap(), "leaf");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn extract_refuses_zip_entries_outside_the_destination() {
+        let (dir, dest) = scratch("zip-traversal");
+        write_zip(&dir.join("evil.zip"), &[("../evil.txt", "escaped")]);
+        let error = extract(&dir.join("evil.zip"), ArchiveKind::Zip, &dest).unwrap_err();
+        assert!(error.contains("points outside the package"), "{}", error);
+        assert!(!dir.join("evil.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn extract_refuses_tar_entries_outside_the_destination() {
+        let (dir, dest) = scratch("tar-traversal");
+        write_tar(&dir.join("evil.tar"), &[("../evil.txt", "escaped")]);
+        let error = extract(&dir.join("evil.tar"), ArchiveKind::Tar, &dest).unwrap_err();
+        assert!(error.contains("points outside the package"), "{}", error);
+        assert!(!dir.join("evil.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn extract_refuses_tar_links() {
+        let (dir, dest) = scratch("tar-link");
+        let mut tar = tar::Builder::new(File::create(dir.join("link.tar")).unwrap());
+        let mut header = tar_header("passwd", tar::EntryType::Symlink, 0);
+        header.set_link_name("/etc/passwd").unwrap();
+        header.set_cksum();
+        tar.append(&header, io::empty()).unwrap();
+        tar.finish().unwrap();
+        drop(tar);
+
+        let error = extract(&dir.join("link.tar"), ArchiveKind::Tar, &dest).unwrap_err();
+        assert!(error.contains("is a link"), "{}", error);
+        assert!(fs::symlink_metadata(dest.join("passwd")).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn restore_previous_installs_puts_back_an_interrupted_replacement() {
+        let (dir, _) = scratch("restore");
+        let previous = dir.join(PREVIOUS_DIR);
+        // Interrupted after the new install was moved in, before it was registered
+        fs::create_dir_all(previous.join("science/Class 8")).unwrap();
+        fs::write(previous.join("science/Class 8/notes.txt"), "old").unwrap();
+        fs::create_dir_all(dir.join("science")).unwrap();
+        fs::write(dir.join("science/partial.txt"), "new").unwrap();
+        // Interrupted while removing a backup that was already replaced
+        fs::create_dir_all(previous.join(format!("{}maths", DISCARDED_PREFIX))).unwrap();
+        fs::create_dir_all(dir.join("maths")).unwrap();
+        fs::write(dir.join("maths/notes.txt"), "new").unwrap();
+
+        restore_previous_installs(&dir);
+        assert_eq!(fs::read_to_string(dir.join("science/Class 8/notes.txt")).unwrap(), "old");
+        assert!(!dir.join("science/partial.txt").exists());
+        assert_eq!(fs::read_to_string(dir.join("maths/notes.txt")).unwrap(), "new");
+        assert_eq!(fs::read_dir(&previous).unwrap().count(), 0);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn swap_into_place_keeps_the_previous_install_until_commit() {
+        let (dir, _) = scratch("swap");
+        let (staged, target, backup) = (dir.join("staged"), dir.join("target"), dir.join("previous/target"));
+        fs::create_dir_all(dir.join("previous")).unwrap();
+        for (path, body) in [(&staged, "new"), (&target, "old")] {
+            fs::create_dir_all(path).unwrap();
+            fs::write(path.join("notes.txt"), body).unwrap();
+        }
+
+        let error = swap_into_place(&staged, &target, &backup, || {
+            assert_eq!(fs::read_to_string(backup.join("notes.txt")).unwrap(), "old");
+            Err("registration failed".to_string())
+        });
+        assert_eq!(error, Err("registration failed".to_string()));
+        assert_eq!(fs::read_to_string(target.join("notes.txt")).unwrap(), "old");
+
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("notes.txt"), "new").unwrap();
+        swap_into_place(&staged, &target, &backup, || Ok(())).unwrap();
+        assert_eq!(fs::read_to_string(target.join("notes.txt")).unwrap(), "new");
+        assert_eq!(fs::read_dir(dir.join("previous")).unwrap().count(), 0);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn package_name_strips_the_archive_extension() {
+        assert_eq!(package_name(Path::new("/media/Science Class 8.tar.gz")), "Science_Class_8");
+        assert_eq!(package_name(Path::new("maths.ZIP")), "maths");
+        assert_eq!(package_name(Path::new("..zip")), "package");
+    }
+}
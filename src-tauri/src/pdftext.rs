@@ -0,0 +1,195 @@
+// src-tauri/src/pdftext.rs
+
+use crate::content::resolve_in_content_roots;
+use crate::db::Database;
+use crate::encryption::{decrypt_bytes, looks_encrypted};
+use crate::roles::require_visible;
+use crate::thumbnail::file_stamp;
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::collections::VecDeque;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+// Encrypted documents whose text is kept in memory. Their text is never written to the
+// database, so it does not sit on disk in plain form.
+const MEMORY_CACHE_DOCUMENTS: usize = 16;
+
+struct CachedText {
+    path: PathBuf,
+    size: i64,
+    modified_at: i64,
+    pages: Arc<Vec<String>>,
+}
+
+// Most recently used first
+static MEMORY_CACHE: Mutex<VecDeque<CachedText>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PdfPageText {
+    pub page: u32, // 1-based
+    pub page_count: u32,
+    pub text: String,
+}
+
+// Reads the PDF into memory, decrypting it there when needed, and extracts the text of
+// every page. Nothing decrypted is written to disk.
+fn extract_pages(path: &Path) -> Result<Vec<String>, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let data = if looks_encrypted(path) { decrypt_bytes(&data)? } else { data };
+    // The extractor panics on some malformed documents; treat that like any other failure
+    match panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(&data)) {
+        Ok(Ok(pages)) => Ok(pages.iter().map(|text| text.trim().to_string()).collect()),
+        Ok(Err(e)) => Err(format!("Failed to extract text from {}: {}", path.display(), e)),
+        Err(_) => Err(format!("Failed to extract text from {}: unreadable PDF", path.display())),
+    }
+}
+
+fn memory_cached_pages(path: &Path, size: i64, modified_at: i64) -> Option<Arc<Vec<String>>> {
+    let mut cache = MEMORY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let index = cache
+        .iter()
+        .position(|entry| entry.path == path && entry.size == size && entry.modified_at == modified_at)?;
+    let entry = cache.remove(index)?;
+    let pages = entry.pages.clone();
+    cache.push_front(entry);
+    Some(pages)
+}
+
+fn memory_cache_pages(path: &Path, size: i64, modified_at: i64, pages: Arc<Vec<String>>) {
+    let mut cache = MEMORY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|entry| entry.path != path);
+    cache.push_front(CachedText { path: path.to_path_buf(), size, modified_at, pages });
+    cache.truncate(MEMORY_CACHE_DOCUMENTS);
+}
+
+fn stored_pages(db: &Connection, path: &Path, size: i64, modified_at: i64) -> Result<Option<Vec<String>>, String> {
+    let key = path.to_string_lossy();
+    let fresh = db
+        .query_row(
+            "SELECT 1 FROM PdfDocuments WHERE path = ?1 AND size = ?2 AND modified_at = ?3",
+            params![key, size, modified_at],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(|e| {
+            error!("Failed to look up PDF text of {}: {}", path.display(), e);
+            e.to_string()
+        })?;
+    if fresh.is_none() {
+        return Ok(None);
+    }
+    let mut stmt = db
+        .prepare_cached("SELECT text FROM PdfPages WHERE path = ?1 ORDER BY page")
+        .map_err(|e| e.to_string())?;
+    stmt.query_map(params![key], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map(Some)
+        .map_err(|e| {
+            error!("Failed to read PDF text of {}: {}", path.display(), e);
+            e.to_string()
+        })
+}
+
+// Replaces the cached pages of one document. A savepoint keeps this atomic whether or
+// not the caller already has a transaction open on `db`.
+fn store_pages(db: &Connection, path: &Path, size: i64, modified_at: i64, pages: &[String]) -> Result<(), String> {
+    let key = path.to_string_lossy();
+    let store = || -> rusqlite::Result<()> {
+        db.execute("DELETE FROM PdfDocuments WHERE path = ?1", params![key])?;
+        db.execute(
+            "INSERT INTO PdfDocuments (path, size, modified_at, page_count) VALUES (?1, ?2, ?3, ?4)",
+            params![key, size, modified_at, pages.len() as u32],
+        )?;
+        let mut stmt = db.prepare_cached("INSERT INTO PdfPages (path, page, text) VALUES (?1, ?2, ?3)")?;
+        for (index, text) in pages.iter().enumerate() {
+            stmt.execute(params![key, index as u32 + 1, text])?;
+        }
+        Ok(())
+    };
+    db.execute_batch("SAVEPOINT pdf_text")
+        .and_then(|_| store())
+        .and_then(|_| db.execute_batch("RELEASE pdf_text"))
+        .map_err(|e| {
+            let _ = db.execute_batch("ROLLBACK TO pdf_text; RELEASE pdf_text");
+            error!("Failed to cache PDF text of {}: {}", path.display(), e);
+            e.to_string()
+        })
+}
+
+// Text of every page of `path`, extracting it first if the cache is missing or stale.
+// Plain documents are cached in the database, encrypted ones only in memory. A pooled
+// connection is only held for the lookup and the insert, not while extracting.
+fn document_pages(database: &Database, path: &Path) -> Result<Arc<Vec<String>>, String> {
+    let (size, modified_at) = file_stamp(path)?;
+    let encrypted = looks_encrypted(path);
+    let cached = if encrypted {
+        memory_cached_pages(path, size, modified_at)
+    } else {
+        stored_pages(&*database.connection()?, path, size, modified_at)?.map(Arc::new)
+    };
+    if let Some(pages) = cached {
+        return Ok(pages);
+    }
+    let pages = extract_pages(path).map_err(|e| {
+        warn!("{}", e);
+        e
+    })?;
+    info!("Extracted text of {} pages from {}", pages.len(), path.display());
+    let pages = Arc::new(pages);
+    if encrypted {
+        memory_cache_pages(path, size, modified_at, pages.clone());
+    } else {
+        store_pages(&*database.connection()?, path, size, modified_at, &pages)?;
+    }
+    Ok(pages)
+}
+
+// Text of the whole document, pages separated by blank lines, for the search index
+pub fn pdf_document_text(database: &Database, path: &Path) -> Result<String, String> {
+    Ok(document_pages(database, path)?.join("\n\n"))
+}
+
+fn pdf_source(db: &Connection, path: &str) -> Result<std::path::PathBuf, String> {
+    let source = resolve_in_content_roots(db, path)?;
+    require_visible(db, &source)?;
+    let is_pdf = source
+        .extension()
+        .map(|e| e.to_string_lossy().eq_ignore_ascii_case("pdf"))
+        .unwrap_or(false);
+    if !is_pdf {
+        return Err(format!("{} is not a PDF", source.display()));
+    }
+    Ok(source)
+}
+
+#[tauri::command]
+pub async fn get_pdf_page_count(database: State<'_, Database>, path: String) -> Result<u32, String> {
+    database
+        .run_blocking(move |database| {
+            let source = pdf_source(&*database.connection()?, &path)?;
+            Ok(document_pages(database, &source)?.len() as u32)
+        })
+        .await
+}
+
+// Text of one page (1-based), for text-to-speech and screen readers
+#[tauri::command]
+pub async fn get_pdf_page_text(database: State<'_, Database>, path: String, page: u32) -> Result<PdfPageText, String> {
+    database
+        .run_blocking(move |database| {
+            let source = pdf_source(&*database.connection()?, &path)?;
+            let pages = document_pages(database, &source)?;
+            let page_count = pages.len() as u32;
+            if page == 0 || page > page_count {
+                return Err(format!("Page {} is out of range; {} has {} pages.", page, source.display(), page_count));
+            }
+            let text = pages[page as usize - 1].clone();
+            Ok(PdfPageText { page, page_count, text })
+        })
+        .await
+}
@@ -0,0 +1,539 @@
+// src-tauri/src/progress.rs
+
+use crate::content::get_active_content_root;
+use crate::db::Database;
+use crate::indexer::{content_item_from_row, ContentItem, ITEM_COLUMNS};
+use crate::roles::{require_own_progress, require_progress_access};
+use crate::roster::require_section_access;
+use crate::xapi::{record_statement, Verb};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Result, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::State;
+
+pub const STATUS_IN_PROGRESS: &str = "in-progress";
+pub const STATUS_COMPLETED: &str = "completed";
+
+// Watching this much of a video counts as finishing it (credits, outros)
+const VIDEO_COMPLETION_THRESHOLD: i64 = 95;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentProgressEntry {
+    pub user_id: i64,
+    pub content_item_id: i64,
+    pub folder_id: Option<i64>,
+    pub completion_percentage: i64,
+    pub status: String,
+    pub position_seconds: Option<f64>,
+    pub duration_seconds: Option<f64>,
+    pub pages_viewed: Vec<i64>,
+    pub last_page: Option<i64>,
+    pub page_count: Option<i64>,
+    pub quiz_score: Option<i64>,
+    pub quiz_total: Option<i64>,
+    pub open_count: i64,
+    pub time_spent_seconds: f64,
+    pub last_opened_at: Option<String>,
+    pub last_closed_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl ContentProgressEntry {
+    fn new(user_id: i64, content_item_id: i64, folder_id: i64) -> Self {
+        ContentProgressEntry {
+            user_id,
+            content_item_id,
+            folder_id: Some(folder_id),
+            completion_percentage: 0,
+            status: STATUS_IN_PROGRESS.to_string(),
+            position_seconds: None,
+            duration_seconds: None,
+            pages_viewed: Vec::new(),
+            last_page: None,
+            page_count: None,
+            quiz_score: None,
+            quiz_total: None,
+            open_count: 0,
+            time_spent_seconds: 0.0,
+            last_opened_at: None,
+            last_closed_at: None,
+            updated_at: None,
+        }
+    }
+
+    // Percentage never goes down, so re-watching the start of a video keeps earlier progress
+    fn raise_completion(&mut self, percentage: i64) {
+        self.completion_percentage = self.completion_percentage.max(percentage.clamp(0, 100));
+        if self.completion_percentage >= 100 {
+            self.status = STATUS_COMPLETED.to_string();
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResumeEntry {
+    pub item: ContentItem,
+    pub progress: ContentProgressEntry,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum RollupLevel {
+    Chapter,
+    Subject,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgressRollup {
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub chapter: Option<String>,
+    pub total_items: i64,
+    pub completed_items: i64,
+    pub completion_percentage: i64,
+}
+
+const PROGRESS_COLUMNS: &str = "user_id, content_item_id, folder_id, completion_percentage, status,
+    position_seconds, duration_seconds, pages_viewed, last_page, page_count, quiz_score, quiz_total, updated_at,
+    open_count, time_spent_seconds, last_opened_at, last_closed_at";
+
+fn progress_from_row(row: &rusqlite::Row) -> Result<ContentProgressEntry> {
+    let pages_viewed: Option<String> = row.get(7)?;
+    Ok(ContentProgressEntry {
+        user_id: row.get(0)?,
+        content_item_id: row.get(1)?,
+        folder_id: row.get(2)?,
+        completion_percentage: row.get::<_, Option<i64>>(3)?.unwrap_or(0),
+        status: row.get::<_, Option<String>>(4)?.unwrap_or_else(|| STATUS_IN_PROGRESS.to_string()),
+        position_seconds: row.get(5)?,
+        duration_seconds: row.get(6)?,
+        pages_viewed: pages_viewed
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        last_page: row.get(8)?,
+        page_count: row.get(9)?,
+        quiz_score: row.get(10)?,
+        quiz_total: row.get(11)?,
+        updated_at: row.get(12)?,
+        open_count: row.get::<_, Option<i64>>(13)?.unwrap_or(0),
+        time_spent_seconds: row.get::<_, Option<f64>>(14)?.unwrap_or(0.0),
+        last_opened_at: row.get(15)?,
+        last_closed_at: row.get(16)?,
+    })
+}
+
+fn load_progress(db: &Connection, user_id: i64, content_item_id: i64) -> Result<Option<ContentProgressEntry>, String> {
+    db.query_row(
+        &format!(
+            "SELECT {} FROM ContentProgress WHERE user_id = ?1 AND content_item_id = ?2",
+            PROGRESS_COLUMNS
+        ),
+        params![user_id, content_item_id],
+        progress_from_row,
+    )
+    .optional()
+    .map_err(|e| {
+        error!("Failed to load progress of item {} for user {}: {}", content_item_id, user_id, e);
+        e.to_string()
+    })
+}
+
+// Learning analytics are secondary to progress, so a failed statement is only logged
+fn record_learning_statement(db: &Connection, user_id: i64, content_item_id: i64, verb: Verb, result: Option<Value>) {
+    if let Err(e) = record_statement(db, user_id, content_item_id, verb, result) {
+        warn!("Failed to record xAPI statement for item {}: {}", content_item_id, e);
+    }
+}
+
+// Loads the current progress (or a fresh entry), applies `update` and writes it back.
+// The write lock is taken before the read, so concurrent updates (a position report
+// racing a viewer close or a SCORM commit) apply one after the other instead of one
+// overwriting the other.
+fn update_progress<F>(db: &mut Connection, user_id: i64, content_item_id: i64, update: F) -> Result<ContentProgressEntry, String>
+where
+    F: FnOnce(&mut ContentProgressEntry),
+{
+    let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| {
+        error!("Failed to start transaction for progress of item {}: {}", content_item_id, e);
+        e.to_string()
+    })?;
+    let folder_id: i64 = tx
+        .query_row(
+            "SELECT folder_id FROM ContentItems WHERE id = ?1",
+            params![content_item_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| {
+            error!("Failed to look up content item {}: {}", content_item_id, e);
+            e.to_string()
+        })?
+        .ok_or_else(|| {
+            warn!("Content item {} not found when recording progress.", content_item_id);
+            format!("Content item with id {} not found.", content_item_id)
+        })?;
+
+    let mut entry = load_progress(&tx, user_id, content_item_id)?
+        .unwrap_or_else(|| ContentProgressEntry::new(user_id, content_item_id, folder_id));
+    update(&mut entry);
+
+    let pages_viewed = serde_json::to_string(&entry.pages_viewed).map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO ContentProgress
+            (user_id, content_item_id, folder_id, completion_percentage, status,
+             position_seconds, duration_seconds, pages_viewed, last_page, page_count, quiz_score, quiz_total,
+             open_count, time_spent_seconds, last_opened_at, last_closed_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, CURRENT_TIMESTAMP)
+         ON CONFLICT(user_id, content_item_id) DO UPDATE SET
+            folder_id = excluded.folder_id,
+            completion_percentage = excluded.completion_percentage,
+            status = excluded.status,
+            position_seconds = excluded.position_seconds,
+            duration_seconds = excluded.duration_seconds,
+            pages_viewed = excluded.pages_viewed,
+            last_page = excluded.last_page,
+            page_count = excluded.page_count,
+            quiz_score = excluded.quiz_score,
+            quiz_total = excluded.quiz_total,
+            open_count = excluded.open_count,
+            time_spent_seconds = excluded.time_spent_seconds,
+            last_opened_at = excluded.last_opened_at,
+            last_closed_at = excluded.last_closed_at,
+            updated_at = CURRENT_TIMESTAMP",
+        params![
+            entry.user_id,
+            entry.content_item_id,
+            folder_id,
+            entry.completion_percentage,
+            entry.status,
+            entry.position_seconds,
+            entry.duration_seconds,
+            pages_viewed,
+            entry.last_page,
+            entry.page_count,
+            entry.quiz_score,
+            entry.quiz_total,
+            entry.open_count,
+            entry.time_spent_seconds,
+            entry.last_opened_at,
+            entry.last_closed_at,
+        ],
+    )
+    .map_err(|e| {
+        error!("Failed to save progress of item {} for user {}: {}", content_item_id, user_id, e);
+        e.to_string()
+    })?;
+
+    let saved = load_progress(&tx, user_id, content_item_id)?
+        .ok_or_else(|| format!("Progress for content item {} was not saved.", content_item_id))?;
+    tx.commit().map_err(|e| {
+        error!("Failed to commit progress of item {} for user {}: {}", content_item_id, user_id, e);
+        e.to_string()
+    })?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn record_video_progress(
+    database: State<'_, Database>,
+    user_id: i64,
+    content_item_id: i64,
+    position_seconds: f64,
+    duration_seconds: f64,
+) -> Result<ContentProgressEntry, String> {
+    require_own_progress(user_id)?;
+    info!(
+        "Recording video progress for user {} on item {}: {:.1}/{:.1}s",
+        user_id, content_item_id, position_seconds, duration_seconds
+    );
+    if !duration_seconds.is_finite() || duration_seconds <= 0.0 || position_seconds < 0.0 {
+        return Err("Video position and duration must be positive.".to_string());
+    }
+    database
+        .run(move |db| {
+            let mut finished_now = false;
+            let entry = update_progress(db, user_id, content_item_id, |entry| {
+                let position = position_seconds.min(duration_seconds);
+                entry.position_seconds = Some(position);
+                entry.duration_seconds = Some(duration_seconds);
+                let percentage = ((position / duration_seconds) * 100.0).round() as i64;
+                let was_complete = entry.completion_percentage >= 100;
+                entry.raise_completion(if percentage >= VIDEO_COMPLETION_THRESHOLD { 100 } else { percentage });
+                finished_now = !was_complete && entry.completion_percentage >= 100;
+            })?;
+            if finished_now {
+                record_learning_statement(db, user_id, content_item_id, Verb::Completed, Some(json!({ "completion": true })));
+            }
+            Ok(entry)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn record_pdf_page_view(
+    database: State<'_, Database>,
+    user_id: i64,
+    content_item_id: i64,
+    page: i64,
+    page_count: i64,
+) -> Result<ContentProgressEntry, String> {
+    require_own_progress(user_id)?;
+    info!("Recording page {}/{} for user {} on item {}", page, page_count, user_id, content_item_id);
+    if page_count <= 0 || page < 1 || page > page_count {
+        return Err(format!("Page {} is outside 1..={}.", page, page_count));
+    }
+    database
+        .run(move |db| {
+            update_progress(db, user_id, content_item_id, |entry| {
+                if !entry.pages_viewed.contains(&page) {
+                    entry.pages_viewed.push(page);
+                    entry.pages_viewed.sort_unstable();
+                }
+                // The last page viewed is where reading resumes
+                entry.last_page = Some(page);
+                entry.page_count = Some(page_count);
+                let viewed = entry.pages_viewed.iter().filter(|p| **p <= page_count).count() as i64;
+                entry.raise_completion(viewed * 100 / page_count);
+            })
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn record_quiz_completion(
+    database: State<'_, Database>,
+    user_id: i64,
+    content_item_id: i64,
+    score: i64,
+    total: i64,
+) -> Result<ContentProgressEntry, String> {
+    require_own_progress(user_id)?;
+    info!("Recording quiz result {}/{} for user {} on item {}", score, total, user_id, content_item_id);
+    if total < 0 || score < 0 || score > total {
+        return Err(format!("Invalid quiz score {}/{}.", score, total));
+    }
+    database
+        .run(move |db| {
+            let entry = update_progress(db, user_id, content_item_id, |entry| {
+                // Keep the best attempt
+                if entry.quiz_score.is_none_or(|best| score >= best) {
+                    entry.quiz_score = Some(score);
+                    entry.quiz_total = Some(total);
+                }
+                entry.raise_completion(100);
+            })?;
+            // Every submission is a statement, not just the best one
+            let mut result = json!({ "score": { "raw": score, "min": 0, "max": total }, "completion": true });
+            if total > 0 {
+                result["score"]["scaled"] = json!(score as f64 / total as f64);
+            }
+            record_learning_statement(db, user_id, content_item_id, Verb::Completed, Some(result));
+            Ok(entry)
+        })
+        .await
+}
+
+// Completion and score reported by an interactive (SCORM) package through its runtime API
+pub fn record_package_progress(
+    db: &mut Connection,
+    user_id: i64,
+    content_item_id: i64,
+    completion_percentage: i64,
+    score: Option<(i64, i64)>,
+) -> Result<ContentProgressEntry, String> {
+    info!(
+        "Recording package progress {}% for user {} on item {}",
+        completion_percentage, user_id, content_item_id
+    );
+    let score = score.filter(|(score, total)| *total > 0 && (0..=*total).contains(score));
+    update_progress(db, user_id, content_item_id, |entry| {
+        if let Some((score, total)) = score {
+            if entry.quiz_score.is_none_or(|best| score >= best) {
+                entry.quiz_score = Some(score);
+                entry.quiz_total = Some(total);
+            }
+        }
+        entry.raise_completion(completion_percentage);
+    })
+}
+
+// Same format as SQLite's CURRENT_TIMESTAMP, so stored times sort and compare alike
+fn current_timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// A viewing session started in the in-app viewer
+pub fn record_content_opened(db: &mut Connection, user_id: i64, content_item_id: i64) -> Result<ContentProgressEntry, String> {
+    info!("Recording that user {} opened item {}", user_id, content_item_id);
+    let entry = update_progress(db, user_id, content_item_id, |entry| {
+        entry.open_count += 1;
+        entry.last_opened_at = Some(current_timestamp());
+    })?;
+    record_learning_statement(db, user_id, content_item_id, Verb::Launched, None);
+    Ok(entry)
+}
+
+// A viewing session ended after `seconds` in the in-app viewer
+pub fn record_content_closed(db: &mut Connection, user_id: i64, content_item_id: i64, seconds: f64) -> Result<ContentProgressEntry, String> {
+    info!("Recording that user {} closed item {} after {:.0}s", user_id, content_item_id, seconds);
+    update_progress(db, user_id, content_item_id, |entry| {
+        entry.time_spent_seconds += seconds.max(0.0);
+        entry.last_closed_at = Some(current_timestamp());
+    })
+}
+
+#[tauri::command]
+pub async fn get_content_progress(database: State<'_, Database>, user_id: i64, content_item_id: i64) -> Result<Option<ContentProgressEntry>, String> {
+    require_progress_access(user_id)?;
+    info!("Retrieving progress of item {} for user {}", content_item_id, user_id);
+    database.run(move |db| load_progress(db, user_id, content_item_id)).await
+}
+
+// The most recently touched item the user has not finished in the active content path
+#[tauri::command]
+pub async fn get_resume_item(database: State<'_, Database>, user_id: i64) -> Result<Option<ResumeEntry>, String> {
+    require_progress_access(user_id)?;
+    info!("Finding resume point for user {}", user_id);
+    database
+        .run(move |db| {
+            let (folder_id, root) = match get_active_content_root(db)? {
+                Some(active) => active,
+                None => return Ok(None),
+            };
+            let content_item_id: Option<i64> = db
+                .query_row(
+                    "SELECT cp.content_item_id FROM ContentProgress cp
+                     JOIN ContentItems ci ON ci.id = cp.content_item_id
+                     WHERE cp.user_id = ?1 AND ci.folder_id = ?2 AND ci.missing_at IS NULL AND cp.status != ?3
+                     ORDER BY cp.updated_at DESC, cp.id DESC
+                     LIMIT 1",
+                    params![user_id, folder_id, STATUS_COMPLETED],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| {
+                    error!("Failed to query resume point for user {}: {}", user_id, e);
+                    e.to_string()
+                })?;
+
+            let Some(content_item_id) = content_item_id else {
+                info!("No unfinished content for user {}", user_id);
+                return Ok(None);
+            };
+
+            let item = db
+                .query_row(
+                    &format!("SELECT {} FROM ContentItems WHERE id = ?1", ITEM_COLUMNS),
+                    params![content_item_id],
+                    |row| content_item_from_row(row, &root),
+                )
+                .map_err(|e| {
+                    error!("Failed to load content item {} for resume point: {}", content_item_id, e);
+                    e.to_string()
+                })?;
+            let progress = load_progress(db, user_id, content_item_id)?
+                .ok_or_else(|| format!("Progress for content item {} not found.", content_item_id))?;
+
+            Ok(Some(ResumeEntry { item, progress }))
+        })
+        .await
+}
+
+// Progress of every student in a section, optionally for a single content item
+#[tauri::command]
+pub async fn get_section_progress(database: State<'_, Database>, section_id: i64, content_item_id: Option<i64>) -> Result<Vec<ContentProgressEntry>, String> {
+    database
+        .run(move |db| {
+            require_section_access(db, section_id)?;
+            info!("Retrieving progress of section {} (item {:?})", section_id, content_item_id);
+            let mut stmt = db
+                .prepare(&format!(
+                    "SELECT {} FROM ContentProgress
+                     WHERE user_id IN (SELECT id FROM Users WHERE section_id = ?1 AND deleted_at IS NULL)
+                       AND (?2 IS NULL OR content_item_id = ?2)
+                     ORDER BY user_id, content_item_id",
+                    PROGRESS_COLUMNS
+                ))
+                .map_err(|e| {
+                    error!("Failed to prepare statement for section progress: {}", e);
+                    e.to_string()
+                })?;
+            let entries = stmt
+                .query_map(params![section_id, content_item_id], progress_from_row)
+                .and_then(|rows| rows.collect::<Result<Vec<_>>>())
+                .map_err(|e| {
+                    error!("Failed to query progress of section {}: {}", section_id, e);
+                    e.to_string()
+                })?;
+            Ok(entries)
+        })
+        .await
+}
+
+// Percentage complete per chapter or subject, averaged over every indexed item in it.
+// Items the user has never opened count as 0%.
+#[tauri::command]
+pub async fn get_progress_rollup(
+    database: State<'_, Database>,
+    user_id: i64,
+    level: RollupLevel,
+    class_name: Option<String>,
+    subject: Option<String>,
+) -> Result<Vec<ProgressRollup>, String> {
+    require_progress_access(user_id)?;
+    info!("Computing {:?} progress rollup for user {}", level, user_id);
+    database
+        .run(move |db| {
+            let (folder_id, _) = match get_active_content_root(db)? {
+                Some(active) => active,
+                None => return Ok(Vec::new()),
+            };
+            let (chapter_column, group_by) = match level {
+                RollupLevel::Chapter => ("ci.chapter", "ci.class_name, ci.subject, ci.chapter"),
+                RollupLevel::Subject => ("NULL", "ci.class_name, ci.subject"),
+            };
+            let sql = format!(
+                "SELECT ci.class_name, ci.subject, {chapter_column},
+                        COUNT(*),
+                        SUM(CASE WHEN cp.status = ?5 THEN 1 ELSE 0 END),
+                        AVG(COALESCE(cp.completion_percentage, 0))
+                 FROM ContentItems ci
+                 LEFT JOIN ContentProgress cp ON cp.content_item_id = ci.id AND cp.user_id = ?1
+                 WHERE ci.folder_id = ?2 AND ci.missing_at IS NULL
+                   AND (?3 IS NULL OR ci.class_name = ?3)
+                   AND (?4 IS NULL OR ci.subject = ?4)
+                 GROUP BY {group_by}
+                 ORDER BY {group_by}"
+            );
+
+            let mut stmt = db.prepare(&sql).map_err(|e| {
+                error!("Failed to prepare statement for progress rollup: {}", e);
+                e.to_string()
+            })?;
+            let rollups = stmt
+                .query_map(params![user_id, folder_id, class_name, subject, STATUS_COMPLETED], |row| {
+                    Ok(ProgressRollup {
+                        class_name: row.get(0)?,
+                        subject: row.get(1)?,
+                        chapter: row.get(2)?,
+                        total_items: row.get(3)?,
+                        completed_items: row.get(4)?,
+                        completion_percentage: row.get::<_, f64>(5)?.round() as i64,
+                    })
+                })
+                .map_err(|e| {
+                    error!("Failed to query map for progress rollup: {}", e);
+                    e.to_string()
+                })?
+                .collect::<Result<Vec<ProgressRollup>, _>>()
+                .map_err(|e| {
+                    error!("Failed to collect progress rollup: {}", e);
+                    e.to_string()
+                })?;
+
+            info!("Computed {} progress rollup rows for user {}", rollups.len(), user_id);
+            Ok(rollups)
+        })
+        .await
+}
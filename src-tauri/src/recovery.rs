@@ -0,0 +1,119 @@
+// src-tauri/src/recovery.rs
+
+use crate::db::Database;
+use crate::roles::{require_permission, Permission, Role};
+use log::{error, info, warn};
+use rand::Rng;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+// Offline admin recovery. A recovery code is shown once at activation, or when an admin
+// generates a new one, and only its hash is stored. Entering it on the login screen
+// restores admin access without the activation server, and the code is then replaced.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const RECOVERY_CODE_GROUPS: usize = 4;
+const RECOVERY_CODE_GROUP_LENGTH: usize = 4;
+
+// Groups of characters that are hard to misread, e.g. "K7QM-2XHP-..."
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_GROUPS)
+        .map(|_| {
+            (0..RECOVERY_CODE_GROUP_LENGTH)
+                .map(|_| RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Case, dashes and spaces do not matter when a code is typed back in
+fn code_hash(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    Sha256::digest(normalized.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Replaces the stored recovery code with a new one and returns it. The caller shows it
+// to the admin; it cannot be read back later.
+pub fn replace_recovery_code(db: &Connection) -> Result<String, String> {
+    let code = generate_code();
+    db.execute(
+        "INSERT INTO AdminRecovery (id, code_hash) VALUES (1, ?1)
+         ON CONFLICT(id) DO UPDATE SET code_hash = excluded.code_hash, created_at = CURRENT_TIMESTAMP",
+        params![code_hash(&code)],
+    )
+    .map_err(|e| {
+        error!("Failed to store admin recovery code: {}", e);
+        e.to_string()
+    })?;
+    info!("Admin recovery code replaced.");
+    Ok(code)
+}
+
+#[tauri::command]
+pub async fn regenerate_recovery_code(database: State<'_, Database>) -> Result<String, String> {
+    let admin = require_permission(Permission::ManageUsers)?;
+    info!("{} is generating a new admin recovery code.", admin.username);
+    database.run(|db| replace_recovery_code(db)).await
+}
+
+// Makes `username` an admin with `new_password`, creating the account if needed, when
+// `recovery_code` is the current code. Returns the code that replaces it.
+#[tauri::command]
+pub async fn recover_admin_access(database: State<'_, Database>, recovery_code: String, username: String, new_password: String) -> Result<String, String> {
+    let username = username.trim().to_string();
+    info!("Attempting admin recovery for {}", username);
+    if username.is_empty() || new_password.is_empty() {
+        return Err("Username and new password are required.".to_string());
+    }
+    database
+        .run(move |db| {
+            let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+
+            let stored_hash: Option<String> = tx
+                .query_row("SELECT code_hash FROM AdminRecovery WHERE id = 1", [], |row| row.get(0))
+                .optional()
+                .map_err(|e| e.to_string())?;
+            let Some(stored_hash) = stored_hash else {
+                warn!("Admin recovery attempted, but no recovery code is set up.");
+                return Err("No recovery code has been set up on this device.".to_string());
+            };
+            if stored_hash != code_hash(&recovery_code) {
+                warn!("Admin recovery for {} failed: wrong recovery code.", username);
+                return Err("Invalid recovery code.".to_string());
+            }
+
+            // Admins have no section or roll number. A deleted account is restored.
+            let updated = tx
+                .execute(
+                    "UPDATE Users SET password = ?1, role = ?2, section_id = NULL, roll_number = NULL,
+                            deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+                     WHERE username = ?3",
+                    params![new_password, Role::Admin, username],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                tx.execute(
+                    "INSERT INTO Users (username, password, role) VALUES (?1, ?2, ?3)",
+                    params![username, new_password, Role::Admin],
+                )
+                .map_err(|e| {
+                    error!("Failed to create recovered admin {}: {}", username, e);
+                    e.to_string()
+                })?;
+            }
+            let new_code = replace_recovery_code(&tx)?;
+            tx.commit().map_err(|e| {
+                error!("Failed to commit admin recovery for {}: {}", username, e);
+                e.to_string()
+            })?;
+            info!("Admin access recovered for {}", username);
+            Ok(new_code)
+        })
+        .await
+}
@@ -0,0 +1,274 @@
+// src-tauri/src/roles.rs
+
+use crate::db::Database;
+use crate::indexer::{classify_file, is_package_dir, ContentType};
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::State;
+
+// Roles stored in Users.role. The CHECK constraint on that column lists the same values.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Teacher,
+    Student,
+    Guest, // Shared kiosk login: browses content, nothing is recorded
+}
+
+// What a role may do. Commands check these through require_permission.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ManageUsers,    // Users, CSV import/export, classes, sections and teacher assignments
+    ManageContent,  // Content paths, indexing, packages and updates
+    ManageSettings, // Content categories each role sees
+    ViewReports,    // Rosters, section progress and xAPI statements
+    TrackProgress,  // The user's own progress is recorded
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::Teacher, Role::Student, Role::Guest];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Teacher => "teacher",
+            Role::Student => "student",
+            Role::Guest => "guest",
+        }
+    }
+
+    // Case-insensitive; "kiosk" is accepted for the guest role
+    pub fn parse(value: &str) -> Option<Role> {
+        match value.trim().to_lowercase().as_str() {
+            "admin" => Some(Role::Admin),
+            "teacher" => Some(Role::Teacher),
+            "student" => Some(Role::Student),
+            "guest" | "kiosk" => Some(Role::Guest),
+            _ => None,
+        }
+    }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Admin => &[
+                Permission::ManageUsers,
+                Permission::ManageContent,
+                Permission::ManageSettings,
+                Permission::ViewReports,
+                Permission::TrackProgress,
+            ],
+            Role::Teacher => &[Permission::ViewReports, Permission::TrackProgress],
+            Role::Student => &[Permission::TrackProgress],
+            Role::Guest => &[],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl rusqlite::types::FromSql for Role {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        let text = value.as_str()?;
+        Role::parse(text).ok_or_else(|| rusqlite::types::FromSqlError::Other(format!("unknown role '{}'", text).into()))
+    }
+}
+
+impl rusqlite::types::ToSql for Role {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+// The signed-in user. Set by perform_login and cleared by logout.
+#[derive(Debug, Clone)]
+pub struct SessionUser {
+    pub id: i64,
+    pub username: String,
+    pub role: Role,
+}
+
+static SESSION: Mutex<Option<SessionUser>> = Mutex::new(None);
+
+pub fn start_session(user: SessionUser) {
+    info!("Session started for {} ({})", user.username, user.role.as_str());
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = Some(user);
+}
+
+pub fn end_session() {
+    if let Some(user) = SESSION.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        info!("Session ended for {}", user.username);
+    }
+}
+
+pub fn current_user() -> Option<SessionUser> {
+    SESSION.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+// The signed-in user, if their role grants `permission`
+pub fn require_permission(permission: Permission) -> Result<SessionUser, String> {
+    let user = current_user().ok_or_else(|| "Not signed in.".to_string())?;
+    if !user.role.has_permission(permission) {
+        warn!("Denied {:?} to {} ({})", permission, user.username, user.role.as_str());
+        return Err(format!("Permission denied: the {} role cannot do this.", user.role.as_str()));
+    }
+    Ok(user)
+}
+
+// Progress is recorded only for the signed-in user, and only if their role tracks it
+pub fn require_own_progress(user_id: i64) -> Result<SessionUser, String> {
+    let user = require_permission(Permission::TrackProgress)?;
+    if user.id != user_id {
+        warn!("User {} tried to record progress for user {}", user.id, user_id);
+        return Err("Permission denied: progress can only be recorded for yourself.".to_string());
+    }
+    Ok(user)
+}
+
+// Users read their own progress; reports of other users need ViewReports
+pub fn require_progress_access(user_id: i64) -> Result<SessionUser, String> {
+    let user = current_user().ok_or_else(|| "Not signed in.".to_string())?;
+    if user.id == user_id {
+        return Ok(user);
+    }
+    require_permission(Permission::ViewReports)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoleCategories {
+    pub role: Role,
+    pub categories: Vec<ContentType>,
+}
+
+const ALL_CONTENT_TYPES: [ContentType; 8] = [
+    ContentType::Video,
+    ContentType::Animation,
+    ContentType::Notes,
+    ContentType::Textbook,
+    ContentType::Quiz,
+    ContentType::Activity,
+    ContentType::Assessment,
+    ContentType::Interactive,
+];
+
+// Categories `role` sees. Categories without a setting are visible; admins see everything.
+fn role_categories(db: &Connection, role: Role) -> Result<Vec<ContentType>, String> {
+    if role == Role::Admin {
+        return Ok(ALL_CONTENT_TYPES.to_vec());
+    }
+    let mut stmt = db
+        .prepare_cached("SELECT content_type FROM RoleContentCategories WHERE role = ?1 AND visible = 0")
+        .map_err(|e| e.to_string())?;
+    let hidden = stmt
+        .query_map(params![role], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| {
+            error!("Failed to read content categories of role {}: {}", role.as_str(), e);
+            e.to_string()
+        })?;
+    Ok(ALL_CONTENT_TYPES.into_iter().filter(|t| !hidden.iter().any(|h| h == t.as_str())).collect())
+}
+
+// Visible categories of the signed-in user's role, for filtering many entries at once
+pub fn visible_content_types(db: &Connection) -> Result<Vec<ContentType>, String> {
+    let role = current_user().map(|user| user.role).unwrap_or(Role::Guest);
+    role_categories(db, role)
+}
+
+// Category of a file or package folder in the content tree. Files inside a package belong
+// to the package, so its pages and media are hidden along with it.
+fn content_type_of(path: &Path) -> Option<ContentType> {
+    if path.ancestors().any(is_package_dir) {
+        return Some(ContentType::Interactive);
+    }
+    classify_file(&path.file_name()?.to_string_lossy())
+}
+
+// Refuses content in a category hidden from the signed-in user's role, for commands that
+// open a single path. Files outside every category are allowed.
+pub fn require_visible(db: &Connection, path: &Path) -> Result<(), String> {
+    let Some(content_type) = content_type_of(path) else {
+        return Ok(());
+    };
+    if visible_content_types(db)?.contains(&content_type) {
+        return Ok(());
+    }
+    warn!("Refused {} content hidden from the current role: {}", content_type.as_str(), path.display());
+    Err("This content is not available to your role.".to_string())
+}
+
+// Categories of every role for users who manage settings; anyone else only gets their own
+#[tauri::command]
+pub async fn get_role_content_categories(database: State<'_, Database>) -> Result<Vec<RoleCategories>, String> {
+    let role = current_user().map(|user| user.role).unwrap_or(Role::Guest);
+    let roles = if role.has_permission(Permission::ManageSettings) { Role::ALL.to_vec() } else { vec![role] };
+    info!("Retrieving content categories of roles {:?}.", roles.iter().map(Role::as_str).collect::<Vec<_>>());
+    database
+        .run(move |db| {
+            roles
+                .iter()
+                .map(|role| Ok(RoleCategories { role: *role, categories: role_categories(db, *role)? }))
+                .collect()
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn set_role_content_categories(database: State<'_, Database>, role: Role, categories: Vec<ContentType>) -> Result<(), String> {
+    require_permission(Permission::ManageSettings)?;
+    info!("Setting content categories of role {}: {:?}", role.as_str(), categories);
+    if role == Role::Admin {
+        return Err("Admins always see every content category.".to_string());
+    }
+    database
+        .run(move |db| {
+            let tx = db.transaction().map_err(|e| e.to_string())?;
+            for content_type in ALL_CONTENT_TYPES {
+                tx.execute(
+                    "INSERT INTO RoleContentCategories (role, content_type, visible) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(role, content_type) DO UPDATE SET visible = excluded.visible",
+                    params![role, content_type.as_str(), categories.contains(&content_type)],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            tx.commit().map_err(|e| {
+                error!("Failed to save content categories of role {}: {}", role.as_str(), e);
+                e.to_string()
+            })
+        })
+        .await
+}
+
+// Permissions of the signed-in user, for the frontend to show or hide actions
+#[tauri::command]
+pub fn get_my_permissions() -> Result<Vec<Permission>, String> {
+    Ok(current_user().map(|user| user.role.permissions().to_vec()).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::SCORM_MANIFEST;
+    use std::fs;
+
+    #[test]
+    fn content_type_of_files_inside_a_package_is_the_package_type() {
+        let root = std::env::temp_dir().join(format!("osiris-test-roles-{}", std::process::id()));
+        let package = root.join("Class 8/Science/Photosynthesis");
+        fs::create_dir_all(package.join("media")).unwrap();
+        fs::write(package.join(SCORM_MANIFEST), "<manifest/>").unwrap();
+
+        assert_eq!(content_type_of(&package), Some(ContentType::Interactive));
+        assert_eq!(content_type_of(&package.join("media/intro.mp4")), Some(ContentType::Interactive));
+        assert_eq!(content_type_of(&root.join("Class 8/Science/Chapter 1 textbook.pdf")), Some(ContentType::Textbook));
+        assert_eq!(content_type_of(&root.join("Class 8/Science/quiz.xml")), Some(ContentType::Quiz));
+        assert_eq!(content_type_of(&root.join("Class 8/Science/cover.png")), None);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,265 @@
+// src-tauri/src/roster.rs
+
+use crate::db::Database;
+use crate::roles::{current_user, require_permission, Permission, Role, SessionUser};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+// A section of a class, shown as "Class 8 - B"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SectionInfo {
+    pub id: i64,
+    pub class_id: i64,
+    pub class_name: String,
+    pub name: String,
+    pub label: String,
+    pub student_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RosterEntry {
+    pub user_id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub roll_number: Option<String>,
+}
+
+pub fn section_label(class_name: &str, section_name: &str) -> String {
+    format!("{} - {}", class_name, section_name)
+}
+
+// Columns and joins selected by section_from_row
+const SECTION_QUERY: &str = "SELECT s.id, s.class_id, c.name, s.name,
+        (SELECT COUNT(*) FROM Users u WHERE u.section_id = s.id AND u.deleted_at IS NULL)
+     FROM Sections s JOIN Classes c ON c.id = s.class_id";
+
+fn section_from_row(row: &rusqlite::Row) -> rusqlite::Result<SectionInfo> {
+    let class_name: String = row.get(2)?;
+    let name: String = row.get(3)?;
+    Ok(SectionInfo {
+        id: row.get(0)?,
+        class_id: row.get(1)?,
+        label: section_label(&class_name, &name),
+        class_name,
+        name,
+        student_count: row.get(4)?,
+    })
+}
+
+fn query_sections(db: &Connection, filter: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<SectionInfo>, String> {
+    let sql = format!("{} {} ORDER BY c.name, s.name", SECTION_QUERY, filter);
+    let mut stmt = db.prepare(&sql).map_err(|e| {
+        error!("Failed to prepare statement for sections: {}", e);
+        e.to_string()
+    })?;
+    let sections = stmt
+        .query_map(params, section_from_row)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| {
+            error!("Failed to query sections: {}", e);
+            e.to_string()
+        })?;
+    Ok(sections)
+}
+
+fn user_role(db: &Connection, user_id: i64) -> Result<Role, String> {
+    db.query_row("SELECT role FROM Users WHERE id = ?1 AND deleted_at IS NULL", params![user_id], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("User with ID {} not found.", user_id))
+}
+
+// Admins see every section's reports; teachers only those of sections assigned to them
+pub fn require_section_access(db: &Connection, section_id: i64) -> Result<SessionUser, String> {
+    let user = require_permission(Permission::ViewReports)?;
+    if user.role == Role::Admin {
+        return Ok(user);
+    }
+    let assigned: bool = db
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM TeacherSections WHERE teacher_id = ?1 AND section_id = ?2)",
+            params![user.id, section_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !assigned {
+        warn!("User {} is not assigned to section {}", user.id, section_id);
+        return Err("Permission denied: you are not assigned to this section.".to_string());
+    }
+    Ok(user)
+}
+
+#[tauri::command]
+pub async fn add_class(database: State<'_, Database>, name: String) -> Result<i64, String> {
+    require_permission(Permission::ManageUsers)?;
+    let name = name.trim().to_string();
+    info!("Adding class {}", name);
+    if name.is_empty() {
+        return Err("Class name cannot be empty.".to_string());
+    }
+    database
+        .run(move |db| {
+            db.execute("INSERT INTO Classes (name) VALUES (?1)", params![name]).map_err(|e| {
+                error!("Failed to insert class {}: {}", name, e);
+                e.to_string()
+            })?;
+            Ok(db.last_insert_rowid())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn add_section(database: State<'_, Database>, class_id: i64, name: String) -> Result<i64, String> {
+    require_permission(Permission::ManageUsers)?;
+    let name = name.trim().to_string();
+    info!("Adding section {} to class {}", name, class_id);
+    if name.is_empty() {
+        return Err("Section name cannot be empty.".to_string());
+    }
+    database
+        .run(move |db| {
+            db.execute("INSERT INTO Sections (class_id, name) VALUES (?1, ?2)", params![class_id, name])
+                .map_err(|e| {
+                    error!("Failed to insert section {} of class {}: {}", name, class_id, e);
+                    e.to_string()
+                })?;
+            Ok(db.last_insert_rowid())
+        })
+        .await
+}
+
+// Sections with students enrolled are kept; move the students first. Deleted students
+// lose their section, and are restored without one.
+#[tauri::command]
+pub async fn delete_section(database: State<'_, Database>, id: i64) -> Result<(), String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Deleting section {}", id);
+    database
+        .run(move |db| {
+            let tx = db.transaction().map_err(|e| e.to_string())?;
+            let students: i64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM Users WHERE section_id = ?1 AND deleted_at IS NULL",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if students > 0 {
+                warn!("Refused to delete section {} with {} students.", id, students);
+                return Err(format!("Section has {} students; move them to another section first.", students));
+            }
+            tx.execute("UPDATE Users SET section_id = NULL, roll_number = NULL WHERE section_id = ?1", params![id])
+                .map_err(|e| e.to_string())?;
+            let deleted = tx.execute("DELETE FROM Sections WHERE id = ?1", params![id]).map_err(|e| {
+                error!("Failed to delete section {}: {}", id, e);
+                e.to_string()
+            })?;
+            if deleted == 0 {
+                return Err(format!("Section with ID {} not found.", id));
+            }
+            tx.commit().map_err(|e| e.to_string())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_sections(database: State<'_, Database>) -> Result<Vec<SectionInfo>, String> {
+    current_user().ok_or_else(|| "Not signed in.".to_string())?;
+    info!("Retrieving all sections.");
+    database.run(|db| query_sections(db, "", &[])).await
+}
+
+// Students of a section in roll number order
+#[tauri::command]
+pub async fn get_section_roster(database: State<'_, Database>, section_id: i64) -> Result<Vec<RosterEntry>, String> {
+    database
+        .run(move |db| {
+            require_section_access(db, section_id)?;
+            info!("Retrieving roster of section {}", section_id);
+            let mut stmt = db
+                .prepare_cached(
+                    "SELECT id, username, display_name, roll_number FROM Users
+                     WHERE section_id = ?1 AND deleted_at IS NULL
+                     ORDER BY CAST(roll_number AS INTEGER), roll_number, username",
+                )
+                .map_err(|e| e.to_string())?;
+            let roster = stmt
+                .query_map(params![section_id], |row| {
+                    Ok(RosterEntry {
+                        user_id: row.get(0)?,
+                        username: row.get(1)?,
+                        display_name: row.get(2)?,
+                        roll_number: row.get(3)?,
+                    })
+                })
+                .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+                .map_err(|e| {
+                    error!("Failed to query roster of section {}: {}", section_id, e);
+                    e.to_string()
+                })?;
+            Ok(roster)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn assign_teacher_to_section(database: State<'_, Database>, teacher_id: i64, section_id: i64) -> Result<(), String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Assigning teacher {} to section {}", teacher_id, section_id);
+    database
+        .run(move |db| {
+            let role = user_role(db, teacher_id)?;
+            if role != Role::Teacher {
+                return Err(format!("User {} is a {}, not a teacher.", teacher_id, role.as_str()));
+            }
+            db.execute(
+                "INSERT OR IGNORE INTO TeacherSections (teacher_id, section_id) VALUES (?1, ?2)",
+                params![teacher_id, section_id],
+            )
+            .map_err(|e| {
+                error!("Failed to assign teacher {} to section {}: {}", teacher_id, section_id, e);
+                e.to_string()
+            })?;
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn remove_teacher_from_section(database: State<'_, Database>, teacher_id: i64, section_id: i64) -> Result<(), String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Removing teacher {} from section {}", teacher_id, section_id);
+    database
+        .run(move |db| {
+            db.execute(
+                "DELETE FROM TeacherSections WHERE teacher_id = ?1 AND section_id = ?2",
+                params![teacher_id, section_id],
+            )
+            .map_err(|e| {
+                error!("Failed to remove teacher {} from section {}: {}", teacher_id, section_id, e);
+                e.to_string()
+            })?;
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_teacher_sections(database: State<'_, Database>, teacher_id: i64) -> Result<Vec<SectionInfo>, String> {
+    let user = require_permission(Permission::ViewReports)?;
+    if user.role != Role::Admin && user.id != teacher_id {
+        return Err("Permission denied: you can only list your own sections.".to_string());
+    }
+    info!("Retrieving sections of teacher {}", teacher_id);
+    database
+        .run(move |db| {
+            query_sections(
+                db,
+                "WHERE s.id IN (SELECT section_id FROM TeacherSections WHERE teacher_id = ?1)",
+                &[&teacher_id],
+            )
+        })
+        .await
+}
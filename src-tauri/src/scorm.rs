@@ -0,0 +1,618 @@
+// src-tauri/src/scorm.rs
+
+use crate::db::Database;
+use crate::indexer::SCORM_MANIFEST;
+use crate::manifest::is_safe_relative;
+use crate::progress::record_package_progress;
+use crate::viewer::scorm_context;
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use tauri::{State, WebviewWindow};
+use xml::reader::{EventReader, XmlEvent};
+
+// Runtime API injected into every HTML page of a SCORM package served to a viewer window.
+// SCORM calls are synchronous, so the API answers from a local copy of the learner's CMI
+// data and posts changed values to the viewer page on Commit and Finish/Terminate, which
+// passes them to commit_scorm_values. Messages are only addressed to the viewer page's origin.
+const BRIDGE_SCRIPT: &str = r#"<script>
+(function () {
+  var version = __SCORM_VERSION__;
+  var data = __SCORM_DATA__;
+  var readOnly = __SCORM_READ_ONLY__;
+  var parentOrigin = __SCORM_PARENT_ORIGIN__;
+  var dirty = {};
+  var state = 0; // 0 not initialized, 1 running, 2 finished
+  var lastError = "0";
+  var is12 = version === "1.2";
+  var errors = {
+    "0": "No error", "101": "General exception", "103": "Already initialized",
+    "112": "Termination before initialization", "113": "Termination after termination",
+    "122": "Retrieve data before initialization", "123": "Retrieve data after termination",
+    "132": "Store data before initialization", "133": "Store data after termination",
+    "142": "Commit before initialization", "143": "Commit after termination",
+    "201": "Invalid argument", "301": "Not initialized", "401": "Undefined data model element",
+    "403": is12 ? "Element is read only" : "Data model element value not initialized",
+    "404": "Data model element is read only"
+  };
+  function fail(code) { lastError = code; return "false"; }
+  function send(finished) {
+    var values = dirty;
+    dirty = {};
+    window.top.postMessage({ type: "osiris-scorm", values: values, finished: finished }, parentOrigin);
+  }
+  function count(element) {
+    var prefix = element.slice(0, element.length - "_count".length);
+    var seen = {};
+    for (var key in data) {
+      if (key.indexOf(prefix) === 0) {
+        var index = key.slice(prefix.length).split(".")[0];
+        if (/^\d+$/.test(index)) seen[index] = true;
+      }
+    }
+    return String(Object.keys(seen).length);
+  }
+  function initialize(arg) {
+    if (state === 1) return fail(is12 ? "101" : "103");
+    if (state === 2) return fail(is12 ? "101" : "104");
+    state = 1;
+    lastError = "0";
+    return "true";
+  }
+  function finish(arg) {
+    if (state !== 1) return fail(is12 ? "301" : state === 0 ? "112" : "113");
+    state = 2;
+    lastError = "0";
+    send(true);
+    return "true";
+  }
+  function getValue(element) {
+    if (state !== 1) { fail(is12 ? "301" : state === 0 ? "122" : "123"); return ""; }
+    lastError = "0";
+    element = String(element);
+    if (/\._count$/.test(element)) return count(element);
+    if (Object.prototype.hasOwnProperty.call(data, element)) return data[element];
+    if (!is12) lastError = "403";
+    return "";
+  }
+  function setValue(element, value) {
+    if (state !== 1) return fail(is12 ? "301" : state === 0 ? "132" : "133");
+    element = String(element);
+    if (readOnly.indexOf(element) >= 0 || /\._(count|children|version)$/.test(element)) {
+      return fail(is12 ? "403" : "404");
+    }
+    data[element] = String(value);
+    dirty[element] = String(value);
+    lastError = "0";
+    return "true";
+  }
+  function commit(arg) {
+    if (state !== 1) return fail(is12 ? "301" : state === 0 ? "142" : "143");
+    lastError = "0";
+    send(false);
+    return "true";
+  }
+  function getLastError() { return lastError; }
+  function getErrorString(code) { return errors[String(code)] || ""; }
+  function getDiagnostic(code) { return errors[String(code || lastError)] || ""; }
+  if (is12) {
+    window.API = {
+      LMSInitialize: initialize, LMSFinish: finish, LMSGetValue: getValue, LMSSetValue: setValue,
+      LMSCommit: commit, LMSGetLastError: getLastError, LMSGetErrorString: getErrorString,
+      LMSGetDiagnostic: getDiagnostic
+    };
+  } else {
+    window.API_1484_11 = {
+      Initialize: initialize, Terminate: finish, GetValue: getValue, SetValue: setValue,
+      Commit: commit, GetLastError: getLastError, GetErrorString: getErrorString,
+      GetDiagnostic: getDiagnostic
+    };
+  }
+  // Values set but not committed when the page goes away are still kept
+  window.addEventListener("pagehide", function () {
+    if (Object.keys(dirty).length > 0) send(false);
+  });
+})();
+</script>"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ScormVersion {
+    #[serde(rename = "1.2")]
+    Scorm12,
+    #[serde(rename = "2004")]
+    Scorm2004,
+}
+
+impl ScormVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScormVersion::Scorm12 => "1.2",
+            ScormVersion::Scorm2004 => "2004",
+        }
+    }
+
+    // Data model elements the runtime sets and the content may only read
+    fn read_only_elements(&self) -> &'static [&'static str] {
+        match self {
+            ScormVersion::Scorm12 => &[
+                "cmi.core.student_id",
+                "cmi.core.student_name",
+                "cmi.core.credit",
+                "cmi.core.entry",
+                "cmi.core.total_time",
+                "cmi.core.lesson_mode",
+                "cmi.launch_data",
+            ],
+            ScormVersion::Scorm2004 => &[
+                "cmi.learner_id",
+                "cmi.learner_name",
+                "cmi.credit",
+                "cmi.entry",
+                "cmi.total_time",
+                "cmi.mode",
+                "cmi.launch_data",
+                "cmi.completion_threshold",
+                "cmi.scaled_passing_score",
+            ],
+        }
+    }
+
+    fn element(&self, scorm12: &'static str, scorm2004: &'static str) -> &'static str {
+        match self {
+            ScormVersion::Scorm12 => scorm12,
+            ScormVersion::Scorm2004 => scorm2004,
+        }
+    }
+}
+
+// Launch details of a SCORM package, read from its imsmanifest.xml
+#[derive(Debug, Clone)]
+pub struct ScormPackage {
+    pub version: ScormVersion,
+    pub title: Option<String>,
+    pub launch: PathBuf,
+    pub launch_query: Option<String>,
+}
+
+// SCORM state of a viewer session, looked up by window label
+#[derive(Debug, Clone)]
+pub struct ScormContext {
+    pub user_id: Option<i64>,
+    pub content_item_id: Option<i64>,
+    pub root: PathBuf,
+    pub version: ScormVersion,
+    // Origin of the viewer page the runtime posts committed values to
+    pub parent_origin: String,
+}
+
+#[derive(Default)]
+struct ManifestResource {
+    base: String,
+    href: Option<String>,
+}
+
+// Reads the launch page of the default organization's first item from imsmanifest.xml
+pub fn read_scorm_package(root: &Path) -> Result<ScormPackage, String> {
+    let manifest_path = root.join(SCORM_MANIFEST);
+    let file = File::open(&manifest_path).map_err(|e| format!("Failed to open {}: {}", manifest_path.display(), e))?;
+
+    let mut stack: Vec<String> = Vec::new();
+    let mut bases: Vec<String> = Vec::new();
+    let mut schema_version = String::new();
+    let mut namespaces = String::new();
+    let mut default_organization: Option<String> = None;
+    // (organization identifier, title, first launchable item's resource)
+    let mut organizations: Vec<(String, Option<String>, Option<String>)> = Vec::new();
+    let mut resources: HashMap<String, ManifestResource> = HashMap::new();
+
+    for event in EventReader::new(BufReader::new(file)) {
+        match event {
+            Ok(XmlEvent::StartElement { name, attributes, namespace }) => {
+                let attr = |local: &str| {
+                    attributes.iter().find(|a| a.name.local_name == local && a.name.prefix.as_deref() != Some("xml")).map(|a| a.value.clone())
+                };
+                let base = attributes
+                    .iter()
+                    .find(|a| a.name.prefix.as_deref() == Some("xml") && a.name.local_name == "base")
+                    .map(|a| a.value.clone())
+                    .unwrap_or_default();
+                bases.push(format!("{}{}", bases.last().map(String::as_str).unwrap_or(""), base));
+                if stack.is_empty() {
+                    namespaces = namespace.0.values().cloned().collect::<Vec<_>>().join(" ");
+                }
+                match name.local_name.as_str() {
+                    "organizations" => default_organization = attr("default"),
+                    "organization" => organizations.push((attr("identifier").unwrap_or_default(), None, None)),
+                    "item" if stack.iter().any(|e| e == "organization") => {
+                        if let (Some(organization), Some(resource)) = (organizations.last_mut(), attr("identifierref")) {
+                            organization.2.get_or_insert(resource);
+                        }
+                    }
+                    "resource" => {
+                        if let Some(identifier) = attr("identifier") {
+                            let base = bases.last().cloned().unwrap_or_default();
+                            resources.insert(identifier, ManifestResource { base, href: attr("href") });
+                        }
+                    }
+                    _ => {}
+                }
+                stack.push(name.local_name);
+            }
+            Ok(XmlEvent::Characters(text)) => match stack.last().map(String::as_str) {
+                Some("schemaversion") if schema_version.is_empty() => schema_version = text.trim().to_string(),
+                // The organization's own title, not those of its items
+                Some("title") if stack.len() >= 2 && stack[stack.len() - 2] == "organization" => {
+                    if let Some(organization) = organizations.last_mut() {
+                        organization.1.get_or_insert_with(|| text.trim().to_string());
+                    }
+                }
+                _ => {}
+            },
+            Ok(XmlEvent::EndElement { .. }) => {
+                stack.pop();
+                bases.pop();
+            }
+            Ok(_) => {}
+            Err(e) => return Err(format!("Failed to read {}: {}", manifest_path.display(), e)),
+        }
+    }
+
+    let version = if schema_version.contains("1.2") {
+        ScormVersion::Scorm12
+    } else if schema_version.contains("2004") || schema_version.contains("1.3") || namespaces.contains("v1p3") {
+        ScormVersion::Scorm2004
+    } else {
+        ScormVersion::Scorm12
+    };
+    let organization = default_organization
+        .as_deref()
+        .and_then(|id| organizations.iter().find(|(identifier, _, _)| identifier == id))
+        .or_else(|| organizations.first());
+    let resource = organization
+        .and_then(|(_, _, resource)| resource.as_deref())
+        .and_then(|id| resources.get(id))
+        .or_else(|| resources.values().find(|r| r.href.is_some()))
+        .ok_or_else(|| format!("{} names no launchable resource.", manifest_path.display()))?;
+    let href = format!("{}{}", resource.base, resource.href.as_deref().unwrap_or_default());
+    let (file, query) = match href.split_once('?') {
+        Some((file, query)) => (file.to_string(), Some(query.to_string())),
+        None => (href, None),
+    };
+    if !is_safe_relative(&file) {
+        return Err(format!("{} has an unsafe launch path '{}'.", manifest_path.display(), file));
+    }
+    Ok(ScormPackage {
+        version,
+        title: organization.and_then(|(_, title, _)| title.clone()).filter(|t| !t.is_empty()),
+        launch: root.join(file),
+        launch_query: query,
+    })
+}
+
+fn registration_id(db: &Connection, context: &ScormContext, user_id: i64) -> Result<i64, String> {
+    let package_path = context.root.to_string_lossy();
+    db.execute(
+        "INSERT INTO ScormRegistrations (user_id, package_path, scorm_version) VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id, package_path) DO UPDATE SET scorm_version = excluded.scorm_version, updated_at = CURRENT_TIMESTAMP",
+        params![user_id, package_path, context.version.as_str()],
+    )
+    .and_then(|_| {
+        db.query_row(
+            "SELECT id FROM ScormRegistrations WHERE user_id = ?1 AND package_path = ?2",
+            params![user_id, package_path],
+            |row| row.get(0),
+        )
+    })
+    .map_err(|e| {
+        error!("Failed to register SCORM package {} for user {}: {}", package_path, user_id, e);
+        e.to_string()
+    })
+}
+
+fn stored_values(db: &Connection, context: &ScormContext, user_id: i64) -> Result<HashMap<String, String>, String> {
+    let mut stmt = db
+        .prepare_cached(
+            "SELECT d.element, d.value FROM ScormCmiData d
+             JOIN ScormRegistrations r ON r.id = d.registration_id
+             WHERE r.user_id = ?1 AND r.package_path = ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let values = stmt
+        .query_map(params![user_id, context.root.to_string_lossy()], |row| Ok((row.get(0)?, row.get(1)?)))
+        .and_then(|rows| rows.collect::<rusqlite::Result<HashMap<String, String>>>())
+        .map_err(|e| {
+            error!("Failed to load SCORM data for user {}: {}", user_id, e);
+            e.to_string()
+        })?;
+    Ok(values)
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+// Seconds in a SCORM 1.2 timespan (HHHH:MM:SS.SS) or a SCORM 2004 ISO 8601 duration (P1DT2H3M4.5S)
+fn parse_duration(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Some(iso) = value.strip_prefix('P') {
+        let mut seconds = 0.0;
+        let mut number = String::new();
+        let mut in_time = false;
+        for c in iso.chars() {
+            match c {
+                'T' => in_time = true,
+                '0'..='9' | '.' => number.push(c),
+                unit => {
+                    let n: f64 = number.parse().ok()?;
+                    number.clear();
+                    seconds += n * match (unit, in_time) {
+                        ('Y', false) => 365.0 * 86400.0,
+                        ('M', false) => 30.0 * 86400.0,
+                        ('D', false) => 86400.0,
+                        ('H', true) => 3600.0,
+                        ('M', true) => 60.0,
+                        ('S', true) => 1.0,
+                        _ => return None,
+                    };
+                }
+            }
+        }
+        return number.is_empty().then_some(seconds);
+    }
+    let parts: Vec<&str> = value.split(':').collect();
+    match parts.as_slice() {
+        [hours, minutes, seconds] => {
+            Some(hours.parse::<f64>().ok()? * 3600.0 + minutes.parse::<f64>().ok()? * 60.0 + seconds.parse::<f64>().ok()?)
+        }
+        _ => None,
+    }
+}
+
+fn format_duration(version: ScormVersion, seconds: f64) -> String {
+    let whole = seconds.max(0.0);
+    let hours = (whole / 3600.0).floor();
+    let minutes = ((whole - hours * 3600.0) / 60.0).floor();
+    let rest = whole - hours * 3600.0 - minutes * 60.0;
+    match version {
+        ScormVersion::Scorm12 => format!("{:04}:{:02}:{:05.2}", hours as u64, minutes as u64, rest),
+        ScormVersion::Scorm2004 => format!("PT{}H{}M{:.2}S", hours as u64, minutes as u64, rest),
+    }
+}
+
+// CMI data handed to the content when it starts: what the learner stored last time plus
+// the values the runtime provides
+pub fn launch_values(db: &Connection, context: &ScormContext) -> Result<HashMap<String, String>, String> {
+    let version = context.version;
+    let mut values = HashMap::new();
+    let mut learner = (String::new(), String::new());
+    if let Some(user_id) = context.user_id {
+        values = stored_values(db, context, user_id)?;
+        let name: Option<String> = db
+            .query_row("SELECT username FROM Users WHERE id = ?1", params![user_id], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        learner = (user_id.to_string(), name.unwrap_or_default());
+    }
+
+    // A suspended attempt resumes; anything else starts over from the content's point of view
+    let suspended = values.get(version.element("cmi.core.exit", "cmi.exit")).map(String::as_str) == Some("suspend");
+    let entry = if suspended {
+        "resume"
+    } else if values.is_empty() {
+        "ab-initio"
+    } else {
+        ""
+    };
+    let provided = [
+        (version.element("cmi.core.student_id", "cmi.learner_id"), learner.0),
+        (version.element("cmi.core.student_name", "cmi.learner_name"), learner.1),
+        (version.element("cmi.core.lesson_mode", "cmi.mode"), "normal".to_string()),
+        (version.element("cmi.core.credit", "cmi.credit"), "credit".to_string()),
+        (version.element("cmi.core.entry", "cmi.entry"), entry.to_string()),
+    ];
+    values.extend(provided.into_iter().map(|(element, value)| (element.to_string(), value)));
+    values.entry(version.element("cmi.core.total_time", "cmi.total_time").to_string()).or_insert_with(|| format_duration(version, 0.0));
+    match version {
+        ScormVersion::Scorm12 => {
+            values.entry("cmi.core.lesson_status".to_string()).or_insert_with(|| "not attempted".to_string());
+        }
+        ScormVersion::Scorm2004 => {
+            values.entry("cmi.completion_status".to_string()).or_insert_with(|| "unknown".to_string());
+            values.entry("cmi.success_status".to_string()).or_insert_with(|| "unknown".to_string());
+        }
+    }
+    // The exit of the previous attempt only decides the entry value above
+    values.remove(version.element("cmi.core.exit", "cmi.exit"));
+    Ok(values)
+}
+
+// JSON for embedding in a script element, escaped so values cannot close it
+fn script_json<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string()).replace("</", "<\\/")
+}
+
+// Adds the runtime API to an HTML page of a SCORM package
+pub fn inject_bridge(db: &Connection, html: Vec<u8>, context: &ScormContext) -> Vec<u8> {
+    let values = match launch_values(db, context) {
+        Ok(values) => values,
+        Err(e) => {
+            warn!("Starting SCORM package {} without stored data: {}", context.root.display(), e);
+            HashMap::new()
+        }
+    };
+    let script = BRIDGE_SCRIPT
+        .replace("__SCORM_VERSION__", &script_json(&context.version.as_str()))
+        .replace("__SCORM_DATA__", &script_json(&values))
+        .replace("__SCORM_READ_ONLY__", &script_json(&context.version.read_only_elements()))
+        .replace("__SCORM_PARENT_ORIGIN__", &script_json(&context.parent_origin));
+
+    // Right after <head ...>, so the API exists before any of the page's own scripts run
+    let lower = html.to_ascii_lowercase();
+    let insert_at = lower
+        .windows(5)
+        .position(|w| w == b"<head")
+        .and_then(|start| lower[start..].iter().position(|b| *b == b'>').map(|end| start + end + 1))
+        .unwrap_or(0);
+    let mut page = Vec::with_capacity(html.len() + script.len());
+    page.extend_from_slice(&html[..insert_at]);
+    page.extend_from_slice(script.as_bytes());
+    page.extend_from_slice(&html[insert_at..]);
+    page
+}
+
+
+// Completion and score reported by the content, mirrored into ContentProgress
+fn mirror_progress(db: &mut Connection, context: &ScormContext, user_id: i64, values: &HashMap<String, String>) -> Result<(), String> {
+    let Some(item_id) = context.content_item_id else {
+        return Ok(());
+    };
+    let version = context.version;
+    let completion = match version {
+        ScormVersion::Scorm12 => match values.get("cmi.core.lesson_status").map(String::as_str) {
+            Some("completed" | "passed" | "failed") => 100,
+            _ => 0,
+        },
+        ScormVersion::Scorm2004 => match values.get("cmi.completion_status").map(String::as_str) {
+            Some("completed") => 100,
+            _ => values
+                .get("cmi.progress_measure")
+                .and_then(|v| parse_number(v))
+                .map(|measure| (measure.clamp(0.0, 1.0) * 100.0).round() as i64)
+                .unwrap_or(0),
+        },
+    };
+    let raw = values.get(version.element("cmi.core.score.raw", "cmi.score.raw")).and_then(|v| parse_number(v));
+    let max = values.get(version.element("cmi.core.score.max", "cmi.score.max")).and_then(|v| parse_number(v));
+    let scaled = values.get("cmi.score.scaled").and_then(|v| parse_number(v)).filter(|_| version == ScormVersion::Scorm2004);
+    let score = match (raw, max, scaled) {
+        (Some(raw), Some(max), _) if max > 0.0 => Some((raw.round() as i64, max.round() as i64)),
+        (Some(raw), None, _) => Some((raw.round() as i64, 100)),
+        (None, _, Some(scaled)) => Some(((scaled * 100.0).round() as i64, 100)),
+        _ => None,
+    };
+    record_package_progress(db, user_id, item_id, completion, score).map(|_| ())
+}
+
+// Stores values committed by a SCORM package and mirrors its completion into ContentProgress.
+// `finished` is set when the content called LMSFinish/Terminate.
+pub fn commit_values(
+    db: &mut Connection,
+    context: &ScormContext,
+    values: HashMap<String, String>,
+    finished: bool,
+) -> Result<(), String> {
+    // Sessions without a signed-in user run, but are not recorded
+    let Some(user_id) = context.user_id else {
+        return Ok(());
+    };
+    let version = context.version;
+    let registration = registration_id(db, context, user_id)?;
+    let mut stored = stored_values(db, context, user_id)?;
+    let read_only = version.read_only_elements();
+    let session_time = version.element("cmi.core.session_time", "cmi.session_time");
+    let total_time = version.element("cmi.core.total_time", "cmi.total_time");
+
+    // Session time adds up into the total instead of being kept
+    for (element, value) in values {
+        if element == session_time {
+            let total = stored.get(total_time).and_then(|t| parse_duration(t)).unwrap_or(0.0);
+            if let Some(session) = parse_duration(&value) {
+                stored.insert(total_time.to_string(), format_duration(version, total + session));
+            }
+        } else if element.starts_with("cmi.") && !read_only.contains(&element.as_str()) {
+            stored.insert(element, value);
+        }
+    }
+    // SCORM 1.2 content that finishes without reporting a status is completed (RTE 3.4.4)
+    let status = stored.get("cmi.core.lesson_status").map(String::as_str);
+    if finished && version == ScormVersion::Scorm12 && matches!(status, None | Some("not attempted")) {
+        stored.insert("cmi.core.lesson_status".to_string(), "completed".to_string());
+    }
+
+    let tx = db.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare_cached(
+                "INSERT INTO ScormCmiData (registration_id, element, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(registration_id, element) DO UPDATE SET value = excluded.value",
+            )
+            .map_err(|e| e.to_string())?;
+        for (element, value) in &stored {
+            stmt.execute(params![registration, element, value]).map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| {
+        error!("Failed to store SCORM data for user {}: {}", user_id, e);
+        e.to_string()
+    })?;
+    mirror_progress(db, context, user_id, &stored)
+}
+
+// Values committed by the SCORM runtime in a viewer window
+#[tauri::command]
+pub async fn commit_scorm_values(
+    database: State<'_, Database>,
+    window: WebviewWindow,
+    values: HashMap<String, String>,
+    finished: bool,
+) -> Result<(), String> {
+    let context = scorm_context(window.label())
+        .ok_or_else(|| format!("Window {} is not showing a SCORM package.", window.label()))?;
+    info!(
+        "Committing {} SCORM values for {}{}",
+        values.len(),
+        context.root.display(),
+        if finished { " (finished)" } else { "" }
+    );
+    database.run(move |db| commit_values(db, &context, values, finished)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_reads_scorm_12_timespans() {
+        assert_eq!(parse_duration("0000:01:30.5"), Some(90.5));
+        assert_eq!(parse_duration("12:00:00"), Some(43200.0));
+        assert_eq!(parse_duration(" 00:00:07 "), Some(7.0));
+        assert_eq!(parse_duration("01:30"), None);
+        assert_eq!(parse_duration("aa:bb:cc"), None);
+    }
+
+    #[test]
+    fn parse_duration_reads_iso_8601_durations() {
+        assert_eq!(parse_duration("PT1H2M3.5S"), Some(3723.5));
+        assert_eq!(parse_duration("P1DT1S"), Some(86401.0));
+        assert_eq!(parse_duration("PT0S"), Some(0.0));
+        // M is months before T and minutes after it
+        assert_eq!(parse_duration("P1M"), Some(30.0 * 86400.0));
+        assert_eq!(parse_duration("PT1M"), Some(60.0));
+    }
+
+    #[test]
+    fn parse_duration_rejects_malformed_iso_durations() {
+        assert_eq!(parse_duration("P1H"), None); // hours need the T
+        assert_eq!(parse_duration("PT5"), None); // number without a unit
+        assert_eq!(parse_duration("PTS"), None); // unit without a number
+        assert_eq!(parse_duration("PT1X"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn format_duration_round_trips_through_parse_duration() {
+        for seconds in [0.0, 59.25, 3723.5, 90061.0] {
+            for version in [ScormVersion::Scorm12, ScormVersion::Scorm2004] {
+                let formatted = format_duration(version, seconds);
+                let parsed = parse_duration(&formatted).unwrap();
+                assert!((parsed - seconds).abs() < 0.01, "{} -> {} -> {}", seconds, formatted, parsed);
+            }
+        }
+        assert_eq!(format_duration(ScormVersion::Scorm12, 3723.5), "0001:02:03.50");
+        assert_eq!(format_duration(ScormVersion::Scorm2004, 3723.5), "PT1H2M3.50S");
+    }
+}
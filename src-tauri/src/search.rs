@@ -0,0 +1,300 @@
+// src-tauri/src/search.rs
+
+use crate::content::get_active_content_root;
+use crate::db::Database;
+use crate::encryption::{looks_encrypted, read_quiz_xml};
+use crate::indexer::{classify_file, is_package_dir, title_from_file_name, ContentType};
+use crate::pdftext::pdf_document_text;
+use crate::roles::{require_permission, visible_content_types, Permission};
+use log::{error, info, warn};
+use rusqlite::{params, Result};
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+use std::path::Path;
+use tauri::State;
+use walkdir::WalkDir;
+use xml::reader::{EventReader, XmlEvent};
+
+// entry_type stored for folders; files use ContentType::as_str
+const FOLDER_ENTRY_TYPE: &str = "folder";
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SearchFilter {
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub content_type: Option<ContentType>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub title: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+    pub entry_type: String, // "folder" or a content type such as "video" or "quiz"
+    pub class_name: Option<String>,
+    pub subject: Option<String>,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+// Collects all text nodes of a quiz so questions match in every language they are written in
+fn quiz_text(path: &Path) -> Option<String> {
+    let xml = match read_quiz_xml(path) {
+        Ok(xml) => xml,
+        Err(e) => {
+            warn!("Skipping quiz text for search index: {}", e);
+            return None;
+        }
+    };
+    let mut text = Vec::new();
+    for e in EventReader::new(BufReader::new(xml.as_bytes())) {
+        match e {
+            Ok(XmlEvent::Characters(chars)) | Ok(XmlEvent::CData(chars)) if chars != "-" => text.push(chars),
+            Ok(_) => (),
+            Err(e) => {
+                warn!("Stopped reading quiz {} for search index: {}", path.display(), e);
+                break;
+            }
+        }
+    }
+    Some(text.join(" "))
+}
+
+struct SearchRow {
+    title: String,
+    path_text: String,
+    body: String,
+    relative_path: String,
+    entry_type: String,
+    class_name: Option<String>,
+    subject: Option<String>,
+}
+
+// Walks `dir` and reads the searchable text of every entry. Quiz and PDF text is read here,
+// before a connection is taken for the index, so the pool is not held while reading.
+fn collect_search_rows(database: &Database, root: &Path, dir: &Path) -> Vec<SearchRow> {
+    let mut rows = Vec::new();
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || (!entry.file_name().to_string_lossy().starts_with('.')
+                    && !entry.path().parent().is_some_and(|parent| parent != root && is_package_dir(parent)))
+        })
+        .filter_map(|entry| entry.ok())
+    {
+        let Ok(relative) = entry.path().strip_prefix(root) else { continue };
+        let relative_path = relative.to_string_lossy().replace('\\', "/");
+        if relative_path.is_empty() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let components: Vec<&str> = relative_path.split('/').collect();
+
+        let (title, entry_type, body, dir_depth) = if entry.file_type().is_dir() && is_package_dir(entry.path()) {
+            (name, ContentType::Interactive.as_str().to_string(), String::new(), components.len() - 1)
+        } else if entry.file_type().is_dir() {
+            (name, FOLDER_ENTRY_TYPE.to_string(), String::new(), components.len())
+        } else {
+            let Some(content_type) = classify_file(&name) else { continue };
+            // The index is stored on disk, so encrypted files are found by title and path only
+            let body = match content_type {
+                _ if looks_encrypted(entry.path()) => String::new(),
+                ContentType::Quiz => quiz_text(entry.path()).unwrap_or_default(),
+                // Extracted once and cached, so later rebuilds only read the cache
+                ContentType::Notes | ContentType::Textbook | ContentType::Activity | ContentType::Assessment => {
+                    pdf_document_text(database, entry.path()).unwrap_or_default()
+                }
+                ContentType::Video | ContentType::Animation | ContentType::Interactive => String::new(),
+            };
+            (title_from_file_name(&name), content_type.as_str().to_string(), body, components.len() - 1)
+        };
+        // Folder names along the way are searchable too, e.g. "photosynthesis" in a chapter name
+        let path_text = components.join(" ");
+        let class_name = components.first().filter(|_| dir_depth >= 1).map(|s| s.to_string());
+        let subject = components.get(1).filter(|_| dir_depth >= 2).map(|s| s.to_string());
+        rows.push(SearchRow { title, path_text, body, relative_path, entry_type, class_name, subject });
+    }
+    rows
+}
+
+// Rebuilds search rows for `prefix` (a folder relative to the root, or "" for everything)
+pub fn index_search_subtree(database: &Database, folder_id: i64, root: &Path, prefix: &str) -> Result<usize, String> {
+    let dir = if prefix.is_empty() { root.to_path_buf() } else { root.join(prefix) };
+    let rows = collect_search_rows(database, root, &dir);
+
+    let mut db = database.connection()?;
+    let tx = db.transaction().map_err(|e| {
+        error!("Failed to start transaction for search indexing: {}", e);
+        e.to_string()
+    })?;
+
+    tx.execute(
+        "DELETE FROM ContentSearch
+         WHERE folder_id = ?1
+           AND (?2 = '' OR relative_path = ?2 OR substr(relative_path, 1, length(?2) + 1) = ?2 || '/')",
+        params![folder_id, prefix],
+    )
+    .map_err(|e| {
+        error!("Failed to clear search rows under '{}': {}", prefix, e);
+        e.to_string()
+    })?;
+
+    {
+        let mut stmt = tx
+            .prepare_cached(
+                "INSERT INTO ContentSearch (title, path_text, body, folder_id, relative_path, entry_type, class_name, subject)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )
+            .map_err(|e| e.to_string())?;
+        for row in &rows {
+            stmt.execute(params![
+                row.title,
+                row.path_text,
+                row.body,
+                folder_id,
+                row.relative_path,
+                row.entry_type,
+                row.class_name,
+                row.subject
+            ])
+            .map_err(|e| {
+                error!("Failed to add {} to search index: {}", row.relative_path, e);
+                e.to_string()
+            })?;
+        }
+    }
+
+    tx.commit().map_err(|e| {
+        error!("Failed to commit search index transaction: {}", e);
+        e.to_string()
+    })?;
+    Ok(rows.len())
+}
+
+// Rebuilds the whole search index for one content path
+pub fn rebuild_search_index(database: &Database, folder_id: i64, root: &Path) -> Result<usize, String> {
+    info!("Rebuilding search index for content path {} ({})", folder_id, root.display());
+    // Rows of other content paths are stale once this one is rebuilt as the active path
+    database.connection()?.execute("DELETE FROM ContentSearch WHERE folder_id != ?1", params![folder_id])
+        .map_err(|e| {
+            error!("Failed to clear search rows of inactive content paths: {}", e);
+            e.to_string()
+        })?;
+    let indexed = index_search_subtree(database, folder_id, root, "")?;
+    info!("Search index for content path {} has {} entries.", folder_id, indexed);
+    Ok(indexed)
+}
+
+// Turns user input into an FTS5 query: every word must match, as a prefix, in any column
+fn to_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[tauri::command]
+pub async fn rebuild_active_search_index(database: State<'_, Database>) -> Result<usize, String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Rebuilding search index for the active content path.");
+    database
+        .run_blocking(|database| {
+            let (folder_id, root) = get_active_content_root(&*database.connection()?)?
+                .ok_or_else(|| "No active content path.".to_string())?;
+            rebuild_search_index(database, folder_id, Path::new(&root))
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn search_content(database: State<'_, Database>, query: String, filter: Option<SearchFilter>, limit: Option<i64>) -> Result<Vec<SearchHit>, String> {
+    let filter = filter.unwrap_or_default();
+    info!("Searching content for '{}' with filter: {:?}", query, filter);
+    let Some(fts_query) = to_fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    database
+        .run(move |db| {
+            let (folder_id, root) = match get_active_content_root(db)? {
+                Some(active) => active,
+                None => return Ok(Vec::new()),
+            };
+
+            // Categories hidden from the user's role are left out before the limit applies.
+            // Folders belong to no category and always match.
+            let visible: Vec<&str> = visible_content_types(db)?
+                .iter()
+                .map(ContentType::as_str)
+                .chain([FOLDER_ENTRY_TYPE])
+                .collect();
+            let visible = serde_json::to_string(&visible).map_err(|e| e.to_string())?;
+
+            // Title matches weigh more than folder names, which weigh more than quiz and PDF text
+            let mut stmt = db
+                .prepare_cached(
+                    "SELECT title, relative_path, entry_type, class_name, subject,
+                            snippet(ContentSearch, -1, '[', ']', '…', 12), bm25(ContentSearch, 10.0, 4.0, 1.0)
+                     FROM ContentSearch
+                     WHERE ContentSearch MATCH ?1
+                       AND folder_id = ?2
+                       AND (?3 IS NULL OR class_name = ?3)
+                       AND (?4 IS NULL OR subject = ?4)
+                       AND (?5 IS NULL OR entry_type = ?5)
+                       AND entry_type IN (SELECT value FROM json_each(?7))
+                     ORDER BY bm25(ContentSearch, 10.0, 4.0, 1.0)
+                     LIMIT ?6",
+                )
+                .map_err(|e| {
+                    error!("Failed to prepare statement for content search: {}", e);
+                    e.to_string()
+                })?;
+
+            let hits = stmt
+                .query_map(
+                    params![
+                        fts_query,
+                        folder_id,
+                        filter.class_name,
+                        filter.subject,
+                        filter.content_type.map(|t| t.as_str()),
+                        limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+                        visible,
+                    ],
+                    |row| {
+                        let relative_path: String = row.get(1)?;
+                        Ok(SearchHit {
+                            title: row.get(0)?,
+                            absolute_path: Path::new(&root).join(&relative_path).to_string_lossy().into_owned(),
+                            relative_path,
+                            entry_type: row.get(2)?,
+                            class_name: row.get(3)?,
+                            subject: row.get(4)?,
+                            snippet: row.get(5)?,
+                            rank: row.get(6)?,
+                        })
+                    },
+                )
+                .map_err(|e| {
+                    error!("Failed to query map for content search: {}", e);
+                    e.to_string()
+                })?
+                .collect::<Result<Vec<SearchHit>, _>>()
+                .map_err(|e| {
+                    error!("Failed to collect content search hits: {}", e);
+                    e.to_string()
+                })?;
+
+            info!("Content search for '{}' returned {} hits.", query, hits.len());
+            Ok(hits)
+        })
+        .await
+}
+
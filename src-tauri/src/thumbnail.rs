@@ -0,0 +1,353 @@
+// src-tauri/src/thumbnail.rs
+
+use crate::content::resolve_in_content_roots;
+use crate::db::{get_app_data_dir, Database};
+use crate::encryption::{looks_encrypted, DecryptingReader};
+use crate::integrity::sha256_file;
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::env;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, UriSchemeContext};
+
+// Thumbnails are served as thumb://localhost/<hash> (http://thumb.localhost/<hash> on Windows);
+// the frontend builds the URL with convertFileSrc(hash, "thumb")
+pub const THUMBNAIL_SCHEME: &str = "thumb";
+pub const THUMBNAIL_READY_EVENT: &str = "thumbnail-ready";
+
+const THUMBNAIL_DIR: &str = "thumbnails";
+const THUMBNAIL_WIDTH: u32 = 320;
+// Seconds into a video to take the poster frame from, skipping black intro frames
+const POSTER_FRAME_OFFSET: &str = "3";
+// Renderers shipped next to the app executable as sidecars (bundle.externalBin)
+const PDF_TOOL: &str = "pdftoppm";
+const VIDEO_TOOL: &str = "ffmpeg";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceKind {
+    Video,
+    Pdf,
+}
+
+impl SourceKind {
+    fn tool(self) -> &'static str {
+        match self {
+            SourceKind::Video => VIDEO_TOOL,
+            SourceKind::Pdf => PDF_TOOL,
+        }
+    }
+}
+
+// `path` is the path exactly as the frontend requested it, so listeners can match on it
+#[derive(Debug, Serialize, Clone)]
+pub struct ThumbnailReadyPayload {
+    pub path: String,
+    pub hash: String,
+}
+
+struct ThumbnailJob {
+    path: PathBuf,
+    requested: String,
+    kind: SourceKind,
+}
+
+static JOBS: Mutex<Option<Sender<ThumbnailJob>>> = Mutex::new(None);
+// Sources queued or known to have no thumbnail, so listings do not queue them again
+static SKIPPED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+// Resolved renderer paths, looked up once when the service starts
+static PDF_RENDERER: OnceLock<Option<PathBuf>> = OnceLock::new();
+static VIDEO_RENDERER: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+fn source_kind(path: &Path) -> Option<SourceKind> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    match extension.as_str() {
+        "mp4" | "mov" | "webm" | "m4v" => Some(SourceKind::Video),
+        "pdf" => Some(SourceKind::Pdf),
+        _ => None,
+    }
+}
+
+fn thumbnail_dir() -> Result<PathBuf, String> {
+    let dir = get_app_data_dir()?.join(THUMBNAIL_DIR);
+    fs::create_dir_all(&dir).map_err(|e| {
+        error!("Failed to create thumbnail directory {}: {}", dir.display(), e);
+        e.to_string()
+    })?;
+    Ok(dir)
+}
+
+pub(crate) fn file_stamp(path: &Path) -> Result<(i64, i64), String> {
+    let metadata = path.metadata().map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    Ok((metadata.len() as i64, modified_at))
+}
+
+// Hash recorded for `path`, if the file has not changed since it was hashed
+fn cached_hash(db: &Connection, path: &Path) -> Result<Option<String>, String> {
+    let (size, modified_at) = file_stamp(path)?;
+    db.query_row(
+        "SELECT sha256 FROM ContentHashes WHERE path = ?1 AND size = ?2 AND modified_at = ?3",
+        params![path.to_string_lossy(), size, modified_at],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| {
+        error!("Failed to look up content hash of {}: {}", path.display(), e);
+        e.to_string()
+    })
+}
+
+// Hashes the file as stored, so encrypted and plain copies of a video get separate entries.
+// A pooled connection is only held for the lookup and the insert, not while hashing.
+fn content_hash(database: &Database, path: &Path) -> Result<String, String> {
+    {
+        let db = database.connection()?;
+        if let Some(hash) = cached_hash(&db, path)? {
+            return Ok(hash);
+        }
+    }
+    let (size, modified_at) = file_stamp(path)?;
+    let hash = sha256_file(path).map_err(|e| format!("Failed to hash {}: {}", path.display(), e))?;
+    let db = database.connection()?;
+    db.execute(
+        "INSERT INTO ContentHashes (path, size, modified_at, sha256) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(path) DO UPDATE SET size = excluded.size, modified_at = excluded.modified_at, sha256 = excluded.sha256",
+        params![path.to_string_lossy(), size, modified_at, hash],
+    )
+    .map_err(|e| {
+        error!("Failed to record content hash of {}: {}", path.display(), e);
+        e.to_string()
+    })?;
+    Ok(hash)
+}
+
+fn executable_name(name: &str) -> String {
+    if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() }
+}
+
+// A copy placed next to the executable, else the tool on PATH
+fn locate_tool(name: &str) -> Option<PathBuf> {
+    let file_name = executable_name(name);
+    let sidecar = env::current_exe().ok().and_then(|exe| exe.parent().map(|dir| dir.join(&file_name)));
+    sidecar
+        .into_iter()
+        .chain(env::var_os("PATH").iter().flat_map(env::split_paths).map(|dir| dir.join(&file_name)))
+        .find(|candidate| candidate.is_file())
+}
+
+fn renderer(kind: SourceKind) -> Option<&'static Path> {
+    let cell = match kind {
+        SourceKind::Video => &VIDEO_RENDERER,
+        SourceKind::Pdf => &PDF_RENDERER,
+    };
+    cell.get_or_init(|| {
+        let found = locate_tool(kind.tool());
+        match &found {
+            Some(path) => info!("Using {} for {:?} thumbnails.", path.display(), kind),
+            None => warn!("{} was not found; {:?} thumbnails are disabled.", kind.tool(), kind),
+        }
+        found
+    })
+    .as_deref()
+}
+
+// Runs an external tool and returns its stdout. An encrypted `input` is decrypted as it is
+// streamed to the tool's stdin, so neither the file nor its plaintext is held in memory.
+fn run_tool(program: &Path, args: &[&str], input: Option<&Path>) -> Result<Vec<u8>, String> {
+    let name = program.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run {}: {}", name, e))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        let mut reader = DecryptingReader::open(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+        // Written from another thread so a tool that stops reading early cannot block us
+        std::thread::spawn(move || {
+            if let Err(e) = io::copy(&mut reader, &mut stdin) {
+                // Tools close stdin once they have what they need
+                if e.kind() != ErrorKind::BrokenPipe {
+                    warn!("Failed to stream content to thumbnail tool: {}", e);
+                }
+            }
+        });
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("{} failed: {}", name, e))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "{} produced no image: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn render_pdf_page(tool: &Path, encrypted: bool, path: &Path) -> Result<Vec<u8>, String> {
+    let width = THUMBNAIL_WIDTH.to_string();
+    let input = path.to_string_lossy();
+    let args = [
+        "-png",
+        "-f",
+        "1",
+        "-l",
+        "1",
+        "-singlefile",
+        "-scale-to-x",
+        &width,
+        "-scale-to-y",
+        "-1",
+        if encrypted { "-" } else { &input },
+    ];
+    run_tool(tool, &args, encrypted.then_some(path))
+}
+
+fn extract_poster_frame(tool: &Path, encrypted: bool, path: &Path) -> Result<Vec<u8>, String> {
+    let scale = format!("scale={}:-2", THUMBNAIL_WIDTH);
+    let input = if encrypted { "pipe:0".to_string() } else { path.to_string_lossy().into_owned() };
+    let frame_at = |offset: &str| {
+        let args = [
+            "-v", "error", "-ss", offset, "-i", &input, "-frames:v", "1", "-vf", &scale, "-f", "image2pipe", "-vcodec",
+            "mjpeg", "pipe:1",
+        ];
+        run_tool(tool, &args, encrypted.then_some(path))
+    };
+    // Clips shorter than the offset have no frame there
+    frame_at(POSTER_FRAME_OFFSET).or_else(|_| frame_at("0"))
+}
+
+// Produces the thumbnail image for one source. Encrypted sources are decrypted while they
+// are piped to the tool, so no decrypted copy is written to disk.
+fn render(tool: &Path, path: &Path, kind: SourceKind) -> Result<Vec<u8>, String> {
+    let encrypted = looks_encrypted(path);
+    match kind {
+        SourceKind::Pdf => render_pdf_page(tool, encrypted, path),
+        SourceKind::Video => extract_poster_frame(tool, encrypted, path),
+    }
+}
+
+fn generate(app: &AppHandle, tool: &Path, job: &ThumbnailJob) -> Result<(), String> {
+    let hash = content_hash(&app.state::<Database>(), &job.path)?;
+    let target = thumbnail_dir()?.join(&hash);
+    if !target.is_file() {
+        let image = render(tool, &job.path, job.kind)?;
+        // Written under a temporary name so the protocol never serves a partial image
+        let partial = target.with_extension("partial");
+        fs::write(&partial, &image).and_then(|_| fs::rename(&partial, &target)).map_err(|e| {
+            error!("Failed to store thumbnail {}: {}", target.display(), e);
+            e.to_string()
+        })?;
+        info!("Generated thumbnail for {}", job.path.display());
+    }
+    if let Err(e) = app.emit(
+        THUMBNAIL_READY_EVENT,
+        ThumbnailReadyPayload { path: job.requested.clone(), hash },
+    ) {
+        warn!("Failed to emit {} event: {}", THUMBNAIL_READY_EVENT, e);
+    }
+    Ok(())
+}
+
+// Starts the background thread that renders queued thumbnails one at a time
+pub fn start_thumbnail_service(app: AppHandle) {
+    let (sender, receiver) = channel::<ThumbnailJob>();
+    *JOBS.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender);
+    std::thread::spawn(move || {
+        info!("Thumbnail service started.");
+        for job in receiver {
+            // A missing tool is reported once by renderer(); its sources simply stay skipped
+            let Some(tool) = renderer(job.kind) else {
+                continue;
+            };
+            match generate(&app, tool, &job) {
+                // Ready thumbnails are found through the hash cache from now on
+                Ok(()) => {
+                    if let Some(skipped) = SKIPPED.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+                        skipped.remove(&job.path);
+                    }
+                }
+                // Failed sources stay skipped until the app restarts
+                Err(e) => warn!("No thumbnail for {}: {}", job.path.display(), e),
+            }
+        }
+    });
+}
+
+fn enqueue(path: PathBuf, requested: String, kind: SourceKind) {
+    let mut skipped = SKIPPED.lock().unwrap_or_else(|e| e.into_inner());
+    if !skipped.get_or_insert_with(HashSet::new).insert(path.clone()) {
+        return;
+    }
+    match JOBS.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(sender) => {
+            if sender.send(ThumbnailJob { path, requested, kind }).is_err() {
+                error!("Thumbnail service has stopped.");
+            }
+        }
+        None => warn!("Thumbnail service is not running."),
+    }
+}
+
+// Hash of the thumbnail for `path` when it is ready. Otherwise the thumbnail is queued,
+// None is returned, and a thumbnail-ready event follows once it exists.
+#[tauri::command]
+pub async fn get_thumbnail(database: State<'_, Database>, path: String) -> Result<Option<String>, String> {
+    database
+        .run(move |db| {
+            let source = resolve_in_content_roots(db, &path)?;
+            let Some(kind) = source_kind(&source) else {
+                return Ok(None);
+            };
+            if let Some(hash) = cached_hash(db, &source)? {
+                if thumbnail_dir()?.join(&hash).is_file() {
+                    return Ok(Some(hash));
+                }
+            }
+            enqueue(source, path, kind);
+            Ok(None)
+        })
+        .await
+}
+
+fn response(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body)
+        .unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+// Serves cached thumbnails by hash. Only names that are a SHA-256 hex digest are looked
+// up, so requests cannot reach anything else on disk.
+pub fn handle_thumbnail_request<R: Runtime>(_ctx: UriSchemeContext<'_, R>, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let hash = request.uri().path().trim_start_matches('/');
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return response(StatusCode::BAD_REQUEST, "text/plain", b"invalid thumbnail".to_vec());
+    }
+    let image = thumbnail_dir().and_then(|dir| fs::read(dir.join(hash)).map_err(|e| e.to_string()));
+    match image {
+        Ok(image) => {
+            let content_type = if image.starts_with(b"\x89PNG") { "image/png" } else { "image/jpeg" };
+            response(StatusCode::OK, content_type, image)
+        }
+        Err(_) => response(StatusCode::NOT_FOUND, "text/plain", b"thumbnail not found".to_vec()),
+    }
+}
@@ -0,0 +1,528 @@
+// src-tauri/src/update.rs
+
+use crate::db::Database;
+use crate::integrity::sha256_file;
+use crate::manifest::{self, is_safe_relative};
+use crate::package::{
+    archive_kind, ensure_free_space, extract, find_package_root, unpacked_size, ImportProgress, ImportStage,
+    StagingDir, IMPORT_PROGRESS_EVENT,
+};
+use crate::roles::{require_permission, Permission};
+use crate::watcher::pause_watcher;
+use log::{error, info, warn};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+// Descriptor at the root of an update package
+pub const UPDATE_FILE: &str = "update.json";
+
+// Both live inside the content root so files move by rename, even on a 40 GB USB drive.
+// Hidden folders are skipped by listings, the indexer and integrity checks.
+const UPDATE_WORK_DIR: &str = ".osiris-update";
+const ROLLBACK_DIR: &str = ".osiris-rollback";
+const ROLLBACK_FILES_DIR: &str = "files";
+const JOURNAL_FILE: &str = "journal.json";
+
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+// What an update package changes. Added and changed files are shipped in the package
+// at their paths relative to the content root; removed files are only listed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDescriptor {
+    pub base_version: String,
+    pub version: String,
+    #[serde(default)]
+    pub added: Vec<UpdateFile>,
+    #[serde(default)]
+    pub changed: Vec<UpdateFile>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateFile {
+    pub path: String,
+    pub sha256: String,
+}
+
+// Written before an update touches the tree. `saved` files were moved into the rollback
+// folder, `written` files were placed by the update. An incomplete journal means the app
+// stopped mid-update, and the tree is restored on the next start.
+#[derive(Debug, Serialize, Deserialize)]
+struct Journal {
+    from_version: String,
+    to_version: String,
+    saved: Vec<String>,
+    written: Vec<String>,
+    complete: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackPoint {
+    pub from_version: String,
+    pub to_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateResult {
+    pub from_version: String,
+    pub to_version: String,
+    pub added: usize,
+    pub changed: usize,
+    pub removed: usize,
+}
+
+fn content_path_version(db: &Connection, id: i64) -> Result<(PathBuf, Option<String>), String> {
+    db.query_row("SELECT path, version FROM ContentPaths WHERE id = ?1", params![id], |row| {
+        Ok((PathBuf::from(row.get::<_, String>(0)?), row.get(1)?))
+    })
+    .map_err(|e| {
+        warn!("Content path {} not found for update: {}", id, e);
+        format!("Content path with id {} not found.", id)
+    })
+}
+
+// Stores the version now on disk, along with package details from the manifest if it has one
+fn record_version(db: &Connection, id: i64, root: &Path, version: &str) -> Result<(), String> {
+    let manifest = manifest::read_manifest(root).unwrap_or_else(|e| {
+        warn!("Ignoring manifest after update: {}", e);
+        None
+    });
+    db.execute(
+        "UPDATE ContentPaths SET
+            version = ?1,
+            title = COALESCE(?2, title),
+            board = COALESCE(?3, board),
+            language = COALESCE(?4, language),
+            updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?5",
+        params![
+            version,
+            manifest.as_ref().map(|m| m.title.clone()),
+            manifest.as_ref().and_then(|m| m.board.clone()),
+            manifest.as_ref().and_then(|m| m.language.clone()),
+            id,
+        ],
+    )
+    .map_err(|e| {
+        error!("Failed to record version {} for content path {}: {}", version, id, e);
+        e.to_string()
+    })?;
+    Ok(())
+}
+
+fn read_journal(root: &Path) -> Option<Journal> {
+    let path = root.join(ROLLBACK_DIR).join(JOURNAL_FILE);
+    let contents = fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&contents)
+        .map_err(|e| warn!("Ignoring unreadable update journal {}: {}", path.display(), e))
+        .ok()
+}
+
+fn write_journal(root: &Path, journal: &Journal) -> Result<(), String> {
+    let path = root.join(ROLLBACK_DIR).join(JOURNAL_FILE);
+    let contents = serde_json::to_string_pretty(journal).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| {
+        error!("Failed to write update journal {}: {}", path.display(), e);
+        format!("Failed to write update journal: {}", e)
+    })
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::rename(from, to).map_err(|e| format!("Failed to move {} to {}: {}", from.display(), to.display(), e))
+}
+
+// Removes folders left empty by removed files, up to but not including the root
+fn prune_empty_dirs(root: &Path, relative: &str) {
+    let mut dir = root.join(relative);
+    while dir.pop() && dir != root && dir.starts_with(root) {
+        if fs::remove_dir(&dir).is_err() {
+            break;
+        }
+    }
+}
+
+// Puts back every saved file and drops files the update added. Only files that actually
+// reached the rollback folder are restored, so this is safe on a half-applied update.
+fn restore_from_journal(root: &Path, journal: &Journal) -> Result<(), String> {
+    let saved_dir = root.join(ROLLBACK_DIR).join(ROLLBACK_FILES_DIR);
+    let saved: BTreeSet<&String> = journal.saved.iter().collect();
+    let mut failures = Vec::new();
+
+    for relative in journal.written.iter().filter(|p| !saved.contains(p)) {
+        let path = root.join(relative);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                failures.push(format!("{}: {}", relative, e));
+                continue;
+            }
+            prune_empty_dirs(root, relative);
+        }
+    }
+    for relative in &journal.saved {
+        let backup = saved_dir.join(relative);
+        if !backup.exists() {
+            continue;
+        }
+        let path = root.join(relative);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                failures.push(format!("{}: {}", relative, e));
+                continue;
+            }
+        }
+        if let Err(e) = move_file(&backup, &path) {
+            failures.push(e);
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        error!("Failed to restore {} files in {}: {:?}", failures.len(), root.display(), failures);
+        Err(format!("Failed to restore {} files: {}", failures.len(), failures.join("; ")))
+    }
+}
+
+fn discard_rollback(root: &Path) {
+    let dir = root.join(ROLLBACK_DIR);
+    if dir.exists() {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            warn!("Failed to remove rollback folder {}: {}", dir.display(), e);
+        }
+    }
+}
+
+// Undoes an update that was interrupted before it finished. The version is put back too,
+// since the update may have recorded its own before the journal was marked complete.
+fn recover_interrupted(db: &Connection, id: i64, root: &Path) -> Result<(), String> {
+    if let Some(journal) = read_journal(root).filter(|j| !j.complete) {
+        warn!(
+            "Restoring {} after an interrupted update from {} to {}",
+            root.display(),
+            journal.from_version,
+            journal.to_version
+        );
+        restore_from_journal(root, &journal)?;
+        record_version(db, id, root, &journal.from_version)?;
+        discard_rollback(root);
+    }
+    Ok(())
+}
+
+// Restores every content path left half-updated when the app stopped. Runs at startup,
+// before the watcher and indexer see the trees.
+pub fn recover_interrupted_updates(database: &Database) -> Result<(), String> {
+    let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let db = database.connection()?;
+    let roots = db
+        .prepare("SELECT id, path FROM ContentPaths")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, PathBuf::from(row.get::<_, String>(1)?))))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|e| {
+            error!("Failed to list content paths for update recovery: {}", e);
+            e.to_string()
+        })?;
+    for (id, root) in roots.iter().filter(|(_, root)| root.is_dir()) {
+        // One damaged root should not keep the others from being restored
+        if let Err(e) = recover_interrupted(&db, *id, root) {
+            error!("Failed to recover interrupted update of {}: {}", root.display(), e);
+        }
+    }
+    Ok(())
+}
+
+// Checks the package against its descriptor before anything in the content root changes
+fn check_update(package: &Path, descriptor: &UpdateDescriptor) -> Result<(), String> {
+    let mut problems = Vec::new();
+    let all_paths = descriptor
+        .added
+        .iter()
+        .chain(&descriptor.changed)
+        .map(|f| &f.path)
+        .chain(&descriptor.removed);
+    for path in all_paths {
+        if !is_safe_relative(path) || path.split('/').any(|c| c.starts_with('.')) {
+            problems.push(format!("'{}' is not a valid content path", path));
+        }
+    }
+    for file in descriptor.added.iter().chain(&descriptor.changed) {
+        match sha256_file(&package.join(&file.path)) {
+            Ok(hash) if hash.eq_ignore_ascii_case(&file.sha256) => (),
+            Ok(_) => problems.push(format!("'{}' is corrupt", file.path)),
+            Err(_) => problems.push(format!("'{}' is missing from the package", file.path)),
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        error!("Update package failed checks: {:?}", problems);
+        Err(format!("The update package is damaged: {}", problems.join("; ")))
+    }
+}
+
+// Connections are taken only for the lookups, not for the length of the extraction
+fn apply_update(
+    database: &Database,
+    id: i64,
+    archive_path: &Path,
+    on_progress: &dyn Fn(ImportProgress),
+) -> Result<UpdateResult, String> {
+    let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (root, installed_version) = {
+        let db = database.connection()?;
+        let (root, _) = content_path_version(&db, id)?;
+        if !root.is_dir() {
+            return Err(format!("Content path {} is not available.", root.display()));
+        }
+        // In case the root was offline when the app started
+        recover_interrupted(&db, id, &root)?;
+        content_path_version(&db, id)?
+    };
+
+    on_progress(ImportProgress { stage: ImportStage::Checking, checked: None, total: None });
+    let kind = archive_kind(archive_path)?;
+    ensure_free_space(&root, unpacked_size(archive_path, kind)?)?;
+
+    on_progress(ImportProgress { stage: ImportStage::Extracting, checked: None, total: None });
+    let work = StagingDir(root.join(UPDATE_WORK_DIR));
+    if work.0.exists() {
+        fs::remove_dir_all(&work.0).map_err(|e| e.to_string())?;
+    }
+    fs::create_dir_all(&work.0).map_err(|e| e.to_string())?;
+    extract(archive_path, kind, &work.0)?;
+    let package = find_package_root(&work.0, UPDATE_FILE);
+    let descriptor: UpdateDescriptor = fs::read_to_string(package.join(UPDATE_FILE))
+        .map_err(|_| format!("The package has no {} and is not an update package.", UPDATE_FILE))
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {}", UPDATE_FILE, e)))?;
+
+    match installed_version.as_deref() {
+        Some(version) if version == descriptor.base_version => (),
+        Some(version) => {
+            return Err(format!(
+                "This update applies to version {}, but version {} is installed.",
+                descriptor.base_version, version
+            ))
+        }
+        None => return Err("The installed content has no recorded version, so updates cannot be applied.".to_string()),
+    }
+
+    on_progress(ImportProgress { stage: ImportStage::Verifying, checked: None, total: None });
+    check_update(&package, &descriptor)?;
+
+    on_progress(ImportProgress { stage: ImportStage::Installing, checked: None, total: None });
+    let written: Vec<String> = descriptor.added.iter().chain(&descriptor.changed).map(|f| f.path.clone()).collect();
+    // Anything about to be replaced or removed is kept for rollback, including "added"
+    // files that already exist locally
+    let saved: Vec<String> = written
+        .iter()
+        .chain(&descriptor.removed)
+        .filter(|p| root.join(p).is_file())
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    for missing in descriptor.removed.iter().chain(descriptor.changed.iter().map(|f| &f.path)) {
+        if !root.join(missing).is_file() {
+            warn!("'{}' listed in the update is not present in {}", missing, root.display());
+        }
+    }
+
+    // Only the latest update can be rolled back
+    discard_rollback(&root);
+    let saved_dir = root.join(ROLLBACK_DIR).join(ROLLBACK_FILES_DIR);
+    fs::create_dir_all(&saved_dir).map_err(|e| e.to_string())?;
+    let mut journal = Journal {
+        from_version: descriptor.base_version.clone(),
+        to_version: descriptor.version.clone(),
+        saved,
+        written,
+        complete: false,
+    };
+    write_journal(&root, &journal)?;
+
+    // Until the journal is complete the tree is half old, half new
+    let _paused = pause_watcher(&root);
+    let patch = || -> Result<(), String> {
+        for relative in &journal.saved {
+            move_file(&root.join(relative), &saved_dir.join(relative))?;
+        }
+        for relative in &journal.written {
+            move_file(&package.join(relative), &root.join(relative))?;
+        }
+        for relative in &descriptor.removed {
+            prune_empty_dirs(&root, relative);
+        }
+        let db = database.connection()?;
+        record_version(&db, id, &root, &descriptor.version)
+    };
+    if let Err(e) = patch() {
+        error!("Update of {} failed, restoring previous version: {}", root.display(), e);
+        restore_from_journal(&root, &journal)?;
+        discard_rollback(&root);
+        return Err(e);
+    }
+
+    journal.complete = true;
+    if let Err(e) = write_journal(&root, &journal) {
+        // The tree is fully updated; without a journal there is just no rollback
+        warn!("Update applied but rollback will not be available: {}", e);
+        discard_rollback(&root);
+    }
+    info!(
+        "Updated {} from {} to {}",
+        root.display(),
+        descriptor.base_version,
+        descriptor.version
+    );
+    Ok(UpdateResult {
+        from_version: descriptor.base_version,
+        to_version: descriptor.version,
+        added: descriptor.added.len(),
+        changed: descriptor.changed.len(),
+        removed: descriptor.removed.len(),
+    })
+}
+
+// Applies a delta update package to a registered content path
+#[tauri::command]
+pub async fn apply_content_update(
+    app: AppHandle,
+    database: State<'_, Database>,
+    id: i64,
+    archive_path: String,
+) -> Result<UpdateResult, String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Applying update {} to content path {}", archive_path, id);
+    let database = database.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        apply_update(&database, id, Path::new(&archive_path), &|progress| {
+            if let Err(e) = app.emit(IMPORT_PROGRESS_EVENT, progress) {
+                warn!("Failed to emit {} event: {}", IMPORT_PROGRESS_EVENT, e);
+            }
+        })
+        .inspect_err(|e| error!("Failed to apply update {}: {}", archive_path, e))
+    })
+    .await
+    .map_err(|e| {
+        error!("Content update task failed: {}", e);
+        e.to_string()
+    })?
+}
+
+// The update that can currently be rolled back, if any
+#[tauri::command]
+pub async fn get_content_rollback_point(database: State<'_, Database>, id: i64) -> Result<Option<RollbackPoint>, String> {
+    info!("Checking rollback point for content path {}", id);
+    let (root, version) = database.run(move |db| content_path_version(db, id)).await?;
+    Ok(read_journal(&root)
+        .filter(|j| j.complete && version.as_deref() == Some(j.to_version.as_str()))
+        .map(|j| RollbackPoint { from_version: j.from_version, to_version: j.to_version }))
+}
+
+// Restores the content path to the version it had before its latest update
+#[tauri::command]
+pub async fn rollback_content_update(database: State<'_, Database>, id: i64) -> Result<RollbackPoint, String> {
+    require_permission(Permission::ManageContent)?;
+    info!("Rolling back latest update of content path {}", id);
+    database
+        .run_blocking(move |database| {
+            let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let (root, version) = content_path_version(&*database.connection()?, id)?;
+            let journal = read_journal(&root)
+                .filter(|j| j.complete)
+                .ok_or_else(|| "There is no update to roll back.".to_string())?;
+            if version.as_deref() != Some(journal.to_version.as_str()) {
+                return Err(format!(
+                    "The installed version is {}, not {}, so the update cannot be rolled back.",
+                    version.unwrap_or_default(),
+                    journal.to_version
+                ));
+            }
+
+            let _paused = pause_watcher(&root);
+            restore_from_journal(&root, &journal)?;
+            record_version(&*database.connection()?, id, &root, &journal.from_version)?;
+            discard_rollback(&root);
+            info!("Rolled back {} from {} to {}", root.display(), journal.to_version, journal.from_version);
+            Ok(RollbackPoint { from_version: journal.from_version, to_version: journal.to_version })
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn restore_from_journal_undoes_a_half_applied_update() {
+        let root = std::env::temp_dir().join(format!("osiris-test-rollback-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let saved_dir = root.join(ROLLBACK_DIR).join(ROLLBACK_FILES_DIR);
+
+        // Changed: the old file was saved and the new one written
+        write(&saved_dir.join("Class 8/Science/notes.pdf"), "old notes");
+        write(&root.join("Class 8/Science/notes.pdf"), "new notes");
+        // Removed: the old file was saved and nothing written
+        write(&saved_dir.join("Class 8/Science/quiz.xml"), "old quiz");
+        // Added: only written, in a folder the update created
+        write(&root.join("Class 8/Maths/Chapter 1/video.mp4"), "new video");
+        // Listed as saved, but the update stopped before moving it
+        write(&root.join("Class 8/Science/animation.mp4"), "untouched");
+
+        let journal = Journal {
+            from_version: "1.0".to_string(),
+            to_version: "1.1".to_string(),
+            saved: vec![
+                "Class 8/Science/notes.pdf".to_string(),
+                "Class 8/Science/quiz.xml".to_string(),
+                "Class 8/Science/animation.mp4".to_string(),
+            ],
+            written: vec!["Class 8/Science/notes.pdf".to_string(), "Class 8/Maths/Chapter 1/video.mp4".to_string()],
+            complete: false,
+        };
+        fs::create_dir_all(root.join(ROLLBACK_DIR)).unwrap();
+        write_journal(&root, &journal).unwrap();
+        assert_eq!(read_journal(&root).map(|j| (j.to_version, j.complete)), Some(("1.1".to_string(), false)));
+
+        restore_from_journal(&root, &journal).unwrap();
+        assert_eq!(fs::read_to_string(root.join("Class 8/Science/notes.pdf")).unwrap(), "old notes");
+        assert_eq!(fs::read_to_string(root.join("Class 8/Science/quiz.xml")).unwrap(), "old quiz");
+        assert_eq!(fs::read_to_string(root.join("Class 8/Science/animation.mp4")).unwrap(), "untouched");
+        assert!(!root.join("Class 8/Maths").exists());
+        assert!(!saved_dir.join("Class 8/Science/notes.pdf").exists());
+
+        discard_rollback(&root);
+        assert!(!root.join(ROLLBACK_DIR).exists());
+        assert!(root.join("Class 8/Science").is_dir());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn prune_empty_dirs_stops_at_the_root_and_at_folders_in_use() {
+        let root = std::env::temp_dir().join(format!("osiris-test-prune-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        write(&root.join("Class 8/keep.txt"), "");
+        fs::create_dir_all(root.join("Class 8/Maths/Chapter 1")).unwrap();
+
+        prune_empty_dirs(&root, "Class 8/Maths/Chapter 1/video.mp4");
+        assert!(!root.join("Class 8/Maths").exists());
+        assert!(root.join("Class 8/keep.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
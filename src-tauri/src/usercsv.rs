@@ -0,0 +1,613 @@
+// src-tauri/src/usercsv.rs
+
+use crate::db::Database;
+use crate::roles::{require_permission, Permission, Role};
+use crate::roster::section_label;
+use crate::users::deleted_username_error;
+use log::{error, info, warn};
+use rand::Rng;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tauri::State;
+
+// Generated passwords avoid look-alike characters (0/O, 1/l/I) since they are handed out on paper
+const PASSWORD_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const GENERATED_PASSWORD_LENGTH: usize = 8;
+// Header written by export_users_csv and expected by default on import
+const EXPORT_HEADER: [&str; 6] = ["username", "display_name", "role", "class", "section", "roll_number"];
+
+// CSV header names to read each field from. Unset fields use the field name as header.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct CsvColumnMapping {
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub role: Option<String>,
+    pub class: Option<String>, // "Class 8", or "Class 8 - B" when there is no section column
+    pub section: Option<String>,
+    pub roll_number: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UserImportOptions {
+    #[serde(default)]
+    pub mapping: CsvColumnMapping,
+    pub default_role: Option<String>, // For rows with no role
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserImportRow {
+    pub line: usize, // 1-based line in the file
+    pub username: String,
+    pub display_name: Option<String>,
+    pub role: String,
+    pub section: Option<String>, // "Class 8 - B"
+    pub roll_number: Option<String>,
+    pub password: Option<String>, // Generated password, only after a real import
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserImportReport {
+    pub dry_run: bool,
+    pub valid: bool,
+    pub imported: usize,
+    pub new_sections: Vec<String>, // Sections created (or to be created) for the rows
+    pub rows: Vec<UserImportRow>,
+}
+
+// Splits CSV text into records (RFC 4180: quoted fields, "" escapes, CRLF or LF). Each
+// record comes with the line it starts on. Blank lines are skipped.
+fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            (c, _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if in_quotes {
+        return Err(format!("Unterminated quoted field starting on line {}.", record_line));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) {
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn generate_password() -> String {
+    let mut rng = rand::thread_rng();
+    (0..GENERATED_PASSWORD_LENGTH)
+        .map(|_| PASSWORD_ALPHABET[rng.gen_range(0..PASSWORD_ALPHABET.len())] as char)
+        .collect()
+}
+
+// Column index of each mapped field, looked up by header name (case-insensitive)
+fn column_indexes(header: &[String], mapping: &CsvColumnMapping) -> Result<HashMap<&'static str, usize>, String> {
+    let fields: [(&'static str, &Option<String>); 7] = [
+        ("username", &mapping.username),
+        ("display_name", &mapping.display_name),
+        ("role", &mapping.role),
+        ("class", &mapping.class),
+        ("section", &mapping.section),
+        ("roll_number", &mapping.roll_number),
+        ("password", &mapping.password),
+    ];
+    let mut indexes = HashMap::new();
+    for (field, mapped) in fields {
+        let name = mapped.as_deref().unwrap_or(field).trim();
+        match header.iter().position(|h| h.trim().eq_ignore_ascii_case(name)) {
+            Some(index) => {
+                indexes.insert(field, index);
+            }
+            // A header named explicitly in the mapping must exist
+            None if mapped.is_some() => return Err(format!("The file has no column named '{}'.", name)),
+            None => {}
+        }
+    }
+    if !indexes.contains_key("username") {
+        return Err("The file has no username column.".to_string());
+    }
+    Ok(indexes)
+}
+
+fn section_id(db: &Connection, class_name: &str, section_name: &str) -> rusqlite::Result<Option<i64>> {
+    db.query_row(
+        "SELECT s.id FROM Sections s JOIN Classes c ON c.id = s.class_id WHERE c.name = ?1 AND s.name = ?2",
+        params![class_name, section_name],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn find_or_create_section(tx: &Transaction, class_name: &str, section_name: &str) -> rusqlite::Result<i64> {
+    if let Some(id) = section_id(tx, class_name, section_name)? {
+        return Ok(id);
+    }
+    tx.execute("INSERT OR IGNORE INTO Classes (name) VALUES (?1)", params![class_name])?;
+    let class_id: i64 = tx.query_row("SELECT id FROM Classes WHERE name = ?1", params![class_name], |row| row.get(0))?;
+    tx.execute("INSERT INTO Sections (class_id, name) VALUES (?1, ?2)", params![class_id, section_name])?;
+    Ok(tx.last_insert_rowid())
+}
+
+// Rows read from an import file, with the section each student goes into
+struct ValidatedImport {
+    rows: Vec<(Option<(String, String)>, UserImportRow)>,
+    sections: Vec<(String, String)>, // Distinct (class, section) pairs, in file order
+    passwords: Vec<Option<String>>, // Passwords given in the file, per row
+}
+
+// Checks every record against the users already stored and the rest of the file. Problems
+// are collected per row so the whole file can be reported at once.
+fn validate_records(
+    db: &Connection,
+    records: impl Iterator<Item = (usize, Vec<String>)>,
+    columns: &HashMap<&'static str, usize>,
+    default_role: Option<&str>,
+) -> Result<ValidatedImport, String> {
+    // Lowercased usernames in use, and whether only deleted users hold them
+    let existing_usernames: HashMap<String, bool> = {
+        let mut stmt = db
+            .prepare("SELECT lower(username), MIN(deleted_at IS NOT NULL) FROM Users GROUP BY lower(username)")
+            .map_err(|e| e.to_string())?;
+        let names = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect::<rusqlite::Result<HashMap<String, bool>>>())
+            .map_err(|e| e.to_string())?;
+        names
+    };
+    let existing_rolls: HashSet<(i64, String)> = {
+        let mut stmt = db
+            .prepare("SELECT section_id, roll_number FROM Users
+                      WHERE section_id IS NOT NULL AND roll_number IS NOT NULL AND deleted_at IS NULL")
+            .map_err(|e| e.to_string())?;
+        let rolls = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect::<rusqlite::Result<HashSet<(i64, String)>>>())
+            .map_err(|e| e.to_string())?;
+        rolls
+    };
+
+    let mut rows = Vec::new();
+    let mut seen_usernames = HashSet::new();
+    let mut seen_rolls = HashSet::new();
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut passwords: Vec<Option<String>> = Vec::new();
+    for (line, record) in records {
+        let get = |field: &str| {
+            columns
+                .get(field)
+                .and_then(|index| record.get(*index))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let mut errors = Vec::new();
+
+        let username = get("username").unwrap_or_default();
+        if username.is_empty() {
+            errors.push("Username is missing.".to_string());
+        } else if let Some(deleted) = existing_usernames.get(&username.to_lowercase()) {
+            errors.push(if *deleted {
+                deleted_username_error(&username)
+            } else {
+                format!("Username '{}' already exists.", username)
+            });
+        } else if !seen_usernames.insert(username.to_lowercase()) {
+            errors.push(format!("Username '{}' appears more than once in the file.", username));
+        }
+
+        let role_name = get("role").or_else(|| default_role.map(str::to_string)).unwrap_or_default();
+        let role = Role::parse(&role_name);
+        if role_name.is_empty() {
+            errors.push("Role is missing.".to_string());
+        } else if role.is_none() {
+            let known: Vec<&str> = Role::ALL.iter().map(Role::as_str).collect();
+            errors.push(format!("Unknown role '{}'; expected one of {}.", role_name, known.join(", ")));
+        }
+
+        // Students need a class and section, either in two columns or as "Class 8 - B"
+        let (class_name, section_name) = match (get("class"), get("section")) {
+            (Some(class), None) => match class.rsplit_once(" - ") {
+                Some((class, section)) => (Some(class.trim().to_string()), Some(section.trim().to_string())),
+                None => (Some(class), None),
+            },
+            (class, section) => (class, section),
+        };
+        let roll_number = get("roll_number");
+        let section = match (class_name, section_name) {
+            (Some(class), Some(section)) => Some((class, section)),
+            (None, _) if role == Some(Role::Student) => {
+                errors.push("Class is missing.".to_string());
+                None
+            }
+            (Some(_), None) if role == Some(Role::Student) => {
+                errors.push("Section is missing.".to_string());
+                None
+            }
+            _ => None,
+        };
+        if let Some(other) = role.filter(|r| *r != Role::Student && (section.is_some() || roll_number.is_some())) {
+            errors.push(format!("Only students have a class and roll number, not a {}.", other.as_str()));
+        }
+        if let (Some((class, section_name)), Some(roll)) = (&section, &roll_number) {
+            let existing = section_id(db, class, section_name)
+                .map_err(|e| e.to_string())?
+                .is_some_and(|id| existing_rolls.contains(&(id, roll.clone())));
+            if existing || !seen_rolls.insert((class.clone(), section_name.clone(), roll.clone())) {
+                errors.push(format!("Roll number {} is already used in {}.", roll, section_label(class, section_name)));
+            }
+        }
+        if let Some(key) = &section {
+            if !sections.contains(key) {
+                sections.push(key.clone());
+            }
+        }
+
+        passwords.push(get("password"));
+        rows.push((
+            section,
+            UserImportRow {
+                line,
+                username,
+                display_name: get("display_name"),
+                role: role.map(|r| r.as_str().to_string()).unwrap_or(role_name),
+                section: None,
+                roll_number,
+                password: None,
+                errors,
+            },
+        ));
+    }
+    Ok(ValidatedImport { rows, sections, passwords })
+}
+
+// Validates every row, then (unless `dry_run`) inserts all users in one transaction.
+// Nothing is inserted if any row has an error.
+#[tauri::command]
+pub async fn import_users_csv(database: State<'_, Database>, path: String, options: UserImportOptions) -> Result<UserImportReport, String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Importing users from {} (dry run: {})", path, options.dry_run);
+    database
+        .run_blocking(move |database| {
+            let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let mut records = parse_csv(&text)?.into_iter();
+            let (_, header) = records.next().ok_or_else(|| format!("{} is empty.", path))?;
+            let columns = column_indexes(&header, &options.mapping)?;
+
+            // Validation and inserts share one write transaction, so a username or section
+            // added in between cannot turn a valid report into a failed import
+            let mut db = database.connection()?;
+            let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| {
+                error!("Failed to start transaction for user import: {}", e);
+                e.to_string()
+            })?;
+            let ValidatedImport { mut rows, sections, passwords } =
+                validate_records(&tx, records, &columns, options.default_role.as_deref())?;
+
+            let new_sections: Vec<String> = sections
+                .iter()
+                .filter(|(class, section)| section_id(&tx, class, section).ok().flatten().is_none())
+                .map(|(class, section)| section_label(class, section))
+                .collect();
+            let valid = rows.iter().all(|(_, row)| row.errors.is_empty());
+            let mut report = UserImportReport { dry_run: options.dry_run, valid, imported: 0, new_sections, rows: Vec::new() };
+
+            if valid && !options.dry_run {
+                for ((section, row), password) in rows.iter_mut().zip(passwords) {
+                    let section_id = match section {
+                        Some((class, section)) => Some(find_or_create_section(&tx, class, section).map_err(|e| e.to_string())?),
+                        None => None,
+                    };
+                    // Passwords from the file are used as given; generated ones are returned to hand out
+                    let password = match password {
+                        Some(password) => password,
+                        None => {
+                            let generated = generate_password();
+                            row.password = Some(generated.clone());
+                            generated
+                        }
+                    };
+                    tx.execute(
+                        "INSERT INTO Users (username, password, role, section_id, roll_number, display_name)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![row.username, password, row.role, section_id, row.roll_number, row.display_name],
+                    )
+                    .map_err(|e| {
+                        error!("Failed to import user {} from line {}: {}", row.username, row.line, e);
+                        format!("Line {}: {}", row.line, e)
+                    })?;
+                }
+                tx.commit().map_err(|e| {
+                    error!("Failed to commit user import: {}", e);
+                    e.to_string()
+                })?;
+                report.imported = rows.len();
+                info!("Imported {} users from {}", report.imported, path);
+            } else if !valid {
+                warn!("User import from {} has errors; nothing was imported.", path);
+            }
+
+            report.rows = rows
+                .into_iter()
+                .map(|(section, mut row)| {
+                    row.section = section.map(|(class, section)| section_label(&class, &section));
+                    row
+                })
+                .collect();
+            Ok(report)
+        })
+        .await
+}
+
+// Writes users to `path` in the import format. Passwords are never exported.
+#[tauri::command]
+pub async fn export_users_csv(database: State<'_, Database>, path: String, section_id: Option<i64>) -> Result<usize, String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Exporting users to {} (section {:?})", path, section_id);
+    database
+        .run(move |db| {
+            let mut stmt = db
+                .prepare(
+                    "SELECT u.username, u.display_name, u.role, c.name, s.name, u.roll_number
+                     FROM Users u
+                     LEFT JOIN Sections s ON s.id = u.section_id
+                     LEFT JOIN Classes c ON c.id = s.class_id
+                     WHERE u.deleted_at IS NULL AND (?1 IS NULL OR u.section_id = ?1)
+                     ORDER BY c.name, s.name, CAST(u.roll_number AS INTEGER), u.roll_number, u.username",
+                )
+                .map_err(|e| e.to_string())?;
+            let users = stmt
+                .query_map(params![section_id], |row| {
+                    (0..EXPORT_HEADER.len())
+                        .map(|i| row.get::<_, Option<String>>(i).map(Option::unwrap_or_default))
+                        .collect::<rusqlite::Result<Vec<String>>>()
+                })
+                .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+                .map_err(|e| {
+                    error!("Failed to query users for export: {}", e);
+                    e.to_string()
+                })?;
+
+            let mut csv = EXPORT_HEADER.join(",");
+            csv.push_str("\r\n");
+            for user in &users {
+                csv.push_str(&user.iter().map(|value| csv_field(value)).collect::<Vec<_>>().join(","));
+                csv.push_str("\r\n");
+            }
+            fs::write(&path, csv).map_err(|e| {
+                error!("Failed to write {}: {}", path, e);
+                format!("Failed to write {}: {}", path, e)
+            })?;
+            info!("Exported {} users to {}", users.len(), path);
+            Ok(users.len())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_csv_handles_quotes_and_line_endings() {
+        let text = "\u{feff}username,display_name\r\nasha,\"Rao, Asha\"\r\n\r\nravi,\"Say \"\"hi\"\"\"\n";
+        let records = parse_csv(text).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (1, strings(&["username", "display_name"])),
+                (2, strings(&["asha", "Rao, Asha"])),
+                (4, strings(&["ravi", "Say \"hi\""])),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_numbers_records_after_multi_line_fields() {
+        let records = parse_csv("a,\"line one\nline two\"\nb,c").unwrap();
+        assert_eq!(records, vec![(1, strings(&["a", "line one\nline two"])), (3, strings(&["b", "c"]))]);
+    }
+
+    #[test]
+    fn parse_csv_rejects_unterminated_quotes() {
+        let error = parse_csv("username\nasha\n\"ravi\n").unwrap_err();
+        assert_eq!(error, "Unterminated quoted field starting on line 3.");
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("asha"), "asha");
+        assert_eq!(csv_field("Rao, Asha"), "\"Rao, Asha\"");
+        assert_eq!(csv_field("Say \"hi\""), "\"Say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn column_indexes_match_headers_case_insensitively() {
+        let header = strings(&["Login", " Role ", "CLASS"]);
+        let mapping = CsvColumnMapping { username: Some("login".to_string()), ..Default::default() };
+        let columns = column_indexes(&header, &mapping).unwrap();
+        assert_eq!(columns.get("username"), Some(&0));
+        assert_eq!(columns.get("role"), Some(&1));
+        assert_eq!(columns.get("class"), Some(&2));
+        assert_eq!(columns.get("section"), None);
+    }
+
+    #[test]
+    fn column_indexes_require_username_and_mapped_columns() {
+        let header = strings(&["name", "role"]);
+        assert_eq!(column_indexes(&header, &CsvColumnMapping::default()).unwrap_err(), "The file has no username column.");
+        let mapping = CsvColumnMapping { username: Some("name".to_string()), role: Some("type".to_string()), ..Default::default() };
+        assert_eq!(column_indexes(&header, &mapping).unwrap_err(), "The file has no column named 'type'.");
+    }
+
+    // Only the tables and columns validation reads
+    fn test_db() -> Connection {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(
+            "CREATE TABLE Classes (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE);
+             CREATE TABLE Sections (id INTEGER PRIMARY KEY, class_id INTEGER NOT NULL, name TEXT NOT NULL);
+             CREATE TABLE Users (id INTEGER PRIMARY KEY, username TEXT NOT NULL, section_id INTEGER,
+                                 roll_number TEXT, deleted_at TEXT);
+             INSERT INTO Classes (id, name) VALUES (1, 'Class 8');
+             INSERT INTO Sections (id, class_id, name) VALUES (1, 1, 'A');
+             INSERT INTO Users (username, section_id, roll_number) VALUES ('asha', 1, '7');
+             INSERT INTO Users (username, deleted_at) VALUES ('ravi', '2026-01-01 00:00:00');",
+        )
+        .unwrap();
+        db
+    }
+
+    fn validate(db: &Connection, lines: &[&[&str]], default_role: Option<&str>) -> ValidatedImport {
+        let header = strings(&["username", "role", "class", "section", "roll_number", "password"]);
+        let columns = column_indexes(&header, &CsvColumnMapping::default()).unwrap();
+        let records = lines.iter().enumerate().map(|(i, line)| (i + 2, strings(line)));
+        validate_records(db, records, &columns, default_role).unwrap()
+    }
+
+    fn errors(import: &ValidatedImport) -> Vec<Vec<String>> {
+        import.rows.iter().map(|(_, row)| row.errors.clone()).collect()
+    }
+
+    #[test]
+    fn validate_records_accepts_valid_rows() {
+        let db = test_db();
+        let import = validate(
+            &db,
+            &[&["meena", "student", "Class 8", "A", "8", "secret"], &["john", "Teacher", "", "", "", ""]],
+            None,
+        );
+        assert_eq!(errors(&import), vec![Vec::<String>::new(), Vec::new()]);
+        assert_eq!(import.rows[0].0, Some(("Class 8".to_string(), "A".to_string())));
+        assert_eq!(import.rows[1].1.role, "teacher");
+        assert_eq!(import.sections, vec![("Class 8".to_string(), "A".to_string())]);
+        assert_eq!(import.passwords, vec![Some("secret".to_string()), None]);
+        assert_eq!(import.rows[1].1.line, 3);
+    }
+
+    #[test]
+    fn validate_records_reports_username_conflicts() {
+        let db = test_db();
+        let import = validate(
+            &db,
+            &[
+                &["", "teacher", "", "", "", ""],
+                &["ASHA", "teacher", "", "", "", ""],
+                &["Ravi", "teacher", "", "", "", ""],
+                &["kiran", "teacher", "", "", "", ""],
+                &["Kiran", "teacher", "", "", "", ""],
+            ],
+            None,
+        );
+        assert_eq!(
+            errors(&import),
+            vec![
+                vec!["Username is missing.".to_string()],
+                vec!["Username 'ASHA' already exists.".to_string()],
+                vec![deleted_username_error("Ravi")],
+                vec![],
+                vec!["Username 'Kiran' appears more than once in the file.".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_records_checks_roles() {
+        let db = test_db();
+        let import = validate(&db, &[&["meena", "", "", "", "", ""], &["john", "principal", "", "", "", ""]], None);
+        let errors = errors(&import);
+        assert_eq!(errors[0], vec!["Role is missing.".to_string()]);
+        assert!(errors[1][0].starts_with("Unknown role 'principal'"));
+
+        let import = validate(&db, &[&["john", "", "", "", "", ""]], Some("teacher"));
+        assert!(import.rows[0].1.errors.is_empty());
+        assert_eq!(import.rows[0].1.role, "teacher");
+    }
+
+    #[test]
+    fn validate_records_reads_class_and_section_from_one_column() {
+        let db = test_db();
+        let import = validate(
+            &db,
+            &[
+                &["meena", "student", "Class 9 - B", "", "", ""],
+                &["kiran", "student", "", "", "", ""],
+                &["hari", "student", "Class 9", "", "", ""],
+            ],
+            None,
+        );
+        assert_eq!(import.rows[0].0, Some(("Class 9".to_string(), "B".to_string())));
+        assert_eq!(
+            errors(&import),
+            vec![vec![], vec!["Class is missing.".to_string()], vec!["Section is missing.".to_string()]]
+        );
+    }
+
+    #[test]
+    fn validate_records_reports_roll_number_conflicts() {
+        let db = test_db();
+        let import = validate(
+            &db,
+            &[
+                &["meena", "student", "Class 8", "A", "7", ""],
+                &["kiran", "student", "Class 8", "B", "7", ""],
+                &["hari", "student", "Class 8 - B", "", "7", ""],
+                &["john", "teacher", "", "", "3", ""],
+            ],
+            None,
+        );
+        assert_eq!(
+            errors(&import),
+            vec![
+                vec!["Roll number 7 is already used in Class 8 - A.".to_string()],
+                vec![],
+                vec!["Roll number 7 is already used in Class 8 - B.".to_string()],
+                vec!["Only students have a class and roll number, not a teacher.".to_string()],
+            ]
+        );
+        assert_eq!(import.sections, vec![("Class 8".to_string(), "A".to_string()), ("Class 8".to_string(), "B".to_string())]);
+    }
+}
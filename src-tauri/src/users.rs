@@ -1,66 +1,142 @@
-use crate::db::get_db_path;
-use rusqlite::{Connection, Result, params};
+use crate::db::Database;
+use crate::roles::{require_permission, Permission, Role};
+use crate::xapi::anonymous_agent;
+use rusqlite::{Connection, OptionalExtension, Result, TransactionBehavior, params};
 use serde::{Deserialize, Serialize};
-use log::{info, error};
+use log::{info, error, warn};
+use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct User {
     pub id: Option<i64>,
     pub username: String,
     pub password: String,
-    pub role: String,
+    pub role: Role,
+    // Roster details of students; see roster.rs
+    #[serde(default)]
+    pub section_id: Option<i64>,
+    #[serde(default)]
+    pub roll_number: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    // Set while the user is soft deleted; see delete_user
+    #[serde(default)]
+    pub deleted_at: Option<String>,
 }
 
-// Command to add a new user
-#[tauri::command]
-pub fn add_user(user: User) -> Result<i64, String> {
-    info!("Adding new user: {}", user.username);
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for adding user: {}", e);
-        e.to_string()
-    })?;
+// What purge_user does with the learning history of the purged user
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PurgeMode {
+    Delete,    // Progress and xAPI statements are deleted with the user
+    Anonymize, // They are kept without a user, so per-item statistics still count them
+}
 
-    db.execute(
-        "INSERT INTO Users (username, password, role) VALUES (?1, ?2, ?3)",
-        params![
+// Only students belong to a section and have a roll number
+fn validate_roster_fields(user: &User) -> Result<(), String> {
+    if user.role != Role::Student && (user.section_id.is_some() || user.roll_number.is_some()) {
+        return Err(format!(
+            "Only students can be assigned a section and roll number; {} is a {}.",
             user.username,
-            user.password,
-            user.role
-        ],
+            user.role.as_str()
+        ));
+    }
+    Ok(())
+}
+
+// Admins other than `user_id`. The school is locked out if this reaches zero.
+fn other_admin_count(db: &Connection, user_id: i64) -> Result<i64, String> {
+    db.query_row(
+        "SELECT COUNT(*) FROM Users WHERE role = ?1 AND id != ?2 AND deleted_at IS NULL",
+        params![Role::Admin, user_id],
+        |row| row.get(0),
     )
     .map_err(|e| {
-        error!("Failed to insert user {}: {}", user.username, e);
+        error!("Failed to count admins: {}", e);
         e.to_string()
-    })?;
+    })
+}
+
+fn stored_role(db: &Connection, user_id: i64) -> Result<Option<Role>, String> {
+    db.query_row("SELECT role FROM Users WHERE id = ?1 AND deleted_at IS NULL", params![user_id], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+// Whether `username` belongs to a soft-deleted user
+pub fn username_of_deleted_user(db: &Connection, username: &str) -> Result<bool, String> {
+    db.query_row(
+        "SELECT EXISTS(SELECT 1 FROM Users WHERE username = ?1 AND deleted_at IS NOT NULL)",
+        params![username],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
 
-    let last_id = db.last_insert_rowid();
-    info!("Successfully added user {} with ID: {}", user.username, last_id);
-    Ok(last_id)
+pub fn deleted_username_error(username: &str) -> String {
+    format!("Username '{}' belongs to a deleted user. Restore or purge the deleted user first.", username)
 }
 
-// Command to get all users
+// Command to add a new user
 #[tauri::command]
-pub fn get_users() -> Result<Vec<User>, String> {
-    info!("Retrieving all users.");
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for getting users: {}", e);
-        e.to_string()
-    })?;
+pub async fn add_user(database: State<'_, Database>, user: User) -> Result<i64, String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Adding new user: {}", user.username);
+    validate_roster_fields(&user)?;
+    database
+        .run(move |db| {
+            // Usernames stay taken while their user is deleted, so the user can be restored
+            if username_of_deleted_user(db, &user.username)? {
+                warn!("Cannot add user {}: a deleted user has that username.", user.username);
+                return Err(deleted_username_error(&user.username));
+            }
+            db.execute(
+                "INSERT INTO Users (username, password, role, section_id, roll_number, display_name)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    user.username,
+                    user.password,
+                    user.role,
+                    user.section_id,
+                    user.roll_number,
+                    user.display_name
+                ],
+            )
+            .map_err(|e| {
+                error!("Failed to insert user {}: {}", user.username, e);
+                e.to_string()
+            })?;
 
-    let mut stmt = db.prepare("SELECT id, username, password, role FROM Users")
+            let last_id = db.last_insert_rowid();
+            info!("Successfully added user {} with ID: {}", user.username, last_id);
+            Ok(last_id)
+        })
+        .await
+}
+
+// Users matching `filter`, a condition on Users columns
+fn query_users(db: &Connection, filter: &str) -> Result<Vec<User>, String> {
+    let mut stmt = db
+        .prepare(&format!(
+            "SELECT id, username, password, role, section_id, roll_number, display_name, deleted_at
+             FROM Users WHERE {}",
+            filter
+        ))
         .map_err(|e| {
             error!("Failed to prepare statement for getting users: {}", e);
             e.to_string()
         })?;
-    
+
     let users = stmt.query_map([], |row| {
         Ok(User {
             id: row.get(0)?,
             username: row.get(1)?,
             password: row.get(2)?,
             role: row.get(3)?,
+            section_id: row.get(4)?,
+            roll_number: row.get(5)?,
+            display_name: row.get(6)?,
+            deleted_at: row.get(7)?,
         })
     })
     .map_err(|e| {
@@ -72,68 +148,219 @@ pub fn get_users() -> Result<Vec<User>, String> {
         error!("Failed to collect users: {}", e);
         e.to_string()
     })?;
-
-    info!("Successfully retrieved {} users.", users.len());
     Ok(users)
 }
 
+// Command to get all users, except deleted ones
+#[tauri::command]
+pub async fn get_users(database: State<'_, Database>) -> Result<Vec<User>, String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Retrieving all users.");
+    database
+        .run(|db| {
+            let users = query_users(db, "deleted_at IS NULL")?;
+            info!("Successfully retrieved {} users.", users.len());
+            Ok(users)
+        })
+        .await
+}
+
+// Soft-deleted users, which can be restored or purged
+#[tauri::command]
+pub async fn get_deleted_users(database: State<'_, Database>) -> Result<Vec<User>, String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Retrieving deleted users.");
+    database.run(|db| query_users(db, "deleted_at IS NOT NULL ORDER BY deleted_at DESC")).await
+}
+
 // Command to update an existing user
 #[tauri::command]
-pub fn update_user(user: User) -> Result<(), String> {
+pub async fn update_user(database: State<'_, Database>, user: User) -> Result<(), String> {
+    require_permission(Permission::ManageUsers)?;
     info!("Updating user with ID: {:?}", user.id);
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for updating user: {}", e);
-        e.to_string()
-    })?;
+    validate_roster_fields(&user)?;
+    database
+        .run(move |db| {
+            // Immediate, so no other change to the admins lands between the check and the update
+            let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
 
-    let updated_rows = db.execute(
-        "UPDATE Users SET username = ?1, password = ?2, role = ?3 WHERE id = ?4",
-        params![
-            user.username,
-            user.password,
-            user.role,
-            user.id,
-        ],
-    )
-    .map_err(|e| {
-        error!("Failed to update user with ID {:?}: {}", user.id, e);
-        e.to_string()
-    })?;
+            if let Some(id) = user.id {
+                if stored_role(&tx, id)? == Some(Role::Admin) && user.role != Role::Admin && other_admin_count(&tx, id)? == 0 {
+                    warn!("Refused to demote user {}, the only admin.", id);
+                    return Err("This is the only admin. Make another user an admin first.".to_string());
+                }
+            }
 
-    if updated_rows == 0 {
-        error!("User with ID {:?} not found for update.", user.id);
-        return Err(format!("User with ID {:?} not found.", user.id));
-    }
+            let updated_rows = tx.execute(
+                "UPDATE Users SET username = ?1, password = ?2, role = ?3, section_id = ?4, roll_number = ?5, display_name = ?6
+                 WHERE id = ?7 AND deleted_at IS NULL",
+                params![
+                    user.username,
+                    user.password,
+                    user.role,
+                    user.section_id,
+                    user.roll_number,
+                    user.display_name,
+                    user.id,
+                ],
+            )
+            .map_err(|e| {
+                error!("Failed to update user with ID {:?}: {}", user.id, e);
+                e.to_string()
+            })?;
 
-    info!("Successfully updated user with ID: {:?}", user.id);
-    Ok(())
+            if updated_rows == 0 {
+                error!("User with ID {:?} not found for update.", user.id);
+                return Err(format!("User with ID {:?} not found.", user.id));
+            }
+            tx.commit().map_err(|e| {
+                error!("Failed to commit update of user {:?}: {}", user.id, e);
+                e.to_string()
+            })?;
+
+            info!("Successfully updated user with ID: {:?}", user.id);
+            Ok(())
+        })
+        .await
 }
 
-// Command to delete a user
+// Command to delete a user. The user can no longer sign in and is hidden from listings,
+// but keeps their progress until restored or purged.
 #[tauri::command]
-pub fn delete_user(id: i64) -> Result<(), String> {
+pub async fn delete_user(database: State<'_, Database>, id: i64) -> Result<(), String> {
+    let admin = require_permission(Permission::ManageUsers)?;
     info!("Deleting user with ID: {}", id);
-    let db_path = get_db_path()?;
-    let db = Connection::open(&db_path).map_err(|e| {
-        error!("Failed to open database for deleting user: {}", e);
-        e.to_string()
-    })?;
+    if admin.id == id {
+        warn!("Refused to let {} delete their own account.", admin.username);
+        return Err("You cannot delete your own account.".to_string());
+    }
+    database
+        .run(move |db| {
+            let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
 
-    let deleted_rows = db.execute(
-        "DELETE FROM Users WHERE id = ?1",
-        params![id],
-    )
-    .map_err(|e| {
-        error!("Failed to delete user with ID {}: {}", id, e);
-        e.to_string()
-    })?;
+            if stored_role(&tx, id)? == Some(Role::Admin) && other_admin_count(&tx, id)? == 0 {
+                warn!("Refused to delete user {}, the only admin.", id);
+                return Err("This is the only admin and cannot be deleted.".to_string());
+            }
 
-    if deleted_rows == 0 {
-        error!("User with ID {} not found for deletion.", id);
-        return Err(format!("User with ID {} not found.", id));
-    }
+            let deleted_rows = tx.execute(
+                "UPDATE Users SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted_at IS NULL",
+                params![id],
+            )
+            .map_err(|e| {
+                error!("Failed to delete user with ID {}: {}", id, e);
+                e.to_string()
+            })?;
 
-    info!("Successfully deleted user with ID: {}", id);
-    Ok(())
-} 
\ No newline at end of file
+            if deleted_rows == 0 {
+                error!("User with ID {} not found for deletion.", id);
+                return Err(format!("User with ID {} not found.", id));
+            }
+            tx.commit().map_err(|e| {
+                error!("Failed to commit deletion of user {}: {}", id, e);
+                e.to_string()
+            })?;
+
+            info!("Successfully deleted user with ID: {}", id);
+            Ok(())
+        })
+        .await
+}
+
+// Command to restore a deleted user
+#[tauri::command]
+pub async fn restore_user(database: State<'_, Database>, id: i64) -> Result<(), String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Restoring user with ID: {}", id);
+    database
+        .run(move |db| {
+            // Checked and restored in one write transaction, so the roll number cannot be
+            // given to another student in between
+            let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            // The roll number may have been given to another student meanwhile
+            let roll_taken: bool = tx
+                .query_row(
+                    "SELECT EXISTS(
+                        SELECT 1 FROM Users u JOIN Users other
+                          ON other.section_id = u.section_id AND other.roll_number = u.roll_number
+                        WHERE u.id = ?1 AND other.id != u.id AND other.deleted_at IS NULL)",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if roll_taken {
+                warn!("Cannot restore user {}: their roll number is in use.", id);
+                return Err("Another student in this section now has the same roll number. Change that student's roll number first.".to_string());
+            }
+
+            let restored_rows = tx
+                .execute("UPDATE Users SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL", params![id])
+                .map_err(|e| {
+                    error!("Failed to restore user with ID {}: {}", id, e);
+                    e.to_string()
+                })?;
+            if restored_rows == 0 {
+                return Err(format!("Deleted user with ID {} not found.", id));
+            }
+            tx.commit().map_err(|e| {
+                error!("Failed to restore user with ID {}: {}", id, e);
+                e.to_string()
+            })?;
+
+            info!("Successfully restored user with ID: {}", id);
+            Ok(())
+        })
+        .await
+}
+
+// Command to permanently remove a deleted user. SCORM data and teacher assignments go
+// with them; progress and xAPI statements are deleted or anonymized according to `mode`.
+#[tauri::command]
+pub async fn purge_user(database: State<'_, Database>, id: i64, mode: PurgeMode) -> Result<(), String> {
+    require_permission(Permission::ManageUsers)?;
+    info!("Purging user with ID {} ({:?})", id, mode);
+    database
+        .run(move |db| {
+            let tx = db.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+
+            let deleted_at: Option<Option<String>> = tx
+                .query_row("SELECT deleted_at FROM Users WHERE id = ?1", params![id], |row| row.get(0))
+                .optional()
+                .map_err(|e| e.to_string())?;
+            match deleted_at {
+                None => return Err(format!("User with ID {} not found.", id)),
+                Some(None) => return Err("Only deleted users can be purged. Delete the user first.".to_string()),
+                Some(Some(_)) => {}
+            }
+
+            let history = match mode {
+                PurgeMode::Delete => [
+                    "DELETE FROM ContentProgress WHERE user_id = ?1".to_string(),
+                    "DELETE FROM XapiStatements WHERE user_id = ?1".to_string(),
+                ],
+                PurgeMode::Anonymize => [
+                    "UPDATE ContentProgress SET user_id = NULL WHERE user_id = ?1".to_string(),
+                    format!(
+                        "UPDATE XapiStatements SET user_id = NULL, statement = json_set(statement, '$.actor', json('{}'))
+                         WHERE user_id = ?1",
+                        anonymous_agent()
+                    ),
+                ],
+            };
+            // SCORM data and teacher assignments cascade
+            for sql in history.iter().map(String::as_str).chain(["DELETE FROM Users WHERE id = ?1"]) {
+                tx.execute(sql, params![id]).map_err(|e| {
+                    error!("Failed to purge user {}: {}\nSQL: {}", id, e, sql);
+                    e.to_string()
+                })?;
+            }
+            tx.commit().map_err(|e| {
+                error!("Failed to commit purge of user {}: {}", id, e);
+                e.to_string()
+            })?;
+
+            info!("Successfully purged user with ID: {}", id);
+            Ok(())
+        })
+        .await
+}
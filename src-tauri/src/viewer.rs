@@ -0,0 +1,495 @@
+// src-tauri/src/viewer.rs
+
+use crate::content::resolve_in_content_roots;
+use crate::db::Database;
+use crate::encryption::open_content;
+use crate::indexer::{find_content_item_id, PACKAGE_ENTRY_PAGE, SCORM_MANIFEST};
+use crate::progress::{get_content_progress, record_content_closed, record_content_opened, record_video_progress};
+use crate::roles::{require_own_progress, require_visible};
+use crate::scorm::{inject_bridge, read_scorm_package, ScormContext, ScormPackage, ScormVersion};
+use log::{error, info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{
+    AppHandle, Emitter, Manager, Runtime, State, UriSchemeContext, UriSchemeResponder, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+// Content is streamed to viewer windows as stream://localhost/<path> (http://stream.localhost/<path>
+// on Windows), decrypting on the fly, so nothing decrypted is written to disk
+pub const STREAM_SCHEME: &str = "stream";
+pub const VIEWER_CLOSED_EVENT: &str = "viewer-closed";
+
+// Viewer windows are labelled "viewer-<id>"; the frontend renders the viewer for these
+const VIEWER_LABEL_PREFIX: &str = "viewer-";
+// Largest body returned for one request; media elements fetch the rest with Range requests
+const MAX_STREAM_CHUNK: u64 = 4 * 1024 * 1024;
+// Files up to this size are returned whole when no range is requested
+const MAX_WHOLE_RESPONSE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewerKind {
+    Pdf,
+    Video,
+    Audio,
+    Image,
+    Html,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ViewerSessionInfo {
+    pub kind: ViewerKind,
+    pub title: String,
+    pub url: String,
+    pub content_item_id: Option<i64>,
+    pub resume_position_seconds: Option<f64>,
+    pub scorm_version: Option<ScormVersion>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ViewerClosedPayload {
+    pub path: String,
+    pub content_item_id: Option<i64>,
+}
+
+struct ViewerSession {
+    path: PathBuf,
+    info: ViewerSessionInfo,
+    user_id: Option<i64>,
+    opened_at: Instant,
+    scorm: Option<ScormContext>,
+}
+
+static SESSIONS: Mutex<Option<HashMap<String, ViewerSession>>> = Mutex::new(None);
+
+// MIME types the stream protocol serves; anything else is refused
+fn mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    let mime = match extension.as_str() {
+        "pdf" => "application/pdf",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript",
+        "css" => "text/css",
+        "json" => "application/json",
+        "xml" | "xsd" => "application/xml",
+        "txt" => "text/plain; charset=utf-8",
+        "vtt" => "text/vtt",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+fn viewer_kind(path: &Path) -> Option<ViewerKind> {
+    let mime = mime_type(path)?;
+    if mime == "application/pdf" {
+        Some(ViewerKind::Pdf)
+    } else if mime.starts_with("text/html") {
+        Some(ViewerKind::Html)
+    } else if mime.starts_with("video/") {
+        Some(ViewerKind::Video)
+    } else if mime.starts_with("audio/") {
+        Some(ViewerKind::Audio)
+    } else if mime.starts_with("image/") {
+        Some(ViewerKind::Image)
+    } else {
+        None
+    }
+}
+
+fn percent_encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// URL of a content file on the stream protocol. Path separators stay unencoded so pages
+// inside HTML5 packages can load their scripts and images by relative URL.
+fn stream_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = percent_encode_path(path.trim_start_matches('/'));
+    if cfg!(windows) {
+        format!("http://{}.localhost/{}", STREAM_SCHEME, path)
+    } else {
+        format!("{}://localhost/{}", STREAM_SCHEME, path)
+    }
+}
+
+fn stream_request_path(request: &Request<Vec<u8>>) -> String {
+    let path = percent_decode(request.uri().path());
+    if cfg!(windows) {
+        path.trim_start_matches('/').to_string()
+    } else {
+        path
+    }
+}
+
+// Inclusive byte range requested by a "bytes=start-end" Range header
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split(',').next()?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.checked_sub(suffix.min(len))?, len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len.checked_sub(1)?)),
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn response(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body)
+        .unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+fn read_failed(path: &Path, e: impl std::fmt::Display) -> Response<Vec<u8>> {
+    error!("Failed to stream {}: {}", path.display(), e);
+    response(StatusCode::INTERNAL_SERVER_ERROR, "text/plain", b"read failed".to_vec())
+}
+
+// Pages of a SCORM package get the runtime API added; they are always sent whole
+fn scorm_page_response(database: &Database, path: &Path, content_type: &str, scorm: &ScormContext) -> Response<Vec<u8>> {
+    let mut html = Vec::new();
+    if let Err(e) = open_content(path).and_then(|mut reader| reader.read_to_end(&mut html).map_err(|e| e.to_string())) {
+        return read_failed(path, e);
+    }
+    let page = match database.connection() {
+        Ok(db) => inject_bridge(&db, html, scorm),
+        Err(e) => return read_failed(path, e),
+    };
+    response(StatusCode::OK, content_type, page)
+}
+
+// A connection is only taken for the lookups, not while the file is read
+fn stream_response(database: &Database, request: &Request<Vec<u8>>, scorm: Option<&ScormContext>) -> Response<Vec<u8>> {
+    let requested = stream_request_path(request);
+    let resolved = database.connection().and_then(|db| {
+        let path = resolve_in_content_roots(&db, &requested)?;
+        require_visible(&db, &path)?;
+        Ok(path)
+    });
+    let path = match resolved {
+        Ok(path) if path.is_file() => path,
+        Ok(_) => return response(StatusCode::NOT_FOUND, "text/plain", b"not found".to_vec()),
+        Err(e) => {
+            warn!("Refused stream request for {}: {}", requested, e);
+            return response(StatusCode::FORBIDDEN, "text/plain", b"forbidden".to_vec());
+        }
+    };
+    let Some(content_type) = mime_type(&path) else {
+        return response(StatusCode::FORBIDDEN, "text/plain", b"unsupported file type".to_vec());
+    };
+    if let Some(scorm) = scorm.filter(|s| content_type.starts_with("text/html") && path.starts_with(&s.root)) {
+        return scorm_page_response(database, &path, content_type, scorm);
+    }
+    let mut reader = match open_content(&path) {
+        Ok(reader) => reader,
+        Err(e) => return read_failed(&path, e),
+    };
+    let total = match reader.seek(SeekFrom::End(0)) {
+        Ok(total) => total,
+        Err(e) => return read_failed(&path, e),
+    };
+
+    let range = request.headers().get(header::RANGE).and_then(|v| v.to_str().ok());
+    let (status, start, end) = match range.map(|range| parse_range(range, total)) {
+        Some(Some((start, end))) => (StatusCode::PARTIAL_CONTENT, start, end.min(start + MAX_STREAM_CHUNK - 1)),
+        Some(None) => {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", total))
+                .body(Vec::new())
+                .unwrap_or_else(|_| Response::new(Vec::new()));
+        }
+        None if total == 0 => return response(StatusCode::OK, content_type, Vec::new()),
+        None if total <= MAX_WHOLE_RESPONSE => (StatusCode::OK, 0, total - 1),
+        // Large files are always sent in ranges
+        None => (StatusCode::PARTIAL_CONTENT, 0, MAX_STREAM_CHUNK - 1),
+    };
+
+    let mut body = Vec::new();
+    let read = reader
+        .seek(SeekFrom::Start(start))
+        .and_then(|_| reader.take(end - start + 1).read_to_end(&mut body));
+    if let Err(e) = read {
+        return read_failed(&path, e);
+    }
+    let mut builder = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+    if status == StatusCode::PARTIAL_CONTENT {
+        let last = start + (body.len() as u64).max(1) - 1;
+        builder = builder.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, last, total));
+    }
+    builder.body(body).unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+// Serves content files inside the registered content roots. Reads happen off the webview
+// thread so seeking in a large encrypted video does not stall the UI.
+pub fn handle_stream_request<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let scorm = scorm_context(ctx.webview_label());
+    let database = ctx.app_handle().state::<Database>().inner().clone();
+    std::thread::spawn(move || responder.respond(stream_response(&database, &request, scorm.as_ref())));
+}
+
+// What to show for a requested path: the file itself, the launch page of a SCORM package
+// folder, or the entry page of any other HTML5 package folder
+fn viewer_target(db: &Connection, path: &str) -> Result<(PathBuf, ViewerKind, Option<ScormPackage>), String> {
+    let source = resolve_in_content_roots(db, path)?;
+    require_visible(db, &source)?;
+    let (target, package) = if source.join(SCORM_MANIFEST).is_file() {
+        let package = read_scorm_package(&source)?;
+        (package.launch.clone(), Some(package))
+    } else if source.is_dir() {
+        (source.join(PACKAGE_ENTRY_PAGE), None)
+    } else {
+        (source, None)
+    };
+    if !target.is_file() {
+        return Err(format!("{} has nothing to show.", path));
+    }
+    let kind = viewer_kind(&target).ok_or_else(|| format!("{} cannot be opened in the viewer.", target.display()))?;
+    Ok((target, kind, package))
+}
+
+fn viewer_label(path: &Path) -> String {
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    let id: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", VIEWER_LABEL_PREFIX, id)
+}
+
+fn close_session(app: &AppHandle, label: &str) {
+    let session = SESSIONS.lock().unwrap_or_else(|e| e.into_inner()).as_mut().and_then(|s| s.remove(label));
+    let Some(session) = session else {
+        return;
+    };
+    info!("Viewer closed for {}", session.path.display());
+    if let (Some(user_id), Some(item_id)) = (session.user_id, session.info.content_item_id) {
+        let seconds = session.opened_at.elapsed().as_secs_f64();
+        let database = app.state::<Database>().inner().clone();
+        // Window events arrive on the event loop, which must not wait on the database
+        tauri::async_runtime::spawn_blocking(move || {
+            let recorded = database.connection().and_then(|mut db| record_content_closed(&mut db, user_id, item_id, seconds));
+            if let Err(e) = recorded {
+                warn!("Failed to record viewer close for item {}: {}", item_id, e);
+            }
+        });
+    }
+    let payload = ViewerClosedPayload {
+        path: session.path.to_string_lossy().into_owned(),
+        content_item_id: session.info.content_item_id,
+    };
+    if let Err(e) = app.emit(VIEWER_CLOSED_EVENT, payload) {
+        warn!("Failed to emit {} event: {}", VIEWER_CLOSED_EVENT, e);
+    }
+}
+
+// Origin the app's pages are served from. Viewer windows load the same app as the window
+// that opens them. Built by hand because custom schemes such as tauri:// have no origin
+// in URL terms, while the webview gives their pages scheme://host.
+fn app_origin(window: &WebviewWindow) -> Result<String, String> {
+    let url = window.url().map_err(|e| e.to_string())?;
+    let host = url.host_str().ok_or_else(|| format!("{} has no host", url))?;
+    Ok(match url.port() {
+        Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+        None => format!("{}://{}", url.scheme(), host),
+    })
+}
+
+// Opens `path` in its own viewer window, or focuses the window already showing it.
+// `user_id` is the signed-in user whose progress the session is recorded against; it is
+// ignored for anyone else, and for roles whose progress is not tracked.
+#[tauri::command]
+pub async fn open_content_viewer(
+    app: AppHandle,
+    caller: WebviewWindow,
+    database: State<'_, Database>,
+    path: String,
+    user_id: Option<i64>,
+) -> Result<String, String> {
+    let user_id = user_id.filter(|id| require_own_progress(*id).is_ok());
+    let requested = path.clone();
+    let (target, kind, package, source, content_item_id) = database
+        .run(move |db| {
+            let (target, kind, package) = viewer_target(db, &requested)?;
+            let source = resolve_in_content_roots(db, &requested)?;
+            // Packages are recorded against the folder the user opened, not their entry page
+            let item_path = if source.is_dir() { &source } else { &target };
+            let content_item_id = find_content_item_id(db, item_path)?;
+            Ok((target, kind, package, source, content_item_id))
+        })
+        .await?;
+    let label = viewer_label(&target);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(label);
+    }
+
+    let item_path = if source.is_dir() { source.clone() } else { target.clone() };
+    let resume_position_seconds = match (user_id, content_item_id) {
+        (Some(user_id), Some(item_id)) if matches!(kind, ViewerKind::Video | ViewerKind::Audio) => {
+            get_content_progress(database.clone(), user_id, item_id).await?.and_then(|p| p.position_seconds)
+        }
+        _ => None,
+    };
+    let title = package
+        .as_ref()
+        .and_then(|p| p.title.clone())
+        .or_else(|| item_path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| path.clone());
+    let url = match package.as_ref().and_then(|p| p.launch_query.as_deref()) {
+        Some(query) => format!("{}?{}", stream_url(&target), query),
+        None => stream_url(&target),
+    };
+    let scorm = match &package {
+        Some(package) => Some(ScormContext {
+            user_id,
+            content_item_id,
+            root: source.clone(),
+            version: package.version,
+            parent_origin: app_origin(&caller)?,
+        }),
+        None => None,
+    };
+    let info = ViewerSessionInfo {
+        kind,
+        title: title.clone(),
+        url,
+        content_item_id,
+        resume_position_seconds,
+        scorm_version: package.map(|p| p.version),
+    };
+    SESSIONS.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert_with(HashMap::new).insert(
+        label.clone(),
+        ViewerSession { path: target.clone(), info, user_id, opened_at: Instant::now(), scorm },
+    );
+
+    // The window loads the app itself, which shows the viewer for viewer-* labels
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App("index.html".into()))
+        .title(&title)
+        .inner_size(1100.0, 760.0)
+        .min_inner_size(480.0, 360.0)
+        .build()
+        .map_err(|e| {
+            SESSIONS.lock().unwrap_or_else(|e| e.into_inner()).as_mut().map(|s| s.remove(&label));
+            error!("Failed to open viewer window for {}: {}", target.display(), e);
+            e.to_string()
+        })?;
+    let closing_app = app.clone();
+    let closing_label = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            close_session(&closing_app, &closing_label);
+        }
+    });
+
+    info!("Opened viewer for {}", target.display());
+    if let (Some(user_id), Some(item_id)) = (user_id, content_item_id) {
+        database.run(move |db| record_content_opened(db, user_id, item_id)).await?;
+    }
+    Ok(label)
+}
+
+// Details of the session shown by the calling viewer window
+#[tauri::command]
+pub fn get_viewer_session(window: WebviewWindow) -> Result<ViewerSessionInfo, String> {
+    SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|s| s.get(window.label()))
+        .map(|session| session.info.clone())
+        .ok_or_else(|| format!("No viewer session for window {}.", window.label()))
+}
+
+// Playback position reported by a video or audio viewer
+#[tauri::command]
+pub async fn record_viewer_position(
+    database: State<'_, Database>,
+    window: WebviewWindow,
+    position_seconds: f64,
+    duration_seconds: f64,
+) -> Result<(), String> {
+    let (user_id, item_id) = {
+        let sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .as_ref()
+            .and_then(|s| s.get(window.label()))
+            .ok_or_else(|| format!("No viewer session for window {}.", window.label()))?;
+        (session.user_id, session.info.content_item_id)
+    };
+    // Sessions without a user or an indexed item are not tracked
+    if let (Some(user_id), Some(item_id)) = (user_id, item_id) {
+        record_video_progress(database, user_id, item_id, position_seconds, duration_seconds).await?;
+    }
+    Ok(())
+}
+
+// SCORM state of the package shown in viewer window `label`, if it shows one
+pub fn scorm_context(label: &str) -> Option<ScormContext> {
+    SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|s| s.get(label))
+        .and_then(|session| session.scorm.clone())
+}
@@ -0,0 +1,365 @@
+// src-tauri/src/watcher.rs
+
+use crate::content::get_active_content_root;
+use crate::db::Database;
+use crate::indexer::{index_content_path, is_package_dir, scan_tree, sync_items, IndexSummary};
+use crate::search::{index_search_subtree, rebuild_search_index};
+use log::{error, info, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+// Event emitted to the frontend when new files show up in the active content path
+pub const CONTENT_ADDED_EVENT: &str = "content-added";
+
+// How long to wait for a burst of filesystem events to settle before re-indexing
+const DEBOUNCE: Duration = Duration::from_secs(2);
+// How often to look for a change of active content path when nothing else wakes us up
+const ACTIVE_PATH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// Full rescan interval for filesystems where change notifications are unreliable
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+static ACTIVE_PATH_CHANGED: AtomicBool = AtomicBool::new(false);
+// Content roots whose files are being moved around, see pause_watcher
+static PAUSED_ROOTS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ContentAddedPayload {
+    pub folder_id: i64,
+    pub summary: IndexSummary,
+}
+
+// Asks the watcher to pick up a new active content path right away
+pub fn notify_active_path_changed() {
+    ACTIVE_PATH_CHANGED.store(true, Ordering::SeqCst);
+}
+
+// Keeps the watcher from re-indexing `root` until the returned guard is dropped. Used while
+// an update moves files aside and back, so a half-moved tree is never indexed. Changes seen
+// in the meantime are indexed once the pause ends.
+pub fn pause_watcher(root: &Path) -> WatcherPause {
+    PAUSED_ROOTS.lock().unwrap_or_else(|e| e.into_inner()).push(root.to_path_buf());
+    WatcherPause { root: root.to_path_buf() }
+}
+
+pub struct WatcherPause {
+    root: PathBuf,
+}
+
+impl Drop for WatcherPause {
+    fn drop(&mut self) {
+        let mut paused = PAUSED_ROOTS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(index) = paused.iter().position(|root| *root == self.root) {
+            paused.remove(index);
+        }
+    }
+}
+
+fn is_paused(root: &Path) -> bool {
+    PAUSED_ROOTS.lock().unwrap_or_else(|e| e.into_inner()).iter().any(|paused| paused == root)
+}
+
+// How the current content root is being kept up to date
+enum WatchMode {
+    // The watcher stops delivering events once dropped, so it lives alongside its receiver
+    Events {
+        _watcher: RecommendedWatcher,
+        events: Receiver<notify::Result<notify::Event>>,
+    },
+    Polling { last_scan: Instant },
+}
+
+struct WatchedRoot {
+    folder_id: i64,
+    root: PathBuf,
+    mode: WatchMode,
+    // Changed paths held back while the root is paused
+    pending: Vec<PathBuf>,
+}
+
+// Starts the background thread that keeps ContentItems in sync with the active content path
+pub fn start_content_watcher(app: AppHandle) {
+    std::thread::spawn(move || {
+        info!("Content watcher started.");
+        let mut watched: Option<WatchedRoot> = None;
+        let mut last_active_check: Option<Instant> = None;
+
+        loop {
+            let recheck = ACTIVE_PATH_CHANGED.swap(false, Ordering::SeqCst)
+                || last_active_check.is_none_or(|t| t.elapsed() >= ACTIVE_PATH_CHECK_INTERVAL);
+            if recheck {
+                last_active_check = Some(Instant::now());
+                match app.state::<Database>().connection().and_then(|db| get_active_content_root(&db)) {
+                    Ok(active) => {
+                        let current = watched.as_ref().map(|w| (w.folder_id, w.root.clone()));
+                        let wanted = active.map(|(id, path)| (id, PathBuf::from(path)));
+                        if current != wanted {
+                            watched = wanted.and_then(|(folder_id, root)| watch_root(&app, folder_id, root));
+                        }
+                    }
+                    Err(e) => error!("Content watcher failed to read the active content path: {}", e),
+                }
+            }
+
+            let Some(current) = watched.as_mut() else {
+                std::thread::sleep(Duration::from_secs(1));
+                continue;
+            };
+
+            match &mut current.mode {
+                WatchMode::Events { events, .. } => {
+                    current.pending.extend(collect_changed_paths(events, Duration::from_secs(1)));
+                    if !current.pending.is_empty() && !is_paused(&current.root) {
+                        let changed = std::mem::take(&mut current.pending);
+                        reindex_subtrees(&app, current.folder_id, &current.root, changed);
+                    }
+                }
+                WatchMode::Polling { last_scan } => {
+                    if last_scan.elapsed() >= POLL_INTERVAL && !is_paused(&current.root) {
+                        *last_scan = Instant::now();
+                        full_index(&app, current.folder_id, &current.root);
+                    } else {
+                        std::thread::sleep(Duration::from_secs(1));
+                    }
+                }
+            }
+        }
+    });
+}
+
+// Indexes a newly activated root and sets up change notifications, or polling as a fallback
+fn watch_root(app: &AppHandle, folder_id: i64, root: PathBuf) -> Option<WatchedRoot> {
+    info!("Content watcher now tracking content path {} ({})", folder_id, root.display());
+    if !root.is_dir() {
+        warn!("Active content path is not available: {}", root.display());
+        return None;
+    }
+    full_index(app, folder_id, &root);
+
+    let mode = if uses_unreliable_notifications(&root) {
+        info!("Filesystem of {} does not support reliable change events; polling instead.", root.display());
+        WatchMode::Polling { last_scan: Instant::now() }
+    } else {
+        let (tx, rx) = channel();
+        match notify::recommended_watcher(tx).and_then(|mut watcher| {
+            watcher.watch(&root, RecursiveMode::Recursive)?;
+            Ok(watcher)
+        }) {
+            Ok(watcher) => WatchMode::Events { _watcher: watcher, events: rx },
+            Err(e) => {
+                warn!("Failed to watch {}: {}. Falling back to polling.", root.display(), e);
+                WatchMode::Polling { last_scan: Instant::now() }
+            }
+        }
+    };
+
+    Some(WatchedRoot { folder_id, root, mode, pending: Vec::new() })
+}
+
+// Waits up to `wait` for an event, then keeps draining until events stop for DEBOUNCE
+fn collect_changed_paths(
+    events: &Receiver<notify::Result<notify::Event>>,
+    wait: Duration,
+) -> Vec<PathBuf> {
+    let mut changed = Vec::new();
+    let mut timeout = wait;
+    loop {
+        match events.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                if !event.kind.is_access() {
+                    changed.extend(event.paths);
+                }
+                timeout = DEBOUNCE;
+            }
+            Ok(Err(e)) => {
+                warn!("Content watcher error: {}", e);
+                timeout = DEBOUNCE;
+            }
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    changed
+}
+
+// Reduces changed paths to the smallest set of directories that need rescanning
+fn changed_subtrees(root: &Path, paths: Vec<PathBuf>) -> Vec<String> {
+    let mut dirs = BTreeSet::new();
+    for path in paths {
+        // A removed path may have been a file or a folder, so rescan its parent
+        let dir = if path.is_dir() { path.as_path() } else { path.parent().unwrap_or(root) };
+        // A change inside a package folder reindexes the folder holding it, where the package's item lives
+        let dir = dir
+            .ancestors()
+            .take_while(|ancestor| *ancestor != root && ancestor.starts_with(root))
+            .filter(|ancestor| is_package_dir(ancestor))
+            .last()
+            .and_then(|package| package.parent())
+            .unwrap_or(dir);
+        if let Ok(relative) = dir.strip_prefix(root) {
+            // Hidden folders, such as update staging and rollback copies, are never indexed
+            if relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) {
+                continue;
+            }
+            dirs.insert(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+
+    let mut subtrees: Vec<String> = Vec::new();
+    for dir in dirs {
+        // BTreeSet order puts ancestors before their descendants
+        let covered = subtrees
+            .iter()
+            .any(|parent| parent.is_empty() || dir == *parent || dir.starts_with(&format!("{}/", parent)));
+        if !covered {
+            subtrees.push(dir);
+        }
+    }
+    subtrees
+}
+
+// Each subtree is scanned before a connection is taken, so the pool is only held for the writes
+fn reindex_subtrees(app: &AppHandle, folder_id: i64, root: &Path, paths: Vec<PathBuf>) {
+    let database = app.state::<Database>();
+    let mut total = IndexSummary::default();
+    for prefix in changed_subtrees(root, paths) {
+        let dir = if prefix.is_empty() { root.to_path_buf() } else { root.join(&prefix) };
+        let files = scan_tree(root, &dir);
+        let synced = database
+            .connection()
+            .and_then(|mut db| sync_items(&mut db, folder_id, &prefix, &files));
+        match synced {
+            Ok(summary) => {
+                info!(
+                    "Re-indexed '{}': {} added, {} updated, {} removed",
+                    prefix, summary.added, summary.updated, summary.removed
+                );
+                total.added += summary.added;
+                total.updated += summary.updated;
+                total.removed += summary.removed;
+                total.unchanged += summary.unchanged;
+            }
+            Err(e) => error!("Failed to re-index '{}' in content path {}: {}", prefix, folder_id, e),
+        }
+        if let Err(e) = index_search_subtree(&database, folder_id, root, &prefix) {
+            error!("Failed to update search index for '{}' in content path {}: {}", prefix, folder_id, e);
+        }
+    }
+    emit_if_added(app, folder_id, total);
+}
+
+fn full_index(app: &AppHandle, folder_id: i64, root: &Path) {
+    let database = app.state::<Database>();
+    match index_content_path(&database, folder_id, root) {
+        Ok(summary) => {
+            // Quiz text only needs re-reading when something on disk changed
+            if summary.added + summary.updated + summary.removed > 0 || search_index_is_empty(&database, folder_id) {
+                if let Err(e) = rebuild_search_index(&database, folder_id, root) {
+                    error!("Failed to rebuild search index for content path {}: {}", folder_id, e);
+                }
+            }
+            emit_if_added(app, folder_id, summary);
+        }
+        Err(e) => error!("Failed to index content path {}: {}", folder_id, e),
+    }
+}
+
+fn search_index_is_empty(database: &Database, folder_id: i64) -> bool {
+    let count = database.connection().and_then(|db| {
+        db.query_row("SELECT COUNT(*) FROM ContentSearch WHERE folder_id = ?1", [folder_id], |row| row.get::<_, i64>(0))
+            .map_err(|e| e.to_string())
+    });
+    match count {
+        Ok(count) => count == 0,
+        Err(e) => {
+            warn!("Failed to check search index for content path {}: {}", folder_id, e);
+            true
+        }
+    }
+}
+
+fn emit_if_added(app: &AppHandle, folder_id: i64, summary: IndexSummary) {
+    if summary.added == 0 {
+        return;
+    }
+    info!("New content detected in content path {}: {} files", folder_id, summary.added);
+    if let Err(e) = app.emit(CONTENT_ADDED_EVENT, ContentAddedPayload { folder_id, summary }) {
+        error!("Failed to emit {} event: {}", CONTENT_ADDED_EVENT, e);
+    }
+}
+
+// Looks up the filesystem type of the mount holding `root` in /proc/mounts
+#[cfg(target_os = "linux")]
+fn uses_unreliable_notifications(root: &Path) -> bool {
+    // Filesystems where inotify misses changes made elsewhere or is not supported
+    const POLLED_FILESYSTEMS: &[&str] = &[
+        "nfs", "nfs4", "cifs", "smbfs", "smb3", "9p", "vfat", "msdos", "exfat", "fuseblk", "ntfs",
+    ];
+
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mounts = match std::fs::read_to_string("/proc/mounts") {
+        Ok(mounts) => mounts,
+        Err(_) => return false,
+    };
+
+    let fs_type = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            Some((PathBuf::from(mount_point), fs_type.to_string()))
+        })
+        .filter(|(mount_point, _)| root.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.as_os_str().len())
+        .map(|(_, fs_type)| fs_type);
+
+    match fs_type {
+        Some(fs_type) => POLLED_FILESYSTEMS.contains(&fs_type.as_str()) || fs_type.starts_with("fuse."),
+        None => false,
+    }
+}
+
+// Network shares and mapped network drives miss changes made from other machines, and
+// removable drives (FAT and exFAT USB sticks) do not report changes dependably
+#[cfg(windows)]
+fn uses_unreliable_notifications(root: &Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::{Component, Prefix};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDriveTypeW(root_path_name: *const u16) -> u32;
+    }
+    const DRIVE_REMOVABLE: u32 = 2;
+    const DRIVE_REMOTE: u32 = 4;
+
+    let Some(Component::Prefix(prefix)) = root.components().next() else {
+        return false;
+    };
+    let letter = match prefix.kind() {
+        Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => letter,
+        Prefix::UNC(..) | Prefix::VerbatimUNC(..) => return true,
+        _ => return false,
+    };
+    // The drive's root folder, such as "E:\", as a NUL-terminated wide string
+    let volume: Vec<u16> = std::ffi::OsStr::new(&format!("{}:\\", letter as char))
+        .encode_wide()
+        .chain([0])
+        .collect();
+    // SAFETY: `volume` is a valid NUL-terminated UTF-16 string that outlives the call
+    let drive_type = unsafe { GetDriveTypeW(volume.as_ptr()) };
+    matches!(drive_type, DRIVE_REMOVABLE | DRIVE_REMOTE)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn uses_unreliable_notifications(_root: &Path) -> bool {
+    false
+}
@@ -0,0 +1,342 @@
+// src-tauri/src/xapi.rs
+
+use crate::db::Database;
+use crate::indexer::ContentType;
+use crate::roles::{require_permission, Permission};
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{error, info};
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+// Local Learning Record Store. Statements are kept in XapiStatements and exported as JSON
+// batches in the body format of POST /statements, for forwarding to an external LRS.
+pub const XAPI_VERSION: &str = "1.0.3";
+// Learners are identified by account (their user id) on this home page
+const ACCOUNT_HOME_PAGE: &str = "https://osiris.local";
+// Content items are activities identified by their path within the content root
+const ACTIVITY_IRI_PREFIX: &str = "https://osiris.local/content/";
+// Largest page returned by one statement query, also used when the query asks for 0 (no limit)
+const MAX_QUERY_LIMIT: usize = 500;
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Verb {
+    Launched,
+    Completed,
+}
+
+impl Verb {
+    pub fn iri(&self) -> &'static str {
+        match self {
+            Verb::Launched => "http://adlnet.gov/expapi/verbs/launched",
+            Verb::Completed => "http://adlnet.gov/expapi/verbs/completed",
+        }
+    }
+
+    fn display(&self) -> &'static str {
+        match self {
+            Verb::Launched => "launched",
+            Verb::Completed => "completed",
+        }
+    }
+}
+
+// Filters of the standard GET /statements resource. `more` continues a previous query.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct StatementQuery {
+    pub statement_id: Option<String>,
+    pub agent: Option<Value>,
+    pub verb: Option<String>,
+    pub activity: Option<String>,
+    pub registration: Option<String>,
+    pub since: Option<String>, // ISO 8601, compared with `stored`
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub ascending: bool,
+    pub more: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatementResult {
+    pub statements: Vec<Value>,
+    pub more: Option<String>, // Pass back as StatementQuery::more for the next page
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct XapiExportSummary {
+    pub files: Vec<String>,
+    pub statement_count: usize,
+}
+
+fn now_iso8601() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// Normalizes an ISO 8601 timestamp to the stored format, so stored times compare as text
+fn normalize_timestamp(value: &str) -> Result<String, String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|t| t.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Millis, true))
+        .map_err(|e| format!("Invalid timestamp '{}': {}", value, e))
+}
+
+// Random (version 4) UUID for statement ids
+fn new_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+fn percent_encode_segment(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn activity_type(content_type: Option<ContentType>) -> &'static str {
+    match content_type {
+        Some(ContentType::Video | ContentType::Animation) => "http://adlnet.gov/expapi/activities/media",
+        Some(ContentType::Quiz | ContentType::Assessment) => "http://adlnet.gov/expapi/activities/assessment",
+        Some(ContentType::Interactive) => "http://adlnet.gov/expapi/activities/module",
+        _ => "http://adlnet.gov/expapi/activities/lesson",
+    }
+}
+
+fn agent(user_id: i64, username: &str) -> Value {
+    json!({
+        "objectType": "Agent",
+        "name": username,
+        "account": { "homePage": ACCOUNT_HOME_PAGE, "name": user_id.to_string() }
+    })
+}
+
+// Actor of statements whose user was purged with their history anonymized
+pub fn anonymous_agent() -> Value {
+    json!({
+        "objectType": "Agent",
+        "account": { "homePage": ACCOUNT_HOME_PAGE, "name": "anonymous" }
+    })
+}
+
+// User id of an agent given in a query; only accounts on our home page can match
+fn agent_user_id(agent: &Value) -> Option<i64> {
+    let account = agent.get("account")?;
+    if account.get("homePage")?.as_str()? != ACCOUNT_HOME_PAGE {
+        return None;
+    }
+    account.get("name")?.as_str()?.parse().ok()
+}
+
+// Records that `user_id` did `verb` to a content item. `result` is an xAPI result object.
+pub fn record_statement(db: &Connection, user_id: i64, content_item_id: i64, verb: Verb, result: Option<Value>) -> Result<String, String> {
+    let username: String = db
+        .query_row("SELECT username FROM Users WHERE id = ?1", params![user_id], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("User with id {} not found.", user_id))?;
+    let (title, relative_path, content_type): (String, Option<String>, Option<String>) = db
+        .query_row(
+            "SELECT title, relative_path, content_type FROM ContentItems WHERE id = ?1",
+            params![content_item_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Content item with id {} not found.", content_item_id))?;
+    let activity_id = match relative_path {
+        Some(relative_path) => format!("{}{}", ACTIVITY_IRI_PREFIX, percent_encode_segment(&relative_path)),
+        None => format!("{}item/{}", ACTIVITY_IRI_PREFIX, content_item_id),
+    };
+
+    let id = new_uuid();
+    let now = now_iso8601();
+    let mut statement = json!({
+        "id": id,
+        "actor": agent(user_id, &username),
+        "verb": { "id": verb.iri(), "display": { "en-US": verb.display() } },
+        "object": {
+            "objectType": "Activity",
+            "id": activity_id,
+            "definition": {
+                "name": { "en-US": title },
+                "type": activity_type(content_type.as_deref().and_then(ContentType::parse))
+            }
+        },
+        "timestamp": now,
+        "stored": now,
+        "version": XAPI_VERSION
+    });
+    if let Some(result) = result {
+        statement["result"] = result;
+    }
+
+    db.execute(
+        "INSERT INTO XapiStatements (id, user_id, verb_id, activity_id, registration, timestamp, stored, statement)
+         VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?5, ?6)",
+        params![id, user_id, verb.iri(), activity_id, now, statement.to_string()],
+    )
+    .map_err(|e| {
+        error!("Failed to store xAPI statement for user {}: {}", user_id, e);
+        e.to_string()
+    })?;
+    info!("Recorded xAPI statement {} ({} item {} by user {})", id, verb.display(), content_item_id, user_id);
+    Ok(id)
+}
+
+// Statements matching the query, newest first unless `ascending`
+#[tauri::command]
+pub async fn get_xapi_statements(database: State<'_, Database>, query: StatementQuery) -> Result<StatementResult, String> {
+    require_permission(Permission::ViewReports)?;
+    info!("Querying xAPI statements: {:?}", query);
+    database
+        .run(move |db| {
+            let mut conditions: Vec<&str> = Vec::new();
+            let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+            if let Some(id) = query.statement_id {
+                conditions.push("id = ?");
+                values.push(Box::new(id));
+            }
+            if let Some(agent) = &query.agent {
+                // An agent that is none of our learners matches nothing
+                conditions.push("user_id = ?");
+                values.push(Box::new(agent_user_id(agent).unwrap_or(-1)));
+            }
+            if let Some(verb) = query.verb {
+                conditions.push("verb_id = ?");
+                values.push(Box::new(verb));
+            }
+            if let Some(activity) = query.activity {
+                conditions.push("activity_id = ?");
+                values.push(Box::new(activity));
+            }
+            if let Some(registration) = query.registration {
+                conditions.push("registration = ?");
+                values.push(Box::new(registration));
+            }
+            if let Some(since) = &query.since {
+                conditions.push("stored > ?");
+                values.push(Box::new(normalize_timestamp(since)?));
+            }
+            if let Some(until) = &query.until {
+                conditions.push("stored <= ?");
+                values.push(Box::new(normalize_timestamp(until)?));
+            }
+            let limit = match query.limit {
+                Some(0) | None => MAX_QUERY_LIMIT,
+                Some(limit) => limit.min(MAX_QUERY_LIMIT),
+            };
+            let offset: usize = match &query.more {
+                Some(more) => more.parse().map_err(|_| format!("Invalid continuation '{}'.", more))?,
+                None => 0,
+            };
+
+            let sql = format!(
+                "SELECT statement FROM XapiStatements {} ORDER BY stored {}, rowid {} LIMIT {} OFFSET {}",
+                if conditions.is_empty() { String::new() } else { format!("WHERE {}", conditions.join(" AND ")) },
+                if query.ascending { "ASC" } else { "DESC" },
+                if query.ascending { "ASC" } else { "DESC" },
+                // One extra row tells whether there is a next page
+                limit + 1,
+                offset
+            );
+            let mut stmt = db.prepare(&sql).map_err(|e| e.to_string())?;
+            let params: Vec<&dyn ToSql> = values.iter().map(|v| v.as_ref()).collect();
+            let mut statements = stmt
+                .query_map(params.as_slice(), |row| row.get::<_, String>(0))
+                .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+                .map_err(|e| {
+                    error!("Failed to query xAPI statements: {}", e);
+                    e.to_string()
+                })?
+                .iter()
+                .map(|text| serde_json::from_str(text).map_err(|e| e.to_string()))
+                .collect::<Result<Vec<Value>, String>>()?;
+            let more = (statements.len() > limit).then(|| (offset + limit).to_string());
+            statements.truncate(limit);
+            Ok(StatementResult { statements, more })
+        })
+        .await
+}
+
+// Writes statements to `destination` as JSON arrays of at most `batch_size` statements,
+// oldest first, and marks them exported. Statements exported before are skipped unless
+// `include_exported` is set.
+#[tauri::command]
+pub async fn export_xapi_statements(
+    database: State<'_, Database>,
+    destination: String,
+    batch_size: Option<usize>,
+    include_exported: bool,
+) -> Result<XapiExportSummary, String> {
+    require_permission(Permission::ViewReports)?;
+    info!("Exporting xAPI statements to {}", destination);
+    database
+        .run(move |db| {
+            let destination = Path::new(&destination);
+            if !destination.is_dir() {
+                return Err(format!("{} is not a folder.", destination.display()));
+            }
+            let batch_size = batch_size.filter(|size| *size > 0).unwrap_or(DEFAULT_BATCH_SIZE);
+            let rows: Vec<(String, String)> = {
+                let sql = format!(
+                    "SELECT id, statement FROM XapiStatements {} ORDER BY stored ASC, rowid ASC",
+                    if include_exported { "" } else { "WHERE exported_at IS NULL" }
+                );
+                let mut stmt = db.prepare(&sql).map_err(|e| e.to_string())?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+                    .map_err(|e| {
+                        error!("Failed to read xAPI statements for export: {}", e);
+                        e.to_string()
+                    })?;
+                rows
+            };
+
+            let stamp = Utc::now().format("%Y%m%dT%H%M%S");
+            let mut files = Vec::new();
+            for (index, batch) in rows.chunks(batch_size).enumerate() {
+                let statements = batch
+                    .iter()
+                    .map(|(_, text)| serde_json::from_str(text).map_err(|e| e.to_string()))
+                    .collect::<Result<Vec<Value>, String>>()?;
+                let file = destination.join(format!("xapi-statements-{}-{:03}.json", stamp, index + 1));
+                let json = serde_json::to_string_pretty(&statements).map_err(|e| e.to_string())?;
+                fs::write(&file, json).map_err(|e| {
+                    error!("Failed to write {}: {}", file.display(), e);
+                    format!("Failed to write {}: {}", file.display(), e)
+                })?;
+                files.push(file.to_string_lossy().into_owned());
+            }
+
+            // Marked only once every batch is on disk
+            let tx = db.transaction().map_err(|e| e.to_string())?;
+            {
+                let mut stmt = tx
+                    .prepare_cached("UPDATE XapiStatements SET exported_at = CURRENT_TIMESTAMP WHERE id = ?1")
+                    .map_err(|e| e.to_string())?;
+                for (id, _) in &rows {
+                    stmt.execute(params![id]).map_err(|e| e.to_string())?;
+                }
+            }
+            tx.commit().map_err(|e| {
+                error!("Failed to mark exported xAPI statements: {}", e);
+                e.to_string()
+            })?;
+            info!("Exported {} xAPI statements in {} files", rows.len(), files.len());
+            Ok(XapiExportSummary { files, statement_count: rows.len() })
+        })
+        .await
+}
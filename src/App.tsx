@@ -10,6 +10,7 @@ import ActivationPage from "./pages/ActivationPage";
 import LoginPage from "./pages/LoginPage";
 import NewActivationForm from "./pages/NewActivationForm";
 import ReactivationForm from "./pages/ReactivationForm";
+import RecoveryForm from "./pages/RecoveryForm";
 import HomePage from "./pages/HomePage";
 import ContentPathPage from "./pages/ContentPathPage";
 import Users from "./pages/Users";
@@ -67,6 +68,7 @@ function App() {
               <Route path="/login" element={<LoginPage />} />
               <Route path="/new-activation" element={<NewActivationForm />} />
               <Route path="/reactivation" element={<ReactivationForm />} />
+              <Route path="/recover" element={<RecoveryForm />} />
               <Route path="/home" element={<HomePage />} />
               <Route path="/content" element={<ContentPathPage />} />
               <Route path="/users" element={<Users />} />
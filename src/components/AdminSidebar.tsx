@@ -1,5 +1,6 @@
 import BrandLogo from "./BrandLogo";
 import { NavLink, useNavigate } from "react-router-dom";
+import { useAuth } from "../contexts/AuthContext";
 import { useLanguage } from "../contexts/LanguageContext";
 import ThemeToggle from "./ui/ThemeToggle";
 
@@ -13,6 +14,7 @@ const navLinkClass = ({ isActive }: { isActive: boolean }) =>
 
 function AdminSidebar() {
   const navigate = useNavigate();
+  const { logout } = useAuth();
   const { t } = useLanguage();
 
   const navItems = [
@@ -73,7 +75,10 @@ function AdminSidebar() {
         <ThemeToggle />
         <button
           type="button"
-          onClick={() => navigate("/login")}
+          onClick={() => {
+            logout();
+            navigate("/login");
+          }}
           className="flex items-center gap-2 rounded-lg px-2 py-1.5 text-sm font-medium text-[var(--color-error)] transition hover:bg-[var(--color-error)]/10"
         >
           <svg className="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
@@ -21,6 +21,7 @@ function AppHeader() {
     "/login",
     "/new-activation",
     "/reactivation",
+    "/recover",
   ];
 
   if (
@@ -48,16 +49,14 @@ function AppHeader() {
         >
           {t("settings")}
         </Link>
-        {userRole === "User" && (
-          <button
-            type="button"
-            onClick={handleLogout}
-            className="flex items-center gap-2 rounded-lg px-3 py-1.5 text-sm font-semibold text-[var(--color-error)] transition hover:bg-[var(--color-error)]/10"
-          >
-            <img src={logoutIcon} alt="" className="h-4 w-4" />
-            <span>{t("logout")}</span>
-          </button>
-        )}
+        <button
+          type="button"
+          onClick={handleLogout}
+          className="flex items-center gap-2 rounded-lg px-3 py-1.5 text-sm font-semibold text-[var(--color-error)] transition hover:bg-[var(--color-error)]/10"
+        >
+          <img src={logoutIcon} alt="" className="h-4 w-4" />
+          <span>{t("logout")}</span>
+        </button>
       </div>
     </header>
   );
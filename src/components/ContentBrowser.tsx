@@ -9,6 +9,7 @@ import {
 } from "../utils/contentImages";
 import { FileEntry } from "../types";
 import FileBrowserView from "./FileBrowserView";
+import { useLanguage } from "../contexts/LanguageContext";
 
 interface ContentBrowserProps {
   currentPath: string;
@@ -28,6 +29,7 @@ const ContentBrowser: React.FC<ContentBrowserProps> = ({
   const [error, setError] = useState<string | null>(null);
   const [hasFiles, setHasFiles] = useState(false);
   const [selectedTab, setSelectedTab] = useState("Videos");
+  const { language } = useLanguage();
 
   useEffect(() => {
     const fetchEntries = async () => {
@@ -36,6 +38,7 @@ const ContentBrowser: React.FC<ContentBrowserProps> = ({
       try {
         const allItems = (await invoke("list_directories_in_path", {
           path: currentPath,
+          language,
         })) as FileEntry[];
         setHasFiles(allItems.some((entry) => !entry.isDirectory));
 
@@ -130,18 +133,9 @@ const ContentBrowser: React.FC<ContentBrowserProps> = ({
 
           filteredItems = contentMap[selectedTab] || contentMap["default"];
         } else {
-          filteredItems = allItems.sort((a, b) => {
-            const nameA = a.name.toLowerCase().replace(/\s/g, "");
-            const nameB = b.name.toLowerCase().replace(/\s/g, "");
-
-            if (nameA === "prenursery") return -1;
-            if (nameB === "prenursery") return 1;
-
-            const numA = parseInt(nameA.replace("class", ""), 10);
-            const numB = parseInt(nameB.replace("class", ""), 10);
-
-            return numA - numB;
-          });
+          // The backend sorts folders: manifest order, pinned names such as
+          // pre-nursery, then natural order so "class2" comes before "class10"
+          filteredItems = allItems;
         }
         setEntries(filteredItems);
       } catch (err: unknown) {
@@ -152,7 +146,7 @@ const ContentBrowser: React.FC<ContentBrowserProps> = ({
     };
 
     if (currentPath) fetchEntries();
-  }, [currentPath, selectedTab]);
+  }, [currentPath, selectedTab, language]);
 
   const handleItemClick = (entry: FileEntry) => {
     const itemPath = `${currentPath}/${entry.originalName || entry.name}`.replace(
@@ -160,7 +154,8 @@ const ContentBrowser: React.FC<ContentBrowserProps> = ({
       "/"
     );
 
-    if (entry.isDirectory) {
+    // HTML5 and SCORM package folders open in the viewer like files
+    if (entry.isDirectory && entry.contentType !== "interactive") {
       onNavigate(itemPath);
     } else {
       onOpenFile(itemPath);
@@ -241,7 +236,7 @@ const ContentBrowser: React.FC<ContentBrowserProps> = ({
                 className="mb-3 h-28 w-full rounded-xl object-cover"
               />
               <p className="text-center text-sm font-semibold text-[var(--color-text)]">
-                {entry.name}
+                {entry.title || entry.name}
               </p>
             </button>
           ))}
@@ -0,0 +1,67 @@
+import { User } from "../types";
+import { useLanguage } from "../contexts/LanguageContext";
+import { SurfaceCard } from "./ui/AppShell";
+import Button from "./ui/Button";
+
+export type PurgeMode = "delete" | "anonymize";
+
+interface DeletedUserListProps {
+  users: User[];
+  onRestore: (id: number) => void;
+  onPurge: (id: number, mode: PurgeMode) => void;
+}
+
+// Soft-deleted users; they keep their progress until purged
+function DeletedUserList({ users, onRestore, onPurge }: DeletedUserListProps) {
+  const { t } = useLanguage();
+
+  if (users.length === 0) {
+    return null;
+  }
+
+  return (
+    <SurfaceCard className="mt-6">
+      <h2 className="mb-4 text-xl font-semibold text-[var(--color-text)]">
+        {t("deleted_users")}
+      </h2>
+      <div className="overflow-x-auto">
+        <table className="min-w-full text-sm">
+          <thead>
+            <tr className="border-b border-[var(--color-border)] text-left text-xs uppercase tracking-wider text-[var(--color-text-secondary)]">
+              <th className="px-4 py-3">{t("name")}</th>
+              <th className="px-4 py-3">{t("role")}</th>
+              <th className="px-4 py-3">{t("deleted_at")}</th>
+              <th className="px-4 py-3">{t("actions")}</th>
+            </tr>
+          </thead>
+          <tbody>
+            {users.map((user) => (
+              <tr key={user.id} className="border-b border-[var(--color-divider)]">
+                <td className="px-4 py-4 text-[var(--color-text)]">{user.name}</td>
+                <td className="px-4 py-4 text-[var(--color-text-secondary)]">{user.role}</td>
+                <td className="px-4 py-4 text-[var(--color-text-secondary)]">{user.deleted_at}</td>
+                <td className="px-4 py-4">
+                  {user.id !== null && (
+                    <div className="flex gap-2">
+                      <Button size="sm" variant="outline" onClick={() => onRestore(user.id!)} fullWidth={false}>
+                        {t("restore_user")}
+                      </Button>
+                      <Button size="sm" variant="danger" onClick={() => onPurge(user.id!, "anonymize")} fullWidth={false}>
+                        {t("purge_keep_history")}
+                      </Button>
+                      <Button size="sm" variant="danger" onClick={() => onPurge(user.id!, "delete")} fullWidth={false}>
+                        {t("purge_with_history")}
+                      </Button>
+                    </div>
+                  )}
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      </div>
+    </SurfaceCard>
+  );
+}
+
+export default DeletedUserList;
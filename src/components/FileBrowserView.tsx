@@ -1,4 +1,6 @@
-import React from "react";
+import React, { useEffect, useState } from "react";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { FileEntry } from "../types";
 import { categoryTabs } from "../constants/theme";
 import Button from "./ui/Button";
@@ -19,6 +21,40 @@ const getFileIcon = (fileName: string) => {
   return "📁";
 };
 
+// Poster frame or first page rendered by the backend, with the emoji icon until it is ready
+const FileThumbnail: React.FC<{ path: string; name: string }> = ({ path, name }) => {
+  const [hash, setHash] = useState<string | null>(null);
+
+  useEffect(() => {
+    let active = true;
+    setHash(null);
+    const unlisten = listen<{ path: string; hash: string }>(
+      "thumbnail-ready",
+      (event) => {
+        if (active && event.payload.path === path) setHash(event.payload.hash);
+      }
+    );
+    invoke<string | null>("get_thumbnail", { path })
+      .then((ready) => {
+        if (active && ready) setHash(ready);
+      })
+      .catch(() => {});
+    return () => {
+      active = false;
+      unlisten.then((stop) => stop());
+    };
+  }, [path]);
+
+  if (!hash) return <>{getFileIcon(name)}</>;
+  return (
+    <img
+      src={convertFileSrc(hash, "thumb")}
+      alt=""
+      className="h-full w-full rounded-lg object-cover"
+    />
+  );
+};
+
 const cleanDisplayName = (name: string) => {
   let displayName = name;
   for (const ext of [".mp4", ".pdf", ".xml"]) {
@@ -103,11 +139,14 @@ const FileBrowserView: React.FC<FileBrowserViewProps> = ({
                 onKeyDown={(e) => e.key === "Enter" && openFile(entry)}
                 className="flex cursor-pointer items-center gap-4 rounded-xl border border-[var(--color-border)] bg-[var(--color-surface)] p-4 shadow-[var(--shadow-md)] transition hover:border-[var(--color-primary)]/40 hover:shadow-[var(--shadow-lg)]"
               >
-                <div className="flex h-12 w-12 shrink-0 items-center justify-center rounded-lg bg-[var(--color-primary)]/10 text-2xl">
-                  {getFileIcon(entry.name)}
+                <div className="flex h-12 w-12 shrink-0 items-center justify-center overflow-hidden rounded-lg bg-[var(--color-primary)]/10 text-2xl">
+                  <FileThumbnail
+                    path={`${currentPath}/${entry.originalName || entry.name}`.replace(/\/+/g, "/")}
+                    name={entry.name}
+                  />
                 </div>
                 <p className="flex-1 text-lg font-semibold text-[var(--color-text)]">
-                  {cleanDisplayName(entry.name)}
+                  {entry.title || cleanDisplayName(entry.name)}
                 </p>
                 <Button
                   size="sm"
@@ -0,0 +1,32 @@
+import { useLanguage } from "../contexts/LanguageContext";
+import Button from "./ui/Button";
+
+interface RecoveryCodeNoticeProps {
+  code: string;
+  message: string;
+  onDone: () => void;
+}
+
+// Shows an admin recovery code once; the backend keeps only its hash
+function RecoveryCodeNotice({ code, message, onDone }: RecoveryCodeNoticeProps) {
+  const { t } = useLanguage();
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-[var(--color-text-secondary)]">{message}</p>
+      <div className="rounded-xl border border-[var(--color-border)] bg-[var(--color-background)] p-4 text-center">
+        <div className="mb-1 text-xs font-medium uppercase text-[var(--color-text-secondary)]">
+          {t("recovery_code")}
+        </div>
+        <code className="select-all font-mono text-2xl font-bold tracking-widest text-[var(--color-text)]">
+          {code}
+        </code>
+      </div>
+      <Button type="button" size="lg" fullWidth onClick={onDone}>
+        {t("recovery_code_saved")}
+      </Button>
+    </div>
+  );
+}
+
+export default RecoveryCodeNotice;
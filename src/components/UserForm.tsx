@@ -1,5 +1,5 @@
 import React, { useState, useEffect } from "react";
-import { User } from "../types";
+import { Section, User } from "../types";
 import { useLanguage } from "../contexts/LanguageContext";
 import InputField from "./ui/InputField";
 import Button from "./ui/Button";
@@ -9,13 +9,20 @@ interface UserFormProps {
   onClose: () => void;
   onSubmit: (formData: Omit<User, "id">) => void;
   initialData?: User | null;
+  sections: Section[];
 }
 
-function UserForm({ isOpen, onClose, onSubmit, initialData }: UserFormProps) {
+const selectClassName =
+  "h-14 w-full rounded-xl border border-[var(--color-border)] bg-[var(--color-surface)] px-4 text-[var(--color-text)] focus:border-[var(--color-primary)] focus:outline-none focus:ring-2 focus:ring-[var(--color-primary)]/20";
+
+function UserForm({ isOpen, onClose, onSubmit, initialData, sections }: UserFormProps) {
   const [name, setName] = useState("");
   const [password, setPassword] = useState("");
   const [confirmPassword, setConfirmPassword] = useState("");
-  const [role, setRole] = useState("Admin");
+  const [role, setRole] = useState("student");
+  const [sectionId, setSectionId] = useState<number | null>(null);
+  const [rollNumber, setRollNumber] = useState("");
+  const [displayName, setDisplayName] = useState("");
   const { t } = useLanguage();
 
   useEffect(() => {
@@ -24,11 +31,17 @@ function UserForm({ isOpen, onClose, onSubmit, initialData }: UserFormProps) {
       setRole(initialData.role);
       setPassword("");
       setConfirmPassword("");
+      setSectionId(initialData.section_id ?? null);
+      setRollNumber(initialData.roll_number ?? "");
+      setDisplayName(initialData.display_name ?? "");
     } else {
       setName("");
       setPassword("");
       setConfirmPassword("");
-      setRole("Admin");
+      setRole("student");
+      setSectionId(null);
+      setRollNumber("");
+      setDisplayName("");
     }
   }, [initialData]);
 
@@ -38,7 +51,14 @@ function UserForm({ isOpen, onClose, onSubmit, initialData }: UserFormProps) {
       alert(t("passwords_do_not_match"));
       return;
     }
-    onSubmit({ name, role, password });
+    onSubmit({
+      name,
+      role,
+      password,
+      section_id: sectionId,
+      roll_number: rollNumber.trim() || null,
+      display_name: displayName.trim() || null,
+    });
   };
 
   if (!isOpen) return null;
@@ -92,12 +112,50 @@ function UserForm({ isOpen, onClose, onSubmit, initialData }: UserFormProps) {
               value={role}
               onChange={(e) => setRole(e.target.value)}
               required
-              className="h-14 w-full rounded-xl border border-[var(--color-border)] bg-[var(--color-surface)] px-4 text-[var(--color-text)] focus:border-[var(--color-primary)] focus:outline-none focus:ring-2 focus:ring-[var(--color-primary)]/20"
+              className={selectClassName}
             >
-              <option>{t("admin_role")}</option>
-              <option>{t("user_role")}</option>
+              <option value="admin">{t("admin_role")}</option>
+              <option value="teacher">{t("teacher_role")}</option>
+              <option value="student">{t("student_role")}</option>
+              <option value="guest">{t("guest_role")}</option>
             </select>
           </div>
+          <InputField
+            label={t("display_name")}
+            value={displayName}
+            onChange={(e) => setDisplayName(e.target.value)}
+            placeholder={name}
+          />
+          {role === "student" && (
+            <>
+              <div className="mb-4">
+                <label
+                  htmlFor="section"
+                  className="mb-2 block text-sm font-medium text-[var(--color-text-secondary)]"
+                >
+                  {t("section")}
+                </label>
+                <select
+                  id="section"
+                  value={sectionId ?? ""}
+                  onChange={(e) => setSectionId(e.target.value ? Number(e.target.value) : null)}
+                  className={selectClassName}
+                >
+                  <option value="">{t("no_section")}</option>
+                  {sections.map((section) => (
+                    <option key={section.id} value={section.id}>
+                      {section.label}
+                    </option>
+                  ))}
+                </select>
+              </div>
+              <InputField
+                label={t("roll_number")}
+                value={rollNumber}
+                onChange={(e) => setRollNumber(e.target.value)}
+              />
+            </>
+          )}
           <div className="flex justify-end gap-3 sm:col-span-2">
             <Button variant="outline" onClick={onClose} fullWidth={false}>
               {t("cancel")}
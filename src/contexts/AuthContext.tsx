@@ -1,4 +1,5 @@
 import React, { createContext, useContext, useState, ReactNode } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { User } from "../types";
 
 interface AuthContextType {
@@ -24,6 +25,7 @@ export const AuthProvider: React.FC<{ children: ReactNode }> = ({
   };
 
   const logout = () => {
+    invoke("logout").catch((error) => console.error("Failed to end session:", error));
     setUser(null);
     setUserRole(null);
   };
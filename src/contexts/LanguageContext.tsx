@@ -122,6 +122,34 @@ const translations: Record<Language, Record<string, string>> = {
     'user_role': 'User',
     'cancel': 'Cancel',
     'submit': 'Submit',
+    'viewer_load_failed': 'This content could not be opened.',
+    'teacher_role': 'Teacher',
+    'student_role': 'Student',
+    'guest_role': 'Guest (kiosk)',
+    'section': 'Class and section',
+    'no_section': 'Not assigned',
+    'roll_number': 'Roll number',
+    'display_name': 'Display name',
+    'recovery_code': 'Recovery code',
+    'recovery_code_notice': 'Write down this recovery code and keep it somewhere safe. It restores admin access without the internet, and it will not be shown again.',
+    'recovery_code_saved': 'I have written it down',
+    'use_recovery_code': 'Forgot the admin password? Use a recovery code',
+    'recover_admin_title': 'Recover admin access',
+    'new_password': 'New password',
+    'recovery_code_replaced': 'Admin access restored. The old recovery code no longer works; write down this new one.',
+    'generate_recovery_code': 'Generate new recovery code',
+    'check_database': 'Check database',
+    'repair_database': 'Repair broken references',
+    'database_ok': 'No problems found.',
+    'database_corrupt': 'The database file is damaged. Restore it from a backup.',
+    'database_orphans': 'Rows with broken references',
+    'database_repaired': 'Rows repaired',
+    'deleted_users': 'Deleted users',
+    'deleted_at': 'Deleted on',
+    'restore_user': 'Restore',
+    'purge_keep_history': 'Purge, keep anonymous progress',
+    'purge_with_history': 'Purge with progress',
+    'confirm_purge_user': 'Permanently remove this user? This cannot be undone.',
   },
   kn: {
     'welcome': 'ಸ್ವಾಗತ',
@@ -230,6 +258,34 @@ const translations: Record<Language, Record<string, string>> = {
     'user_role': 'ಬಳಕೆದಾರ',
     'cancel': 'ರದ್ದುಮಾಡು',
     'submit': 'ಸಲ್ಲಿಸಿ',
+    'viewer_load_failed': 'ಈ ವಿಷಯವನ್ನು ತೆರೆಯಲು ಸಾಧ್ಯವಾಗಲಿಲ್ಲ.',
+    'teacher_role': 'ಶಿಕ್ಷಕ',
+    'student_role': 'ವಿದ್ಯಾರ್ಥಿ',
+    'guest_role': 'ಅತಿಥಿ (ಕಿಯೋಸ್ಕ್)',
+    'section': 'ತರಗತಿ ಮತ್ತು ವಿಭಾಗ',
+    'no_section': 'ನಿಯೋಜಿಸಲಾಗಿಲ್ಲ',
+    'roll_number': 'ಹಾಜರಾತಿ ಸಂಖ್ಯೆ',
+    'display_name': 'ಪ್ರದರ್ಶನ ಹೆಸರು',
+    'recovery_code': 'ಮರುಪಡೆಯುವಿಕೆ ಕೋಡ್',
+    'recovery_code_notice': 'ಈ ಮರುಪಡೆಯುವಿಕೆ ಕೋಡ್ ಅನ್ನು ಬರೆದು ಸುರಕ್ಷಿತವಾಗಿ ಇಡಿ. ಇದು ಇಂಟರ್ನೆಟ್ ಇಲ್ಲದೆ ನಿರ್ವಾಹಕ ಪ್ರವೇಶವನ್ನು ಮರಳಿ ನೀಡುತ್ತದೆ, ಮತ್ತು ಇದನ್ನು ಮತ್ತೆ ತೋರಿಸಲಾಗುವುದಿಲ್ಲ.',
+    'recovery_code_saved': 'ನಾನು ಇದನ್ನು ಬರೆದುಕೊಂಡಿದ್ದೇನೆ',
+    'use_recovery_code': 'ನಿರ್ವಾಹಕ ಪಾಸ್‌ವರ್ಡ್ ಮರೆತಿರುವಿರಾ? ಮರುಪಡೆಯುವಿಕೆ ಕೋಡ್ ಬಳಸಿ',
+    'recover_admin_title': 'ನಿರ್ವಾಹಕ ಪ್ರವೇಶವನ್ನು ಮರುಪಡೆಯಿರಿ',
+    'new_password': 'ಹೊಸ ಪಾಸ್‌ವರ್ಡ್',
+    'recovery_code_replaced': 'ನಿರ್ವಾಹಕ ಪ್ರವೇಶವನ್ನು ಮರುಸ್ಥಾಪಿಸಲಾಗಿದೆ. ಹಳೆಯ ಮರುಪಡೆಯುವಿಕೆ ಕೋಡ್ ಇನ್ನು ಕೆಲಸ ಮಾಡುವುದಿಲ್ಲ; ಈ ಹೊಸ ಕೋಡ್ ಅನ್ನು ಬರೆದುಕೊಳ್ಳಿ.',
+    'generate_recovery_code': 'ಹೊಸ ಮರುಪಡೆಯುವಿಕೆ ಕೋಡ್ ರಚಿಸಿ',
+    'check_database': 'ಡೇಟಾಬೇಸ್ ಪರಿಶೀಲಿಸಿ',
+    'repair_database': 'ಮುರಿದ ಉಲ್ಲೇಖಗಳನ್ನು ಸರಿಪಡಿಸಿ',
+    'database_ok': 'ಯಾವುದೇ ಸಮಸ್ಯೆಗಳು ಕಂಡುಬಂದಿಲ್ಲ.',
+    'database_corrupt': 'ಡೇಟಾಬೇಸ್ ಫೈಲ್ ಹಾನಿಗೊಳಗಾಗಿದೆ. ಬ್ಯಾಕಪ್‌ನಿಂದ ಮರುಸ್ಥಾಪಿಸಿ.',
+    'database_orphans': 'ಮುರಿದ ಉಲ್ಲೇಖಗಳಿರುವ ಸಾಲುಗಳು',
+    'database_repaired': 'ಸರಿಪಡಿಸಿದ ಸಾಲುಗಳು',
+    'deleted_users': 'ಅಳಿಸಲಾದ ಬಳಕೆದಾರರು',
+    'deleted_at': 'ಅಳಿಸಿದ ದಿನಾಂಕ',
+    'restore_user': 'ಮರುಸ್ಥಾಪಿಸಿ',
+    'purge_keep_history': 'ಶಾಶ್ವತವಾಗಿ ಅಳಿಸಿ, ಅನಾಮಧೇಯ ಪ್ರಗತಿ ಉಳಿಸಿ',
+    'purge_with_history': 'ಪ್ರಗತಿಯೊಂದಿಗೆ ಶಾಶ್ವತವಾಗಿ ಅಳಿಸಿ',
+    'confirm_purge_user': 'ಈ ಬಳಕೆದಾರರನ್ನು ಶಾಶ್ವತವಾಗಿ ತೆಗೆದುಹಾಕಬೇಕೆ? ಇದನ್ನು ರದ್ದುಗೊಳಿಸಲು ಸಾಧ್ಯವಿಲ್ಲ.',
   },
   hi: {
     'welcome': 'स्वागत हे',
@@ -338,6 +394,34 @@ const translations: Record<Language, Record<string, string>> = {
     'user_role': 'उपयोगकर्ता',
     'cancel': 'रद्द करें',
     'submit': 'प्रस्तुत करें',
+    'viewer_load_failed': 'यह सामग्री खोली नहीं जा सकी।',
+    'teacher_role': 'शिक्षक',
+    'student_role': 'छात्र',
+    'guest_role': 'अतिथि (कियोस्क)',
+    'section': 'कक्षा और वर्ग',
+    'no_section': 'निर्धारित नहीं',
+    'roll_number': 'रोल नंबर',
+    'display_name': 'प्रदर्शित नाम',
+    'recovery_code': 'रिकवरी कोड',
+    'recovery_code_notice': 'यह रिकवरी कोड लिखकर सुरक्षित जगह रखें। इससे बिना इंटरनेट के व्यवस्थापक पहुँच वापस मिलती है, और यह दोबारा नहीं दिखाया जाएगा।',
+    'recovery_code_saved': 'मैंने इसे लिख लिया है',
+    'use_recovery_code': 'व्यवस्थापक पासवर्ड भूल गए? रिकवरी कोड का उपयोग करें',
+    'recover_admin_title': 'व्यवस्थापक पहुँच पुनः प्राप्त करें',
+    'new_password': 'नया पासवर्ड',
+    'recovery_code_replaced': 'व्यवस्थापक पहुँच बहाल हो गई। पुराना रिकवरी कोड अब काम नहीं करेगा; यह नया कोड लिख लें।',
+    'generate_recovery_code': 'नया रिकवरी कोड बनाएँ',
+    'check_database': 'डेटाबेस जाँचें',
+    'repair_database': 'टूटे संदर्भ ठीक करें',
+    'database_ok': 'कोई समस्या नहीं मिली।',
+    'database_corrupt': 'डेटाबेस फ़ाइल खराब है। इसे बैकअप से पुनर्स्थापित करें।',
+    'database_orphans': 'टूटे संदर्भ वाली पंक्तियाँ',
+    'database_repaired': 'ठीक की गई पंक्तियाँ',
+    'deleted_users': 'हटाए गए उपयोगकर्ता',
+    'deleted_at': 'हटाने की तिथि',
+    'restore_user': 'पुनर्स्थापित करें',
+    'purge_keep_history': 'स्थायी रूप से हटाएं, गुमनाम प्रगति रखें',
+    'purge_with_history': 'प्रगति सहित स्थायी रूप से हटाएं',
+    'confirm_purge_user': 'इस उपयोगकर्ता को स्थायी रूप से हटाएं? इसे पूर्ववत नहीं किया जा सकता।',
   },
 };
 
@@ -1,10 +1,12 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import App from "./App";
+import ViewerPage from "./pages/ViewerPage";
+import { ThemeProvider } from "./contexts/ThemeContext";
+import { LanguageProvider } from "./contexts/LanguageContext";
 import { warn, debug, info, error, attachConsole } from '@tauri-apps/plugin-log';
 
-attachConsole();
-
 function forwardConsole(
   fnName: 'log' | 'debug' | 'info' | 'warn' | 'error',
   logger: (message: string) => Promise<void>
@@ -16,14 +18,29 @@ function forwardConsole(
   };
 }
 
-forwardConsole('log', info);
-forwardConsole('debug', debug);
-forwardConsole('info', info);
-forwardConsole('warn', warn);
-forwardConsole('error', error);
+// Content viewer windows (see open_content_viewer) load the same bundle. Their capability
+// only covers their own session commands, so they do not log through the backend.
+const isViewerWindow = getCurrentWebviewWindow().label.startsWith("viewer-");
+
+if (!isViewerWindow) {
+  attachConsole();
+  forwardConsole('log', info);
+  forwardConsole('debug', debug);
+  forwardConsole('info', info);
+  forwardConsole('warn', warn);
+  forwardConsole('error', error);
+}
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {isViewerWindow ? (
+      <ThemeProvider>
+        <LanguageProvider>
+          <ViewerPage />
+        </LanguageProvider>
+      </ThemeProvider>
+    ) : (
+      <App />
+    )}
   </React.StrictMode>
 );
@@ -25,7 +25,7 @@ interface QuizData {
 function HomePage() {
   const navigate = useNavigate();
   const { user, userRole } = useAuth();
-  const { t } = useLanguage();
+  const { t, language } = useLanguage();
   const [hasActiveContentPath, setHasActiveContentPath] = useState(false);
   const [loadingContentPath, setLoadingContentPath] = useState(true);
   const [currentPath, setCurrentPath] = useState<string | null>(null);
@@ -63,10 +63,9 @@ function HomePage() {
       const extension = file.split(".").pop()?.toLowerCase();
 
       if (extension === "xml") {
-        const decryptedFilePath = await invoke("decrypt_file", { filePath: file });
         const quizDataFromRust = await invoke<{ questions: Question[] }>(
           "parse_xml_quiz",
-          { filePath: decryptedFilePath }
+          { filePath: file, language }
         );
         const questions = Array.isArray(quizDataFromRust?.questions)
           ? quizDataFromRust.questions
@@ -74,8 +73,7 @@ function HomePage() {
         setCurrentQuizData({ questions });
         setShowQuiz(true);
       } else {
-        const decryptedFilePath = await invoke("decrypt_file", { filePath: file });
-        await invoke("open_file_in_system", { path: decryptedFilePath });
+        await invoke("open_content_viewer", { path: file, userId: user?.id ?? null });
       }
     } catch (error) {
       setSnackbarMessage(`Error: ${error}`);
@@ -1,5 +1,5 @@
 import React, { useState } from "react";
-import { useNavigate } from "react-router-dom";
+import { useNavigate, Link } from "react-router-dom";
 import { invoke } from "@tauri-apps/api/core";
 import { useAuth } from "../contexts/AuthContext";
 import { useLanguage } from "../contexts/LanguageContext";
@@ -31,11 +31,11 @@ function LoginPage() {
     try {
       const loginResponse = (await invoke("perform_login", {
         credentials: { email, password } as LoginCredentials,
-      })) as { username: string; role: string };
+      })) as { id: number; username: string; role: string };
 
       if (loginResponse?.role) {
         setUser({
-          id: null,
+          id: loginResponse.id,
           name: loginResponse.username,
           role: loginResponse.role,
         });
@@ -108,6 +108,12 @@ function LoginPage() {
           >
             {loading ? "Signing in..." : t("login")}
           </Button>
+          <Link
+            to="/recover"
+            className="mt-4 block text-center text-sm font-medium text-[var(--color-primary)] hover:opacity-80"
+          >
+            {t("use_recovery_code")}
+          </Link>
         </form>
       </Card>
     </AuthLayout>
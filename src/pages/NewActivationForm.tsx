@@ -6,6 +6,7 @@ import AuthLayout from "../components/ui/AuthLayout";
 import Card from "../components/ui/Card";
 import InputField from "../components/ui/InputField";
 import Button from "../components/ui/Button";
+import RecoveryCodeNotice from "../components/RecoveryCodeNotice";
 
 interface NewActivationFormData {
   institutionName: string;
@@ -15,6 +16,11 @@ interface NewActivationFormData {
   productKey: string;
 }
 
+interface NewActivationResult {
+  message: string;
+  recovery_code: string;
+}
+
 function NewActivationForm() {
   const navigate = useNavigate();
   const { t } = useLanguage();
@@ -28,6 +34,7 @@ function NewActivationForm() {
   const [error, setError] = useState("");
   const [success, setSuccess] = useState("");
   const [loading, setLoading] = useState(false);
+  const [recoveryCode, setRecoveryCode] = useState("");
 
   const handleChange = (e: React.ChangeEvent<HTMLInputElement>) => {
     setFormData({ ...formData, [e.target.name]: e.target.value });
@@ -40,11 +47,12 @@ function NewActivationForm() {
     setLoading(true);
 
     try {
-      const result = await invoke("perform_new_activation", { formData });
+      const result = await invoke<NewActivationResult>("perform_new_activation", { formData });
 
       if (result) {
+        // Stay on this page until the admin has noted the recovery code
         setSuccess(t("application_activated_success"));
-        setTimeout(() => navigate("/login"), 2000);
+        setRecoveryCode(result.recovery_code);
       } else {
         setError(t("activation_failed"));
       }
@@ -67,72 +75,80 @@ function NewActivationForm() {
       }
     >
       <Card title={t("activation_title")}>
-        <form onSubmit={handleSubmit}>
-          <div className="grid grid-cols-1 gap-2 md:grid-cols-2">
-            <InputField
-              fieldSize="lg"
-              name="institutionName"
-              label={t("institution_name")}
-              placeholder={t("institution_name")}
-              value={formData.institutionName}
-              onChange={handleChange}
-              required
-            />
-            <InputField
-              fieldSize="lg"
-              name="headOfInstitution"
-              label={t("head_of_institution")}
-              placeholder={t("head_of_institution")}
-              value={formData.headOfInstitution}
-              onChange={handleChange}
-              required
-            />
-            <InputField
-              fieldSize="lg"
-              name="mobileNo"
-              type="tel"
-              label={t("mobile_no")}
-              placeholder={t("mobile_no")}
-              value={formData.mobileNo}
-              onChange={handleChange}
-              required
-            />
-            <InputField
-              fieldSize="lg"
-              name="serialNumber"
-              label={t("serial_number")}
-              placeholder={t("serial_number")}
-              value={formData.serialNumber}
-              onChange={handleChange}
-              required
-            />
-            <div className="md:col-span-2">
+        {recoveryCode ? (
+          <RecoveryCodeNotice
+            code={recoveryCode}
+            message={`${success} ${t("recovery_code_notice")}`}
+            onDone={() => navigate("/login")}
+          />
+        ) : (
+          <form onSubmit={handleSubmit}>
+            <div className="grid grid-cols-1 gap-2 md:grid-cols-2">
               <InputField
                 fieldSize="lg"
-                name="productKey"
-                label={t("product_key")}
-                placeholder={t("product_key")}
-                value={formData.productKey}
+                name="institutionName"
+                label={t("institution_name")}
+                placeholder={t("institution_name")}
+                value={formData.institutionName}
                 onChange={handleChange}
                 required
               />
+              <InputField
+                fieldSize="lg"
+                name="headOfInstitution"
+                label={t("head_of_institution")}
+                placeholder={t("head_of_institution")}
+                value={formData.headOfInstitution}
+                onChange={handleChange}
+                required
+              />
+              <InputField
+                fieldSize="lg"
+                name="mobileNo"
+                type="tel"
+                label={t("mobile_no")}
+                placeholder={t("mobile_no")}
+                value={formData.mobileNo}
+                onChange={handleChange}
+                required
+              />
+              <InputField
+                fieldSize="lg"
+                name="serialNumber"
+                label={t("serial_number")}
+                placeholder={t("serial_number")}
+                value={formData.serialNumber}
+                onChange={handleChange}
+                required
+              />
+              <div className="md:col-span-2">
+                <InputField
+                  fieldSize="lg"
+                  name="productKey"
+                  label={t("product_key")}
+                  placeholder={t("product_key")}
+                  value={formData.productKey}
+                  onChange={handleChange}
+                  required
+                />
+              </div>
             </div>
-          </div>
-          {(error || success) && (
-            <div
-              className={`mb-4 rounded-lg p-3 text-sm ${
-                error
-                  ? "bg-[var(--color-error)]/10 text-[var(--color-error)]"
-                  : "bg-[var(--color-success)]/10 text-[var(--color-success)]"
-              }`}
-            >
-              {error || success}
-            </div>
-          )}
-          <Button type="submit" size="lg" fullWidth loading={loading} disabled={loading} className="mt-4">
-            {t("activate")}
-          </Button>
-        </form>
+            {(error || success) && (
+              <div
+                className={`mb-4 rounded-lg p-3 text-sm ${
+                  error
+                    ? "bg-[var(--color-error)]/10 text-[var(--color-error)]"
+                    : "bg-[var(--color-success)]/10 text-[var(--color-success)]"
+                }`}
+              >
+                {error || success}
+              </div>
+            )}
+            <Button type="submit" size="lg" fullWidth loading={loading} disabled={loading} className="mt-4">
+              {t("activate")}
+            </Button>
+          </form>
+        )}
       </Card>
     </AuthLayout>
   );
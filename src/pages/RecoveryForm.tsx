@@ -0,0 +1,100 @@
+import React, { useState } from "react";
+import { useNavigate, Link } from "react-router-dom";
+import { invoke } from "@tauri-apps/api/core";
+import { useLanguage } from "../contexts/LanguageContext";
+import AuthLayout from "../components/ui/AuthLayout";
+import Card from "../components/ui/Card";
+import InputField from "../components/ui/InputField";
+import Button from "../components/ui/Button";
+import RecoveryCodeNotice from "../components/RecoveryCodeNotice";
+
+// Offline admin recovery: the code from activation makes the given user an admin
+function RecoveryForm() {
+  const navigate = useNavigate();
+  const { t } = useLanguage();
+  const [recoveryCode, setRecoveryCode] = useState("");
+  const [username, setUsername] = useState("");
+  const [newPassword, setNewPassword] = useState("");
+  const [error, setError] = useState("");
+  const [loading, setLoading] = useState(false);
+  const [newRecoveryCode, setNewRecoveryCode] = useState("");
+
+  const handleSubmit = async (e: React.FormEvent<HTMLFormElement>) => {
+    e.preventDefault();
+    setError("");
+    setLoading(true);
+
+    try {
+      const code = await invoke<string>("recover_admin_access", {
+        recoveryCode,
+        username,
+        newPassword,
+      });
+      setNewRecoveryCode(code);
+    } catch (err: unknown) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <AuthLayout
+      backLink={
+        <Link
+          to="/login"
+          className="inline-flex items-center text-sm font-semibold text-[var(--color-primary)] hover:opacity-80"
+        >
+          &larr; {t("back_button")}
+        </Link>
+      }
+    >
+      <Card title={t("recover_admin_title")} className="mx-auto">
+        {newRecoveryCode ? (
+          <RecoveryCodeNotice
+            code={newRecoveryCode}
+            message={t("recovery_code_replaced")}
+            onDone={() => navigate("/login")}
+          />
+        ) : (
+          <form onSubmit={handleSubmit}>
+            <InputField
+              fieldSize="lg"
+              label={t("recovery_code")}
+              placeholder="XXXX-XXXX-XXXX-XXXX"
+              value={recoveryCode}
+              onChange={(e) => setRecoveryCode(e.target.value)}
+              required
+              autoComplete="off"
+            />
+            <InputField
+              fieldSize="lg"
+              label={t("username")}
+              placeholder={t("username")}
+              value={username}
+              onChange={(e) => setUsername(e.target.value)}
+              required
+              autoComplete="username"
+            />
+            <InputField
+              fieldSize="lg"
+              label={t("new_password")}
+              type="password"
+              placeholder={t("new_password")}
+              value={newPassword}
+              onChange={(e) => setNewPassword(e.target.value)}
+              error={error}
+              required
+              autoComplete="new-password"
+            />
+            <Button type="submit" size="lg" fullWidth loading={loading} disabled={loading} className="mt-4">
+              {t("submit")}
+            </Button>
+          </form>
+        )}
+      </Card>
+    </AuthLayout>
+  );
+}
+
+export default RecoveryForm;
@@ -1,19 +1,62 @@
 import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useLanguage } from "../contexts/LanguageContext";
 import { useAuth } from "../contexts/AuthContext";
 import AdminSidebar from "../components/AdminSidebar";
 import AppShell, { PageContent, SurfaceCard } from "../components/ui/AppShell";
 import Button from "../components/ui/Button";
+import RecoveryCodeNotice from "../components/RecoveryCodeNotice";
+
+interface OrphanGroup {
+  table: string;
+  column: string;
+  parent: string;
+  on_delete: string;
+  count: number;
+}
+
+interface DatabaseCheckReport {
+  integrity_ok: boolean;
+  integrity_messages: string[];
+  orphans: OrphanGroup[];
+  repaired: number;
+}
 
 const SettingsPage = () => {
   const { language, setLanguage, t } = useLanguage();
   const { userRole } = useAuth();
   const [selectedLanguage, setSelectedLanguage] = useState(language);
+  const [recoveryCode, setRecoveryCode] = useState("");
+  const [recoveryError, setRecoveryError] = useState("");
+  const [dbReport, setDbReport] = useState<DatabaseCheckReport | null>(null);
+  const [dbError, setDbError] = useState("");
 
   const handleSave = () => {
     setLanguage(selectedLanguage);
   };
 
+  // Replaces the admin recovery code; the previous one stops working
+  const handleGenerateRecoveryCode = async () => {
+    setRecoveryError("");
+    try {
+      setRecoveryCode(await invoke<string>("regenerate_recovery_code"));
+    } catch (err: unknown) {
+      setRecoveryError(String(err));
+    }
+  };
+
+  // Lists broken references first; repair applies each relation's ON DELETE rule
+  const handleCheckDatabase = async (repairOrphans: boolean) => {
+    setDbError("");
+    try {
+      setDbReport(
+        await invoke<DatabaseCheckReport>("check_database_integrity", { repairOrphans })
+      );
+    } catch (err: unknown) {
+      setDbError(String(err));
+    }
+  };
+
   return (
     <AppShell sidebar={userRole === "admin" ? <AdminSidebar /> : undefined}>
       <PageContent className="flex items-center justify-center">
@@ -44,6 +87,58 @@ const SettingsPage = () => {
           <Button onClick={handleSave} fullWidth>
             {t("save")}
           </Button>
+          {userRole === "admin" && (
+            <div className="mt-6 border-t border-[var(--color-border)] pt-6">
+              {recoveryCode ? (
+                <RecoveryCodeNotice
+                  code={recoveryCode}
+                  message={t("recovery_code_notice")}
+                  onDone={() => setRecoveryCode("")}
+                />
+              ) : (
+                <Button variant="outline" onClick={handleGenerateRecoveryCode} fullWidth>
+                  {t("generate_recovery_code")}
+                </Button>
+              )}
+              {recoveryError && (
+                <p className="mt-2 text-sm text-[var(--color-error)]">{recoveryError}</p>
+              )}
+              <div className="mt-4">
+                <Button variant="outline" onClick={() => handleCheckDatabase(false)} fullWidth>
+                  {t("check_database")}
+                </Button>
+                {dbReport && (
+                  <div className="mt-2 text-sm text-[var(--color-text-secondary)]">
+                    {!dbReport.integrity_ok && (
+                      <p className="text-[var(--color-error)]">{t("database_corrupt")}</p>
+                    )}
+                    {dbReport.repaired > 0 ? (
+                      <p>
+                        {t("database_repaired")}: {dbReport.repaired}
+                      </p>
+                    ) : dbReport.orphans.length > 0 ? (
+                      <>
+                        <p>{t("database_orphans")}:</p>
+                        <ul className="mb-2 list-disc pl-5">
+                          {dbReport.orphans.map((group) => (
+                            <li key={`${group.table}.${group.column}`}>
+                              {group.table}.{group.column} → {group.parent}: {group.count}
+                            </li>
+                          ))}
+                        </ul>
+                        <Button variant="outline" onClick={() => handleCheckDatabase(true)} fullWidth>
+                          {t("repair_database")}
+                        </Button>
+                      </>
+                    ) : (
+                      dbReport.integrity_ok && <p>{t("database_ok")}</p>
+                    )}
+                  </div>
+                )}
+                {dbError && <p className="mt-2 text-sm text-[var(--color-error)]">{dbError}</p>}
+              </div>
+            </div>
+          )}
         </SurfaceCard>
       </PageContent>
     </AppShell>
@@ -1,10 +1,11 @@
 import { useState, useEffect } from "react";
 import UserList from "../components/UserList";
 import UserForm from "../components/UserForm";
+import DeletedUserList, { PurgeMode } from "../components/DeletedUserList";
 import AdminSidebar from "../components/AdminSidebar";
 import { useAuth } from "../contexts/AuthContext";
 import { invoke } from "@tauri-apps/api/core";
-import { User } from "../types";
+import { Section, User } from "../types";
 import { useLanguage } from "../contexts/LanguageContext";
 import AppShell, { PageContent } from "../components/ui/AppShell";
 import Button from "../components/ui/Button";
@@ -14,30 +15,60 @@ interface BackendUser {
   username: string;
   password?: string;
   role: string;
+  section_id?: number | null;
+  roll_number?: string | null;
+  display_name?: string | null;
+  deleted_at?: string | null;
 }
 
+const fromBackendUser = (user: BackendUser): User => ({
+  id: user.id || null,
+  name: user.username,
+  role: user.role,
+  section_id: user.section_id ?? null,
+  roll_number: user.roll_number ?? null,
+  display_name: user.display_name ?? null,
+  deleted_at: user.deleted_at ?? null,
+});
+
+// Roster fields only apply to students
+const rosterFields = (user: Omit<User, "id">) =>
+  user.role === "student"
+    ? {
+        section_id: user.section_id ?? null,
+        roll_number: user.roll_number || null,
+        display_name: user.display_name || null,
+      }
+    : { section_id: null, roll_number: null, display_name: user.display_name || null };
+
 function Users() {
   const { userRole } = useAuth();
   const { t } = useLanguage();
   const [users, setUsers] = useState<User[]>([]);
+  const [deletedUsers, setDeletedUsers] = useState<User[]>([]);
+  const [error, setError] = useState("");
   const [showModal, setShowModal] = useState(false);
   const [currentUser, setCurrentUser] = useState<User | null>(null);
+  const [sections, setSections] = useState<Section[]>([]);
+
+  const fetchUsers = async () => {
+    try {
+      const [active, deleted] = await Promise.all([
+        invoke<BackendUser[]>("get_users"),
+        invoke<BackendUser[]>("get_deleted_users"),
+      ]);
+      setUsers(active.map(fromBackendUser));
+      setDeletedUsers(deleted.map(fromBackendUser));
+    } catch (error) {
+      console.error("Failed to fetch users:", error);
+    }
+  };
 
   useEffect(() => {
-    const fetchUsers = async () => {
-      try {
-        const result = await invoke<BackendUser[]>("get_users");
-        const mappedUsers: User[] = result.map((user) => ({
-          id: user.id || null,
-          name: user.username,
-          role: user.role,
-        }));
-        setUsers(mappedUsers);
-      } catch (error) {
-        console.error("Failed to fetch users:", error);
-      }
-    };
     fetchUsers();
+    invoke<Section[]>("get_sections")
+      .then(setSections)
+      .catch((error) => console.error("Failed to fetch sections:", error));
   }, []);
 
   const handleAddUser = async (newUserFormData: Omit<User, "id">) => {
@@ -46,6 +77,7 @@ function Users() {
         username: newUserFormData.name,
         password: newUserFormData.password || "",
         role: newUserFormData.role,
+        ...rosterFields(newUserFormData),
       };
       const id = await invoke<number>("add_user", { user: userToAdd });
       setUsers((prevUsers) => [
@@ -66,6 +98,7 @@ function Users() {
           username: formData.name,
           password: formData.password || "",
           role: formData.role,
+          ...rosterFields(formData),
         };
         await invoke("update_user", { user: userToUpdate });
         setUsers((prevUsers) =>
@@ -84,11 +117,38 @@ function Users() {
   };
 
   const handleDeleteUser = async (id: number) => {
+    setError("");
     try {
       await invoke("delete_user", { id });
-      setUsers((prevUsers) => prevUsers.filter((user) => user.id !== id));
+      await fetchUsers();
     } catch (error) {
       console.error("Failed to delete user:", error);
+      setError(String(error));
+    }
+  };
+
+  const handleRestoreUser = async (id: number) => {
+    setError("");
+    try {
+      await invoke("restore_user", { id });
+      await fetchUsers();
+    } catch (error) {
+      console.error("Failed to restore user:", error);
+      setError(String(error));
+    }
+  };
+
+  const handlePurgeUser = async (id: number, mode: PurgeMode) => {
+    if (!window.confirm(t("confirm_purge_user"))) {
+      return;
+    }
+    setError("");
+    try {
+      await invoke("purge_user", { id, mode });
+      setDeletedUsers((prevUsers) => prevUsers.filter((user) => user.id !== id));
+    } catch (error) {
+      console.error("Failed to purge user:", error);
+      setError(String(error));
     }
   };
 
@@ -104,6 +164,12 @@ function Users() {
           </Button>
         </div>
 
+        {error && (
+          <div className="mb-4 rounded-lg bg-[var(--color-error)]/10 p-3 text-sm text-[var(--color-error)]">
+            {error}
+          </div>
+        )}
+
         <UserList
           users={users}
           onEdit={(id) => {
@@ -116,11 +182,18 @@ function Users() {
           onDelete={handleDeleteUser}
         />
 
+        <DeletedUserList
+          users={deletedUsers}
+          onRestore={handleRestoreUser}
+          onPurge={handlePurgeUser}
+        />
+
         <UserForm
           isOpen={showModal}
           onClose={() => { setShowModal(false); setCurrentUser(null); }}
           onSubmit={currentUser ? handleEditUser : handleAddUser}
           initialData={currentUser ? { ...currentUser, password: "" } : null}
+          sections={sections}
         />
       </PageContent>
     </AppShell>
@@ -0,0 +1,134 @@
+import React, { useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useLanguage } from "../contexts/LanguageContext";
+import LoadingOverlay from "../components/ui/LoadingOverlay";
+import { ViewerSession } from "../types";
+
+// How often playback position is written to progress while a video plays
+const POSITION_REPORT_INTERVAL_MS = 10_000;
+
+// Whether `source` is the package frame or a frame nested inside it, such as a SCO
+// launched in a frameset
+const isContentFrame = (source: MessageEventSource | null, frame: Window | null | undefined): boolean => {
+  if (!source || !frame) return false;
+  if (source === frame) return true;
+  for (let i = 0; i < frame.frames.length; i++) {
+    if (isContentFrame(source, frame.frames[i])) return true;
+  }
+  return false;
+};
+
+// Origin of pages on the stream protocol. Built by hand because URL gives custom schemes
+// such as stream:// an origin of "null".
+const streamOrigin = (url: string) => {
+  const parsed = new URL(url);
+  return `${parsed.protocol}//${parsed.host}`;
+};
+
+// Shown in viewer-* windows opened by open_content_viewer. Content is streamed from the
+// backend; opening and closing the window are recorded there.
+function ViewerPage() {
+  const { t } = useLanguage();
+  const [session, setSession] = useState<ViewerSession | null>(null);
+  const [failed, setFailed] = useState(false);
+  const lastReport = useRef(0);
+  const frameRef = useRef<HTMLIFrameElement>(null);
+
+  useEffect(() => {
+    invoke<ViewerSession>("get_viewer_session")
+      .then(setSession)
+      .catch((error) => {
+        console.error("Failed to load viewer session:", error);
+        setFailed(true);
+      });
+  }, []);
+
+  // SCORM packages post committed CMI values from their runtime API (see scorm.rs). The
+  // sandboxed frame has an opaque origin, so its messages arrive with origin "null".
+  useEffect(() => {
+    if (!session?.scorm_version) return;
+    const origins = [streamOrigin(session.url), "null"];
+    const onMessage = (event: MessageEvent) => {
+      if (!isContentFrame(event.source, frameRef.current?.contentWindow)) return;
+      if (!origins.includes(event.origin)) return;
+      const data = event.data;
+      if (!data || data.type !== "osiris-scorm") return;
+      invoke("commit_scorm_values", { values: data.values ?? {}, finished: !!data.finished }).catch((error) =>
+        console.error("Failed to store SCORM data:", error)
+      );
+    };
+    window.addEventListener("message", onMessage);
+    return () => window.removeEventListener("message", onMessage);
+  }, [session]);
+
+  const reportPosition = (media: HTMLMediaElement, force = false) => {
+    const now = Date.now();
+    if (!force && now - lastReport.current < POSITION_REPORT_INTERVAL_MS) return;
+    if (!Number.isFinite(media.duration) || media.duration <= 0) return;
+    lastReport.current = now;
+    invoke("record_viewer_position", {
+      positionSeconds: media.currentTime,
+      durationSeconds: media.duration,
+    }).catch((error) => console.error("Failed to record position:", error));
+  };
+
+  const resume = (media: HTMLMediaElement) => {
+    const position = session?.resume_position_seconds;
+    // Finished videos start over
+    if (position && position < media.duration - 1) media.currentTime = position;
+  };
+
+  if (failed) {
+    return (
+      <div className="flex h-screen items-center justify-center bg-[var(--color-background)] text-[var(--color-text)]">
+        {t("viewer_load_failed")}
+      </div>
+    );
+  }
+  if (!session) {
+    return <LoadingOverlay message={t("loading")} />;
+  }
+
+  const mediaProps = {
+    src: session.url,
+    controls: true,
+    autoPlay: true,
+    onLoadedMetadata: (e: React.SyntheticEvent<HTMLMediaElement>) => resume(e.currentTarget),
+    onTimeUpdate: (e: React.SyntheticEvent<HTMLMediaElement>) => reportPosition(e.currentTarget),
+    onPause: (e: React.SyntheticEvent<HTMLMediaElement>) => reportPosition(e.currentTarget, true),
+    onEnded: (e: React.SyntheticEvent<HTMLMediaElement>) => reportPosition(e.currentTarget, true),
+    onError: () => setFailed(true),
+  };
+
+  return (
+    <div className="flex h-screen w-screen items-center justify-center overflow-hidden bg-black">
+      {session.kind === "video" && <video className="h-full w-full" {...mediaProps} />}
+      {session.kind === "audio" && <audio className="w-2/3" {...mediaProps} />}
+      {session.kind === "image" && (
+        <img
+          className="max-h-full max-w-full object-contain"
+          src={session.url}
+          alt={session.title}
+          onError={() => setFailed(true)}
+        />
+      )}
+      {session.kind === "pdf" && (
+        <iframe className="h-full w-full border-0 bg-white" src={session.url} title={session.title} />
+      )}
+      {/* HTML5 and SCORM packages run scripts, so they get an opaque origin and no access to
+          this page or the app's commands. PDFs are left out: the webview's PDF viewer does not
+          load inside a sandboxed frame, and documents cannot script the page. */}
+      {session.kind === "html" && (
+        <iframe
+          ref={frameRef}
+          className="h-full w-full border-0 bg-white"
+          src={session.url}
+          title={session.title}
+          sandbox="allow-scripts"
+        />
+      )}
+    </div>
+  );
+}
+
+export default ViewerPage;
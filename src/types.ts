@@ -1,6 +1,13 @@
 export interface FileEntry {
     name: string;
     isDirectory: boolean;
+    title?: string | null; // Localized title resolved by the backend
+    order?: number | null; // Display position from the content manifest
+    size?: number | null; // Files only, in bytes
+    modifiedAt?: number | null; // Seconds since the Unix epoch
+    contentType?: string | null; // e.g. "video", "textbook", "quiz"
+    childCount?: number | null; // Folders only
+    encrypted?: boolean;
     originalName?: string;
 }
 
@@ -8,6 +15,53 @@ export interface FileEntry {
 export interface User {
     id: number | null; // ID can be null for new users (before saving to DB)
     name: string; // Frontend display name (maps to username in backend)
-    role: string; // e.g., "admin", "user"
+    role: string; // "admin", "teacher", "student" or "guest"
     password?: string; // Optional for frontend display, but required for backend add/update
-} 
\ No newline at end of file
+    section_id?: number | null; // Students only
+    roll_number?: string | null;
+    display_name?: string | null;
+    deleted_at?: string | null; // Set for soft-deleted users
+}
+
+// A section of a class as returned by get_sections, e.g. label "Class 8 - B"
+export interface Section {
+    id: number;
+    class_id: number;
+    class_name: string;
+    name: string;
+    label: string;
+    student_count: number;
+}
+
+// Video details returned by get_media_info, read from the container headers
+export interface MediaChapter {
+    title: string;
+    start_seconds: number;
+}
+
+export interface MediaInfo {
+    container: string; // "mp4", "mov", "webm" or "mkv"
+    duration_seconds: number | null;
+    width: number | null;
+    height: number | null;
+    video_codec: string | null; // e.g. "h264", "vp9"
+    audio_codec: string | null; // e.g. "aac", "opus"
+    chapters: MediaChapter[];
+}
+
+// One page of PDF text returned by get_pdf_page_text, for text-to-speech and screen readers
+export interface PdfPageText {
+    page: number; // 1-based
+    page_count: number;
+    text: string;
+}
+
+// Session details a viewer window loads from get_viewer_session
+export interface ViewerSession {
+    kind: "pdf" | "video" | "audio" | "image" | "html";
+    title: string;
+    url: string; // Stream URL served by the backend
+    content_item_id: number | null;
+    resume_position_seconds: number | null;
+    scorm_version: "1.2" | "2004" | null; // Set for SCORM packages
+}